@@ -1,4 +1,6 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, StdError};
+use cw_ics721_callback::UnauthorizedCallback;
+use cw_ownable::OwnershipError;
 use cw_utils::ParseReplyError;
 use thiserror::Error;
 
@@ -10,6 +12,9 @@ pub enum ContractError {
     #[error(transparent)]
     ParseReplyError(#[from] ParseReplyError),
 
+    #[error(transparent)]
+    Ownership(#[from] OwnershipError),
+
     #[error("unrecognised reply ID")]
     UnrecognisedReplyId {},
 
@@ -21,4 +26,110 @@ pub enum ContractError {
 
     #[error("Unauthorized callback. Only ICS721 can call back.")]
     UnauthorizedCallback {},
+
+    #[error("Packet sender {sender} is not a counterparty passport contract")]
+    UnauthorizedPacketSender { sender: String },
+
+    #[error("Ack of packet sent by {sender}, only packets sent by this contract are acked")]
+    UnauthorizedAckPacket { sender: String },
+
+    #[error("Token {token_id} of callback data is not part of acked packet")]
+    TokenNotInPacket { token_id: String },
+
+    #[error("Passport must be creator of voucher collection {cw721}, but creator is {creator}")]
+    NotVoucherCreator { cw721: String, creator: String },
+
+    #[error("Passport policy requires passport to be creator of collection {cw721}, but creator is {creator}")]
+    NotCollectionCreator { cw721: String, creator: String },
+
+    #[error("Collection {cw721} is not allowed for bridging")]
+    CollectionNotAllowed { cw721: String },
+
+    #[error("NFT {token_id} in {cw721} has no token uri")]
+    MissingTokenUri { cw721: String, token_id: String },
+
+    #[error("Invalid contract name. Expected {expected}, got {actual}")]
+    InvalidContractName { expected: String, actual: String },
+
+    #[error("Invalid contract version {version}: {error}")]
+    InvalidContractVersion { version: String, error: String },
+
+    #[error("Cannot downgrade contract from version {stored} to {new}")]
+    CannotDowngrade { stored: String, new: String },
+
+    #[error("Invalid token uri {uri}. Allowed schemes: {allowed_schemes}")]
+    InvalidUriScheme {
+        uri: String,
+        allowed_schemes: String,
+    },
+
+    #[error("Token uri exceeds max length of {max_length}: {uri}")]
+    UriTooLong { uri: String, max_length: usize },
+
+    #[error("Invalid timeout policy. Min seconds must not exceed max seconds")]
+    InvalidTimeoutPolicy {},
+
+    #[error("Timeout must be a timestamp")]
+    TimeoutNotTimestamp {},
+
+    #[error("Timeout must be at least {min_seconds} seconds from now")]
+    TimeoutTooShort { min_seconds: u64 },
+
+    #[error("Timeout must be at most {max_seconds} seconds from now")]
+    TimeoutTooLong { max_seconds: u64 },
+
+    #[error("Unauthorized. Only owner or pauser can pause and unpause")]
+    UnauthorizedPauser {},
+
+    #[error("{operation} is paused")]
+    Paused { operation: String },
+
+    #[error("Unpause height {height} must be after current height {current_height}")]
+    InvalidUnpauseHeight { height: u64, current_height: u64 },
+
+    #[error("Invalid royalty share {share}. Must be greater than zero and at most one")]
+    InvalidRoyaltyShare { share: Decimal },
+
+    #[error("Invalid rate limit policy. Max transfers and window must not be zero")]
+    InvalidRateLimitPolicy {},
+
+    #[error("Rate limit exceeded for {sender} on {channel_id}. Try again at {retry_at}")]
+    RateLimited {
+        sender: String,
+        channel_id: String,
+        retry_at: String,
+    },
+
+    #[error("Relayer share must not exceed 1")]
+    InvalidRelayerShare {},
+
+    #[error("Invalid fee payment. Expected {expected}")]
+    InvalidFeePayment { expected: String },
+
+    #[error("Insufficient prepaid fees of {address}. Balance {balance}, required {required}")]
+    InsufficientPrepaidFees {
+        address: String,
+        balance: String,
+        required: String,
+    },
+
+    #[error("No funds attached")]
+    NoFunds {},
+
+    #[error("Only owner of NFT {token_id} can transfer it")]
+    NotNftOwner { token_id: String },
+
+    #[error("NFT {token_id} of {cw721} is not awaiting an outgoing transfer")]
+    NoOutgoingTransfer { cw721: String, token_id: String },
+}
+
+impl From<UnauthorizedCallback> for ContractError {
+    fn from(err: UnauthorizedCallback) -> Self {
+        match err {
+            UnauthorizedCallback::Sender => ContractError::UnauthorizedCallback {},
+            UnauthorizedCallback::PacketSender { sender } => {
+                ContractError::UnauthorizedAckPacket { sender }
+            }
+        }
+    }
 }
@@ -0,0 +1,165 @@
+//! Typed events emitted by passport, each with a stable event type and attribute keys.
+//! Indexers can parse tx events back into these structs using [`PassportEvent::parse`] and [`parse_events`].
+//!
+//! Deprecated: the ad-hoc `wasm` attributes `method`, `ics721_status`, `ack_error`, `owner`, `old_token_uri` and
+//! `new_token_uri` duplicate these events. They are only kept for existing indexers and will be removed in a future version.
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Event, StdError, StdResult};
+
+/// Custom events are prefixed by wasmd, e.g. `wasm-arkite_passport_minted`.
+const WASM_EVENT_PREFIX: &str = "wasm-";
+
+macro_rules! passport_events {
+    ($(
+        $(#[$meta:meta])*
+        $name:ident = $event_type:literal {
+            $($(#[$field_meta:meta])* $field:ident),* $(,)?
+        }
+    )*) => {
+        $(
+            $(#[$meta])*
+            #[cw_serde]
+            pub struct $name {
+                $($(#[$field_meta])* pub $field: String,)*
+            }
+
+            impl $name {
+                pub const EVENT_TYPE: &'static str = $event_type;
+            }
+
+            impl From<$name> for Event {
+                fn from(event: $name) -> Self {
+                    Event::new($event_type)$(.add_attribute(stringify!($field), event.$field))*
+                }
+            }
+
+            impl TryFrom<&Event> for $name {
+                type Error = StdError;
+
+                fn try_from(event: &Event) -> StdResult<Self> {
+                    if event_type(event) != $event_type {
+                        return Err(StdError::generic_err(format!(
+                            "expected event {}, got {}",
+                            $event_type, event.ty
+                        )));
+                    }
+                    Ok(Self {
+                        $($field: attribute(event, stringify!($field))?,)*
+                    })
+                }
+            }
+        )*
+
+        #[cw_serde]
+        pub enum PassportEvent {
+            $($name($name),)*
+        }
+
+        impl PassportEvent {
+            /// Parses event emitted by passport, any other event type is ignored and returns `None`.
+            pub fn parse(event: &Event) -> StdResult<Option<Self>> {
+                match event_type(event) {
+                    $($event_type => Ok(Some(Self::$name($name::try_from(event)?))),)*
+                    _ => Ok(None),
+                }
+            }
+        }
+
+        impl From<PassportEvent> for Event {
+            fn from(event: PassportEvent) -> Self {
+                match event {
+                    $(PassportEvent::$name(event) => event.into(),)*
+                }
+            }
+        }
+    };
+}
+
+passport_events! {
+    /// Passport NFT minted on home chain.
+    PassportMinted = "arkite_passport_minted" {
+        cw721,
+        token_id,
+        owner,
+    }
+    /// NFT forwarded to ics721 for an interchain transfer.
+    TransferInitiated = "arkite_transfer_initiated" {
+        cw721,
+        token_id,
+        sender,
+        receiver,
+        channel_id,
+    }
+    /// Transfer succeeded, NFT is escrowed on source chain.
+    TransferAcked = "arkite_transfer_acked" {
+        cw721,
+        token_id,
+        sender,
+    }
+    /// Transfer failed, NFT is returned to sender.
+    TransferFailed = "arkite_transfer_failed" {
+        cw721,
+        token_id,
+        sender,
+        error,
+    }
+    /// POAP minted for receiver of a transfer on target chain.
+    PoapMinted = "arkite_poap_minted" {
+        poap,
+        token_id,
+        receiver,
+    }
+    /// Bridge fee of an outgoing transfer collected, amounts are in denom of fee.
+    FeeCollected = "arkite_fee_collected" {
+        payer,
+        channel_id,
+        fee,
+        treasury_amount,
+        relayer_amount,
+    }
+    /// Relayer incentives of an outgoing transfer released to relayer pool address, on successful ack.
+    IncentiveReleased = "arkite_incentive_released" {
+        cw721,
+        token_id,
+        relayer_pool,
+        amount,
+    }
+    /// Relayer incentive of a payer refunded, on failed ack or timeout.
+    IncentiveRefunded = "arkite_incentive_refunded" {
+        cw721,
+        token_id,
+        payer,
+        amount,
+    }
+    /// Token uri of an NFT updated, on source chain (ack) or target chain (receive).
+    MetadataUpdated = "arkite_metadata_updated" {
+        cw721,
+        token_id,
+        old_token_uri,
+        new_token_uri,
+    }
+}
+
+/// Parses all passport events, in order of occurrence. Events of other types are skipped.
+pub fn parse_events(events: &[Event]) -> StdResult<Vec<PassportEvent>> {
+    events
+        .iter()
+        .filter_map(|event| PassportEvent::parse(event).transpose())
+        .collect()
+}
+
+fn event_type(event: &Event) -> &str {
+    event
+        .ty
+        .strip_prefix(WASM_EVENT_PREFIX)
+        .unwrap_or(&event.ty)
+}
+
+fn attribute(event: &Event, key: &str) -> StdResult<String> {
+    event
+        .attributes
+        .iter()
+        .find(|attribute| attribute.key == key)
+        .map(|attribute| attribute.value.clone())
+        .ok_or_else(|| StdError::generic_err(format!("no {key} in event {}", event.ty)))
+}
@@ -3,48 +3,76 @@ use std::vec;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    from_json, to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Reply,
-    Response, StdResult, Storage, SubMsg, WasmMsg,
+    coins, from_json, to_json_binary, to_json_string, Addr, BankMsg, Binary, ChannelResponse, Coin,
+    Decimal, Deps, DepsMut, Empty, Env, Event, IbcQuery, IbcTimeout, MessageInfo, Order,
+    QueryRequest, Reply, Response, StdError, StdResult, Storage, SubMsg, WasmMsg,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw721_base::{
-    msg::{NftExtensionMsg, NftInfoResponse, NumTokensResponse},
+    msg::{
+        AllNftInfoResponse, CollectionExtensionMsg, CollectionInfoMsg, NftExtensionMsg,
+        NftInfoResponse, NumTokensResponse, OwnerOfResponse, TokensResponse,
+    },
     receiver::Cw721ReceiveMsg,
     state::Trait,
     DefaultOptionalCollectionExtensionMsg, DefaultOptionalNftExtension,
-    DefaultOptionalNftExtensionMsg,
+    DefaultOptionalNftExtensionMsg, Ownership,
+};
+use cw_ics721_callback::{
+    handle_ack_callback, handle_receive_callback, outgoing_proxy_or_ics721, set_memo,
+    Ics721CallbackHandler,
 };
 use cw_utils::parse_reply_instantiate_data;
-use ics721::msg::InstantiateMsg as Ics721InstantiateMsg;
+use ics721::{msg::InstantiateMsg as Ics721InstantiateMsg, ClassId};
 use ics721_types::{
     ibc_types::IbcOutgoingMsg,
-    types::{
-        Ics721AckCallbackMsg, Ics721Callbacks, Ics721Memo, Ics721ReceiveCallbackMsg, Ics721Status,
-    },
+    types::{Ics721AckCallbackMsg, Ics721Memo, Ics721ReceiveCallbackMsg, Ics721Status},
 };
 
 use crate::{
     error::ContractError,
-    msg::{CallbackData, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg},
+    events::{
+        FeeCollected, IncentiveRefunded, IncentiveReleased, MetadataUpdated, PassportMinted,
+        PoapMinted, TransferAcked, TransferFailed, TransferInitiated,
+    },
+    msg::{
+        CallbackData, ChannelFee, CollectedFeesResponse, CollectionPolicyResponse,
+        ContractInfoResponse, ExecuteMsg, FeesResponse, HomeLocationResponse, InstantiateMsg,
+        LinkedContracts, MigrateMsg, PacketIncentive, PauseStatusResponse, QueryMsg,
+        RateLimitQuotaResponse, RemoteLocationResponse,
+    },
     state::{
-        ADDR_CW721, ADDR_ICS721, ADDR_POAP, COUNTERPARTY_CONTRACT, DEFAULT_TOKEN_URI,
-        ESCROWED_TOKEN_URI, TRANSFERRED_TOKEN_URI,
+        CollectedFees, CollectionMetadata, Config, Counters, FeeConfig, HomeMetadata,
+        MetadataPolicy, OutgoingTransfer, PauseState, PausedOperations, PoapPolicy,
+        RateLimitPolicy, RemoteLocation, RoyaltyInfo, TimeoutPolicy, TransferPoint,
+        CHANNEL_COUNTERPARTIES, CHANNEL_FEES, COLLECTED_FEES, COLLECTION_POLICIES, CONFIG,
+        COUNTERS, FEE_CONFIG, OUTGOING_CHANNELS, OUTGOING_TRANSFERS, PACKET_INCENTIVES, PAUSE,
+        POAP_RECEIVERS, PREPAID_FEES, RECENT_TRANSFERS, REMOTE_LOCATIONS, SYNCED_COLLECTIONS,
+        SYNCING_COLLECTION, VOUCHER_METADATA, VOUCHER_POLICIES,
     },
-    INSTANTIATE_CW721_REPLY_ID, INSTANTIATE_ICS721_REPLY_ID, INSTANTIATE_POAP_REPLY_ID,
-    MINT_NFT_REPLY_ID, UPDATE_NFT_REPLY_ID,
+    upgrades, INSTANTIATE_CW721_REPLY_ID, INSTANTIATE_ICS721_REPLY_ID, INSTANTIATE_POAP_REPLY_ID,
+    MINT_NFT_REPLY_ID, SYNC_COLLECTION_REPLY_ID, UPDATE_NFT_REPLY_ID,
 };
 
-const CONTRACT_NAME: &str = "crates.io:arkite-passport";
-const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub(crate) const CONTRACT_NAME: &str = "crates.io:arkite-passport";
+pub(crate) const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const ALLOWED_TOKEN_URI_SCHEMES: &[&str] = &["https", "ipfs", "ar"];
+const MAX_TOKEN_URI_LENGTH: usize = 512;
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
+) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(info.sender.as_str()))?;
+    validate_token_uri(&msg.default_token_uri)?;
+    validate_token_uri(&msg.escrowed_token_uri)?;
+    validate_token_uri(&msg.transferred_token_uri)?;
     // override and use contract address as creator
     let mut ics721_base = msg.ics721_base;
     let mut instantiate_ics721_msg: Ics721InstantiateMsg = from_json(&ics721_base.msg)?;
@@ -65,9 +93,25 @@ pub fn instantiate(
             INSTANTIATE_ICS721_REPLY_ID,
         ),
     ];
-    DEFAULT_TOKEN_URI.save(deps.storage, &msg.default_token_uri)?;
-    ESCROWED_TOKEN_URI.save(deps.storage, &msg.escrowed_token_uri)?;
-    TRANSFERRED_TOKEN_URI.save(deps.storage, &msg.transferred_token_uri)?;
+    // addresses are set in instantiate replies
+    let config = Config {
+        default_token_uri: msg.default_token_uri,
+        escrowed_token_uri: msg.escrowed_token_uri,
+        transferred_token_uri: msg.transferred_token_uri,
+        addr_cw721: None,
+        addr_poap: None,
+        addr_ics721: None,
+        counterparty_contract: None,
+        poap_policy: PoapPolicy::Always,
+        timeout_policy: TimeoutPolicy::default(),
+        rate_limit_policy: RateLimitPolicy::default(),
+        royalty_info: None,
+        collection_metadata: None,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    COUNTERS.save(deps.storage, &Counters::default())?;
+    PAUSE.save(deps.storage, &PauseState::default())?;
+    FEE_CONFIG.save(deps.storage, &FeeConfig::default())?;
     Ok(Response::default()
         .add_attribute("method", "instantiate")
         .add_attribute("addr_arkite_passport", env.contract.address.to_string())
@@ -82,72 +126,620 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Mint {} => execute_mint(deps, info.sender.to_string()),
+        ExecuteMsg::Mint {} => execute_mint(deps, env, info.sender.to_string()),
         ExecuteMsg::ReceiveNft(msg) => execute_receive_nft(deps, env, info, msg),
-        ExecuteMsg::CounterPartyContract { addr } => execute_counterparty_contract(deps, addr),
-        ExecuteMsg::Ics721AckCallback(msg) => execute_ack_callback(deps, env, info, msg),
-        ExecuteMsg::Ics721ReceiveCallback(msg) => execute_receive_callback(deps, env, info, msg),
+        ExecuteMsg::Transfer {
+            cw721,
+            token_id,
+            msg,
+        } => execute_transfer(deps, env, info, cw721, token_id, msg),
+        ExecuteMsg::PrepayFees {} => execute_prepay_fees(deps, info),
+        ExecuteMsg::WithdrawPrepaidFees { amount } => {
+            execute_withdraw_prepaid_fees(deps, info, amount)
+        }
+        ExecuteMsg::PayPacketIncentive { cw721, token_id } => {
+            execute_pay_packet_incentive(deps, info, cw721, token_id)
+        }
+        ExecuteMsg::UpdateFeeConfig {
+            treasury,
+            relayer_pool,
+            relayer_share,
+        } => execute_update_fee_config(deps, info, treasury, relayer_pool, relayer_share),
+        ExecuteMsg::SetChannelFee { channel_id, fee } => {
+            execute_set_channel_fee(deps, info, channel_id, fee)
+        }
+        ExecuteMsg::CounterPartyContract { addr } => {
+            execute_counterparty_contract(deps, info, addr)
+        }
+        ExecuteMsg::SetChannelCounterparty {
+            channel_id,
+            counterparty_contract,
+        } => execute_set_channel_counterparty(deps, info, channel_id, counterparty_contract),
+        ExecuteMsg::UpdateConfig {
+            default_token_uri,
+            escrowed_token_uri,
+            transferred_token_uri,
+            poap_policy,
+            timeout_policy,
+            rate_limit_policy,
+        } => execute_update_config(
+            deps,
+            info,
+            default_token_uri,
+            escrowed_token_uri,
+            transferred_token_uri,
+            poap_policy,
+            timeout_policy,
+            rate_limit_policy,
+        ),
+        ExecuteMsg::SetRoyaltyInfo { royalty_info } => {
+            execute_set_royalty_info(deps, info, royalty_info)
+        }
+        ExecuteMsg::SetCollectionMetadata {
+            collection_metadata,
+        } => execute_set_collection_metadata(deps, info, collection_metadata),
+        ExecuteMsg::SetCollectionPolicy { cw721, policy } => {
+            execute_set_collection_policy(deps, env, info, cw721, policy)
+        }
+        ExecuteMsg::ResyncMetadata { start_after, limit } => {
+            execute_resync_metadata(deps, info, start_after, limit)
+        }
+        ExecuteMsg::SetPauser { pauser } => execute_set_pauser(deps, info, pauser),
+        ExecuteMsg::Pause {
+            operations,
+            unpause_at_height,
+        } => execute_pause(deps, env, info, operations, unpause_at_height),
+        ExecuteMsg::Unpause {} => execute_unpause(deps, info),
+        ExecuteMsg::UpdateOwnership(action) => execute_update_ownership(deps, env, info, action),
+        ExecuteMsg::Ics721AckCallback(msg) => {
+            handle_ack_callback(&PassportCallbacks, deps, env, info, msg)
+        }
+        ExecuteMsg::Ics721ReceiveCallback(msg) => {
+            handle_receive_callback(&PassportCallbacks, deps, env, info, msg)
+        }
     }
 }
 
-fn execute_counterparty_contract(deps: DepsMut, addr: String) -> Result<Response, ContractError> {
-    COUNTERPARTY_CONTRACT.save(deps.storage, &addr)?;
+fn execute_update_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: cw_ownable::Action,
+) -> Result<Response, ContractError> {
+    let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
+    Ok(Response::default()
+        .add_attribute("method", "execute_update_ownership")
+        .add_attributes(ownership.into_attributes()))
+}
+
+fn execute_counterparty_contract(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.counterparty_contract = Some(addr.clone());
+        Ok(config)
+    })?;
     Ok(Response::default()
         .add_attribute("method", "execute_counterparty_contract")
         .add_attribute("counterparty_contract", addr))
 }
 
-fn execute_mint(deps: DepsMut, owner: String) -> Result<Response, ContractError> {
-    let cw721 = ADDR_CW721.load(deps.storage)?;
-    let sub_msg = create_mint_msg(deps, cw721, owner)?;
+fn execute_set_channel_counterparty(
+    deps: DepsMut,
+    info: MessageInfo,
+    channel_id: String,
+    counterparty_contract: Option<String>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let response = Response::default()
+        .add_attribute("method", "execute_set_channel_counterparty")
+        .add_attribute("channel_id", channel_id.clone());
+    match counterparty_contract {
+        Some(counterparty_contract) => {
+            CHANNEL_COUNTERPARTIES.save(deps.storage, &channel_id, &counterparty_contract)?;
+            Ok(response.add_attribute("counterparty_contract", counterparty_contract))
+        }
+        None => {
+            CHANNEL_COUNTERPARTIES.remove(deps.storage, &channel_id);
+            Ok(response.add_attribute("counterparty_contract", "none"))
+        }
+    }
+}
+
+fn execute_set_pauser(
+    deps: DepsMut,
+    info: MessageInfo,
+    pauser: Option<String>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let mut pause = PauseState::load(deps.storage)?;
+    pause.pauser = pauser
+        .map(|pauser| deps.api.addr_validate(&pauser))
+        .transpose()?;
+    PAUSE.save(deps.storage, &pause)?;
+    Ok(Response::default()
+        .add_attribute("method", "execute_set_pauser")
+        .add_attribute("pauser", pause.pauser.map(String::from).unwrap_or_default()))
+}
+
+/// Only owner or pauser can pause and unpause.
+fn assert_pauser(deps: Deps, sender: &Addr, pause: &PauseState) -> Result<(), ContractError> {
+    if pause.pauser.as_ref() == Some(sender) || cw_ownable::is_owner(deps.storage, sender)? {
+        return Ok(());
+    }
+    Err(ContractError::UnauthorizedPauser {})
+}
+
+fn execute_pause(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operations: PausedOperations,
+    unpause_at_height: Option<u64>,
+) -> Result<Response, ContractError> {
+    let mut pause = PauseState::load(deps.storage)?;
+    assert_pauser(deps.as_ref(), &info.sender, &pause)?;
+    if let Some(height) = unpause_at_height {
+        if height <= env.block.height {
+            return Err(ContractError::InvalidUnpauseHeight {
+                height,
+                current_height: env.block.height,
+            });
+        }
+    }
+    pause.paused = operations;
+    pause.unpause_at_height = unpause_at_height;
+    PAUSE.save(deps.storage, &pause)?;
+    Ok(Response::default()
+        .add_attribute("method", "execute_pause")
+        .add_attribute("mint", pause.paused.mint.to_string())
+        .add_attribute("transfer", pause.paused.transfer.to_string())
+        .add_attribute("poap", pause.paused.poap.to_string())
+        .add_attribute(
+            "unpause_at_height",
+            unpause_at_height
+                .map(|height| height.to_string())
+                .unwrap_or_default(),
+        ))
+}
+
+fn execute_unpause(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut pause = PauseState::load(deps.storage)?;
+    assert_pauser(deps.as_ref(), &info.sender, &pause)?;
+    pause.paused = PausedOperations::default();
+    pause.unpause_at_height = None;
+    PAUSE.save(deps.storage, &pause)?;
+    Ok(Response::default().add_attribute("method", "execute_unpause"))
+}
+
+/// Operations paused at current block height.
+fn paused_operations(deps: Deps, env: &Env) -> StdResult<PausedOperations> {
+    Ok(PauseState::load(deps.storage)?.paused_at(env.block.height))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    default_token_uri: Option<String>,
+    escrowed_token_uri: Option<String>,
+    transferred_token_uri: Option<String>,
+    poap_policy: Option<PoapPolicy>,
+    timeout_policy: Option<TimeoutPolicy>,
+    rate_limit_policy: Option<RateLimitPolicy>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    let mut response = Response::default().add_attribute("method", "execute_update_config");
+    if let Some(token_uri) = default_token_uri {
+        validate_token_uri(&token_uri)?;
+        response = response
+            .add_attribute("old_default_token_uri", config.default_token_uri)
+            .add_attribute("new_default_token_uri", token_uri.clone());
+        config.default_token_uri = token_uri;
+    }
+    if let Some(token_uri) = escrowed_token_uri {
+        validate_token_uri(&token_uri)?;
+        response = response
+            .add_attribute("old_escrowed_token_uri", config.escrowed_token_uri)
+            .add_attribute("new_escrowed_token_uri", token_uri.clone());
+        config.escrowed_token_uri = token_uri;
+    }
+    if let Some(token_uri) = transferred_token_uri {
+        validate_token_uri(&token_uri)?;
+        response = response
+            .add_attribute("old_transferred_token_uri", config.transferred_token_uri)
+            .add_attribute("new_transferred_token_uri", token_uri.clone());
+        config.transferred_token_uri = token_uri;
+    }
+    if let Some(poap_policy) = poap_policy {
+        response = response
+            .add_attribute("old_poap_policy", to_json_string(&config.poap_policy)?)
+            .add_attribute("new_poap_policy", to_json_string(&poap_policy)?);
+        config.poap_policy = poap_policy;
+    }
+    if let Some(timeout_policy) = timeout_policy {
+        if let (Some(min_seconds), Some(max_seconds)) =
+            (timeout_policy.min_seconds, timeout_policy.max_seconds)
+        {
+            if min_seconds > max_seconds {
+                return Err(ContractError::InvalidTimeoutPolicy {});
+            }
+        }
+        response = response
+            .add_attribute(
+                "old_timeout_policy",
+                to_json_string(&config.timeout_policy)?,
+            )
+            .add_attribute("new_timeout_policy", to_json_string(&timeout_policy)?);
+        config.timeout_policy = timeout_policy;
+    }
+    if let Some(rate_limit_policy) = rate_limit_policy {
+        if let Some((max_transfers, window)) = rate_limit_policy.limit() {
+            if max_transfers == 0 || window == 0 {
+                return Err(ContractError::InvalidRateLimitPolicy {});
+            }
+        }
+        response = response
+            .add_attribute(
+                "old_rate_limit_policy",
+                to_json_string(&config.rate_limit_policy)?,
+            )
+            .add_attribute("new_rate_limit_policy", to_json_string(&rate_limit_policy)?);
+        config.rate_limit_policy = rate_limit_policy;
+    }
+    CONFIG.save(deps.storage, &config)?;
+    Ok(response)
+}
+
+fn execute_set_royalty_info(
+    deps: DepsMut,
+    info: MessageInfo,
+    royalty_info: Option<RoyaltyInfo>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    let response = Response::default().add_attribute("method", "execute_set_royalty_info");
+    let response = match royalty_info {
+        Some(royalty_info) => {
+            if royalty_info.share.is_zero() || royalty_info.share > Decimal::one() {
+                return Err(ContractError::InvalidRoyaltyShare {
+                    share: royalty_info.share,
+                });
+            }
+            deps.api.addr_validate(&royalty_info.payment_address)?;
+            let response = response
+                .add_attribute("payment_address", royalty_info.payment_address.clone())
+                .add_attribute("share", royalty_info.share.to_string());
+            config.royalty_info = Some(royalty_info);
+            response
+        }
+        None => {
+            config.royalty_info = None;
+            response.add_attribute("royalty_info", "none")
+        }
+    };
+    CONFIG.save(deps.storage, &config)?;
+    Ok(response)
+}
+
+fn execute_set_collection_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    collection_metadata: Option<CollectionMetadata>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    let response = Response::default().add_attribute("method", "execute_set_collection_metadata");
+    let response = match collection_metadata {
+        Some(collection_metadata) => {
+            for uri in [
+                &collection_metadata.image,
+                &collection_metadata.external_link,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                validate_token_uri(uri)?;
+            }
+            let response = response
+                .add_attribute(
+                    "description",
+                    collection_metadata.description.clone().unwrap_or_default(),
+                )
+                .add_attribute(
+                    "image",
+                    collection_metadata.image.clone().unwrap_or_default(),
+                )
+                .add_attribute(
+                    "external_link",
+                    collection_metadata
+                        .external_link
+                        .clone()
+                        .unwrap_or_default(),
+                );
+            config.collection_metadata = Some(collection_metadata);
+            response
+        }
+        None => {
+            config.collection_metadata = None;
+            response.add_attribute("collection_metadata", "none")
+        }
+    };
+    CONFIG.save(deps.storage, &config)?;
+    Ok(response)
+}
+
+/// Passport policy updates NFTs of the collection, so passport must be its cw721 creator.
+fn execute_set_collection_policy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw721: String,
+    policy: Option<MetadataPolicy>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let cw721 = deps.api.addr_validate(&cw721)?;
+    let res = Response::default()
+        .add_attribute("method", "execute_set_collection_policy")
+        .add_attribute("cw721", cw721.to_string());
+    match policy {
+        Some(policy) => {
+            if policy == MetadataPolicy::Passport {
+                let creator = query_creator(deps.as_ref(), cw721.as_str())?;
+                if creator.as_ref() != Some(&env.contract.address) {
+                    return Err(ContractError::NotCollectionCreator {
+                        cw721: cw721.to_string(),
+                        creator: creator
+                            .map(|owner| owner.to_string())
+                            .unwrap_or("none".to_string()),
+                    });
+                }
+            }
+            COLLECTION_POLICIES.save(deps.storage, cw721.as_str(), &policy)?;
+            Ok(res.add_attribute("policy", to_json_string(&policy)?))
+        }
+        None => {
+            COLLECTION_POLICIES.remove(deps.storage, cw721.as_str());
+            Ok(res.add_attribute("policy", "none"))
+        }
+    }
+}
+
+/// Policy applied on transfer of NFTs in collection, None if collection is not allowed:
+/// - passport collection always uses passport policy
+/// - allowed collections use policy set by owner
+/// - voucher collections use policy of their home collection, received in callback data
+/// - voucher collections received before policies were introduced use passport policy
+fn collection_policy(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    cw721: &str,
+) -> StdResult<Option<MetadataPolicy>> {
+    if cw721 == config.cw721()?.as_str() {
+        return Ok(Some(MetadataPolicy::Passport));
+    }
+    if let Some(policy) = COLLECTION_POLICIES.may_load(deps.storage, cw721)? {
+        return Ok(Some(policy));
+    }
+    if let Some(policy) = VOUCHER_POLICIES.may_load(deps.storage, cw721)? {
+        return Ok(Some(policy));
+    }
+    // any other collection is rejected, cw721 contracts not implementing creator query included
+    let creator = query_creator(deps, cw721).ok().flatten();
+    Ok((creator.as_ref() == Some(&env.contract.address)).then_some(MetadataPolicy::Passport))
+}
+
+/// Rewrites traits and image of passports on home chain, using current config.
+/// Escrowed passports keep escrowed uri, all others get default uri. Passports escrowed by pending transfers get
+/// escrowed uri too, in case transfer fails ack resets it to default uri.
+fn execute_resync_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let config = CONFIG.load(deps.storage)?;
+    let cw721 = config.cw721()?;
+    let ics721 = config.ics721()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    let tokens: TokensResponse = deps.querier.query_wasm_smart(
+        cw721.clone(),
+        &cw721_base::msg::QueryMsg::<
+            DefaultOptionalNftExtensionMsg,
+            DefaultOptionalCollectionExtensionMsg,
+            Empty,
+        >::AllTokens {
+            start_after: start_after.clone(),
+            limit: Some(limit),
+        },
+    )?;
+
+    let mut messages = vec![];
+    let mut num_escrowed = 0;
+    for token_id in tokens.tokens.iter() {
+        let all_nft_info: AllNftInfoResponse<DefaultOptionalNftExtension> =
+            deps.querier.query_wasm_smart(
+                cw721.clone(),
+                &cw721_base::msg::QueryMsg::<
+                    DefaultOptionalNftExtensionMsg,
+                    DefaultOptionalCollectionExtensionMsg,
+                    Empty,
+                >::AllNftInfo {
+                    token_id: token_id.clone(),
+                    include_expired: None,
+                },
+            )?;
+        // passport is escrowed by ics721, while it is transferred to another chain
+        let token_uri = if all_nft_info.access.owner == ics721.as_str() {
+            num_escrowed += 1;
+            config.escrowed_token_uri.clone()
+        } else {
+            config.default_token_uri.clone()
+        };
+        let extension = create_nft_extension(
+            token_uri.clone(),
+            config.default_token_uri.clone(),
+            config.escrowed_token_uri.clone(),
+            config.transferred_token_uri.clone(),
+            config.royalty_info.clone(),
+        );
+        messages.push(create_update_nft_info_wasm_msg(
+            cw721.to_string(),
+            token_id.clone(),
+            Some(token_uri),
+            extension,
+        )?);
+    }
+
+    let last_token_id = tokens.tokens.last().cloned();
+    // less tokens than limit means there are no more tokens to resync
+    let finished = (tokens.tokens.len() as u32) < limit;
+    Ok(Response::default()
+        .add_messages(messages)
+        .add_attribute("method", "execute_resync_metadata")
+        .add_attribute("start_after", start_after.unwrap_or_default())
+        .add_attribute("limit", limit.to_string())
+        .add_attribute("num_resynced", tokens.tokens.len().to_string())
+        .add_attribute("num_escrowed", num_escrowed.to_string())
+        .add_attribute("last_token_id", last_token_id.unwrap_or_default())
+        .add_attribute("finished", finished.to_string()))
+}
+
+/// Token uris must use an allowed scheme and must not exceed max length.
+fn validate_token_uri(uri: &str) -> Result<(), ContractError> {
+    if uri.len() > MAX_TOKEN_URI_LENGTH {
+        return Err(ContractError::UriTooLong {
+            uri: uri.to_string(),
+            max_length: MAX_TOKEN_URI_LENGTH,
+        });
+    }
+    let scheme_allowed = ALLOWED_TOKEN_URI_SCHEMES
+        .iter()
+        .any(|scheme| uri.starts_with(&format!("{scheme}://")));
+    if !scheme_allowed {
+        return Err(ContractError::InvalidUriScheme {
+            uri: uri.to_string(),
+            allowed_schemes: ALLOWED_TOKEN_URI_SCHEMES.join(", "),
+        });
+    }
+    Ok(())
+}
+
+/// Checks timeout of an outgoing transfer against timeout policy.
+fn validate_timeout(
+    policy: &TimeoutPolicy,
+    env: &Env,
+    timeout: &IbcTimeout,
+) -> Result<(), ContractError> {
+    if policy.min_seconds.is_none() && policy.max_seconds.is_none() {
+        return Ok(());
+    }
+    let timestamp = timeout
+        .timestamp()
+        .ok_or(ContractError::TimeoutNotTimestamp {})?;
+    if let Some(min_seconds) = policy.min_seconds {
+        if timestamp < env.block.time.plus_seconds(min_seconds) {
+            return Err(ContractError::TimeoutTooShort { min_seconds });
+        }
+    }
+    if let Some(max_seconds) = policy.max_seconds {
+        if timestamp > env.block.time.plus_seconds(max_seconds) {
+            return Err(ContractError::TimeoutTooLong { max_seconds });
+        }
+    }
+    Ok(())
+}
+
+/// Records an outgoing transfer of sender on channel, unless sender has exceeded rate limit within window.
+fn check_rate_limit(
+    storage: &mut dyn Storage,
+    env: &Env,
+    policy: &RateLimitPolicy,
+    sender: &str,
+    channel_id: &str,
+) -> Result<(), ContractError> {
+    if policy.limit().is_none() {
+        return Ok(());
+    }
+    let now = TransferPoint::from(&env.block);
+    let transfers = RECENT_TRANSFERS
+        .may_load(storage, (sender, channel_id))?
+        .unwrap_or_default();
+    let mut transfers = policy.in_window(transfers, &now);
+    if let Some(retry_at) = policy.retry_at(&transfers) {
+        let retry_at = match policy {
+            RateLimitPolicy::PerSeconds { .. } => format!("timestamp {retry_at}"),
+            _ => format!("block {retry_at}"),
+        };
+        return Err(ContractError::RateLimited {
+            sender: sender.to_string(),
+            channel_id: channel_id.to_string(),
+            retry_at,
+        });
+    }
+    transfers.push(now);
+    RECENT_TRANSFERS.save(storage, (sender, channel_id), &transfers)?;
+    Ok(())
+}
+
+fn execute_mint(deps: DepsMut, env: Env, owner: String) -> Result<Response, ContractError> {
+    if paused_operations(deps.as_ref(), &env)?.mint {
+        return Err(ContractError::Paused {
+            operation: "mint".to_string(),
+        });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let cw721 = config.cw721()?;
+    let (sub_msg, token_id) = create_mint_msg(deps.as_ref(), &config, cw721.clone(), &owner)?;
+    COUNTERS.update(deps.storage, |mut counters| -> StdResult<_> {
+        counters.passports_minted += 1;
+        Ok(counters)
+    })?;
     Ok(Response::default()
         .add_attribute("method", "execute_mint")
+        .add_event(PassportMinted {
+            cw721: cw721.to_string(),
+            token_id,
+            owner,
+        })
         .add_submessage(sub_msg))
 }
 
-fn create_mint_msg(deps: DepsMut, cw721: Addr, owner: String) -> Result<SubMsg, ContractError> {
+pub(crate) fn query_num_tokens(deps: Deps, cw721: Addr) -> StdResult<u64> {
     let num_tokens: NumTokensResponse = deps.querier.query_wasm_smart(
-        cw721.clone(),
+        cw721,
         &cw721_base::msg::QueryMsg::<
             DefaultOptionalNftExtensionMsg,
             DefaultOptionalCollectionExtensionMsg,
             Empty,
         >::NumTokens {},
     )?;
+    Ok(num_tokens.count)
+}
 
-    let default_token_uri = DEFAULT_TOKEN_URI.load(deps.storage)?;
-    let escrowed_token_uri = ESCROWED_TOKEN_URI.load(deps.storage)?;
-    let transferred_token_uri = TRANSFERRED_TOKEN_URI.load(deps.storage)?;
-    let trait_token_uri = Trait {
-        display_type: None,
-        trait_type: "token_uri".to_string(),
-        value: default_token_uri.clone(),
-    };
-    let trait_default_uri = Trait {
-        display_type: None,
-        trait_type: "default_uri".to_string(),
-        value: default_token_uri.clone(),
-    };
-    let trait_escrowed_uri = Trait {
-        display_type: None,
-        trait_type: "escrowed_uri".to_string(),
-        value: escrowed_token_uri.clone(),
-    };
-    let trait_transferred_uri = Trait {
-        display_type: None,
-        trait_type: "transferred_uri".to_string(),
-        value: transferred_token_uri.clone(),
-    };
-    let extension = Some(NftExtensionMsg {
-        image: Some(default_token_uri.clone()),
-        attributes: Some(vec![
-            trait_token_uri,
-            trait_default_uri,
-            trait_escrowed_uri,
-            trait_transferred_uri,
-        ]),
-        ..Default::default()
-    });
+/// Mint sub message and token id of new NFT.
+fn create_mint_msg(
+    deps: Deps,
+    config: &Config,
+    cw721: Addr,
+    owner: &str,
+) -> Result<(SubMsg, String), ContractError> {
+    let token_id = query_num_tokens(deps, cw721.clone())?.to_string();
+
+    let default_token_uri = config.default_token_uri.clone();
+    let extension = create_nft_extension(
+        default_token_uri.clone(),
+        default_token_uri.clone(),
+        config.escrowed_token_uri.clone(),
+        config.transferred_token_uri.clone(),
+        config.royalty_info.clone(),
+    );
     let mint_msg = WasmMsg::Execute {
         contract_addr: cw721.to_string(),
         msg: to_json_binary(&cw721_base::msg::ExecuteMsg::<
@@ -155,15 +747,23 @@ fn create_mint_msg(deps: DepsMut, cw721: Addr, owner: String) -> Result<SubMsg,
             DefaultOptionalCollectionExtensionMsg,
             Empty,
         >::Mint {
-            token_id: num_tokens.count.to_string(),
-            owner,
+            token_id: token_id.clone(),
+            owner: owner.to_string(),
             token_uri: Some(default_token_uri.clone()),
             extension,
         })?,
         funds: vec![],
     };
     let sub_msg = SubMsg::reply_on_success(mint_msg, MINT_NFT_REPLY_ID); // revert TX if it fails
-    Ok(sub_msg)
+    Ok((sub_msg, token_id))
+}
+
+/// How bridge fee of an outgoing transfer is paid.
+enum FeePayment {
+    /// funds attached to `Transfer`
+    Funds(Vec<Coin>),
+    /// prepaid balance of NFT owner, since `send_nft` can't carry funds
+    Prepaid,
 }
 
 fn execute_receive_nft(
@@ -172,20 +772,113 @@ fn execute_receive_nft(
     info: MessageInfo,
     msg: Cw721ReceiveMsg,
 ) -> Result<Response, ContractError> {
-    let ics721 = ADDR_ICS721.load(deps.storage)?;
-    // query whether there is an outgoing proxy defined by ics721
-    let outgoing_proxy_or_ics721 = match deps
-        .querier
-        .query_wasm_smart(ics721.clone(), &ics721::msg::QueryMsg::OutgoingProxy {})?
-    {
-        Some(outgoing_proxy) => outgoing_proxy,
-        None => ics721,
-    };
-    let mut ibc_msg: IbcOutgoingMsg = from_json(&msg.msg)?;
-    let memo = create_memo(deps.storage, env, msg.sender, msg.token_id.clone())?;
-    ibc_msg.memo = Some(Binary::to_base64(&to_json_binary(&memo)?));
+    let ibc_msg: IbcOutgoingMsg = from_json(&msg.msg)?;
+    forward_nft(
+        deps,
+        env,
+        "execute_receive_nft",
+        info.sender,
+        msg.sender,
+        msg.token_id,
+        ibc_msg,
+        FeePayment::Prepaid,
+    )
+}
+
+fn execute_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw721: String,
+    token_id: String,
+    ibc_msg: IbcOutgoingMsg,
+) -> Result<Response, ContractError> {
+    let cw721 = deps.api.addr_validate(&cw721)?;
+    // this contract is approved for the NFT, so make sure only its owner can transfer it
+    let owner: OwnerOfResponse = deps.querier.query_wasm_smart(
+        cw721.clone(),
+        &cw721_base::msg::QueryMsg::<
+            DefaultOptionalNftExtensionMsg,
+            DefaultOptionalCollectionExtensionMsg,
+            Empty,
+        >::OwnerOf {
+            token_id: token_id.clone(),
+            include_expired: None,
+        },
+    )?;
+    if owner.owner != info.sender.as_str() {
+        return Err(ContractError::NotNftOwner { token_id });
+    }
+    forward_nft(
+        deps,
+        env,
+        "execute_transfer",
+        cw721,
+        info.sender.to_string(),
+        token_id,
+        ibc_msg,
+        FeePayment::Funds(info.funds),
+    )
+}
+
+/// Collects bridge fee and forwards NFT to ics721 or its outgoing proxy, with callbacks attached as memo.
+#[allow(clippy::too_many_arguments)]
+fn forward_nft(
+    deps: DepsMut,
+    env: Env,
+    method: &str,
+    cw721: Addr,
+    sender: String,
+    token_id: String,
+    mut ibc_msg: IbcOutgoingMsg,
+    fee_payment: FeePayment,
+) -> Result<Response, ContractError> {
+    // paused transfers revert `send_nft`, so NFT stays with its owner
+    if paused_operations(deps.as_ref(), &env)?.transfer {
+        return Err(ContractError::Paused {
+            operation: "transfer".to_string(),
+        });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let metadata_policy = collection_policy(deps.as_ref(), &env, &config, cw721.as_str())?
+        .ok_or_else(|| ContractError::CollectionNotAllowed {
+            cw721: cw721.to_string(),
+        })?;
+    let outgoing_proxy_or_ics721 = outgoing_proxy_or_ics721(&deps.querier, config.ics721()?)?;
+    validate_timeout(&config.timeout_policy, &env, &ibc_msg.timeout)?;
+    check_rate_limit(
+        deps.storage,
+        &env,
+        &config.rate_limit_policy,
+        &sender,
+        &ibc_msg.channel_id,
+    )?;
+    let (fee_msgs, fee_collected) =
+        collect_fee(deps.storage, &ibc_msg.channel_id, &sender, fee_payment)?;
+    OUTGOING_TRANSFERS.save(
+        deps.storage,
+        (cw721.as_str(), &token_id),
+        &OutgoingTransfer {
+            sender: sender.clone(),
+            receiver: ibc_msg.receiver.clone(),
+            channel_id: ibc_msg.channel_id.clone(),
+        },
+    )?;
+    // vouchers carry metadata of their home collection on to the next chain, vouchers received before have none
+    let home_metadata = VOUCHER_METADATA
+        .may_load(deps.storage, cw721.as_str())?
+        .unwrap_or_else(|| HomeMetadata::from(&config));
+    let counterparty_contract = config.channel_counterparty(deps.storage, &ibc_msg.channel_id)?;
+    let memo = create_memo(
+        env,
+        sender.clone(),
+        token_id.clone(),
+        metadata_policy,
+        home_metadata,
+        counterparty_contract,
+    )?;
+    set_memo(&mut ibc_msg, &memo)?;
     // forward nft to ics721 or outgoing proxy
-    let cw721 = info.sender;
     let send_msg = WasmMsg::Execute {
         contract_addr: cw721.to_string(),
         msg: to_json_binary(&cw721_base::msg::ExecuteMsg::<
@@ -194,90 +887,546 @@ fn execute_receive_nft(
             Empty,
         >::SendNft {
             contract: outgoing_proxy_or_ics721.to_string(),
-            token_id: msg.token_id,
+            token_id: token_id.clone(),
             msg: to_json_binary(&ibc_msg)?,
         })?,
         funds: vec![],
     };
     Ok(Response::default()
         .add_message(send_msg)
-        .add_attribute("method", "execute_receive_nft")
-        .add_attribute("cw721", cw721)
+        .add_messages(fee_msgs)
+        .add_attribute("method", method)
+        .add_attribute("cw721", cw721.clone())
         .add_attribute("receiver", ibc_msg.receiver.clone())
-        .add_attribute("channel_id", ibc_msg.channel_id.clone()))
+        .add_attribute("channel_id", ibc_msg.channel_id.clone())
+        .add_event(TransferInitiated {
+            cw721: cw721.to_string(),
+            token_id,
+            sender,
+            receiver: ibc_msg.receiver,
+            channel_id: ibc_msg.channel_id,
+        })
+        .add_events(fee_collected.map(Event::from)))
+}
+
+/// Takes bridge fee of channel from payment, and sends it to treasury and relayer pool address.
+fn collect_fee(
+    storage: &mut dyn Storage,
+    channel_id: &str,
+    payer: &str,
+    fee_payment: FeePayment,
+) -> Result<(Vec<BankMsg>, Option<FeeCollected>), ContractError> {
+    let Some(fee) = CHANNEL_FEES.may_load(storage, channel_id)? else {
+        if let FeePayment::Funds(funds) = fee_payment {
+            if !funds.is_empty() {
+                return Err(ContractError::InvalidFeePayment {
+                    expected: "no funds".to_string(),
+                });
+            }
+        }
+        return Ok((vec![], None));
+    };
+    match fee_payment {
+        FeePayment::Funds(funds) => {
+            if funds != vec![fee.clone()] {
+                return Err(ContractError::InvalidFeePayment {
+                    expected: fee.to_string(),
+                });
+            }
+        }
+        FeePayment::Prepaid => {
+            let balance = PREPAID_FEES
+                .may_load(storage, (payer, &fee.denom))?
+                .unwrap_or_default();
+            if balance < fee.amount {
+                return Err(ContractError::InsufficientPrepaidFees {
+                    address: payer.to_string(),
+                    balance: Coin::new(balance.u128(), &fee.denom).to_string(),
+                    required: fee.to_string(),
+                });
+            }
+            PREPAID_FEES.save(storage, (payer, &fee.denom), &(balance - fee.amount))?;
+        }
+    }
+
+    let fee_config = FeeConfig::load(storage)?;
+    let (treasury_amount, relayer_amount) = fee_config.split(fee.amount);
+    let mut msgs = vec![];
+    // bank module rejects sending zero amounts
+    if !treasury_amount.is_zero() {
+        msgs.push(BankMsg::Send {
+            to_address: fee_config.treasury()?.to_string(),
+            amount: coins(treasury_amount.u128(), &fee.denom),
+        });
+    }
+    if let Some(relayer_pool) = &fee_config.relayer_pool {
+        if !relayer_amount.is_zero() {
+            msgs.push(BankMsg::Send {
+                to_address: relayer_pool.to_string(),
+                amount: coins(relayer_amount.u128(), &fee.denom),
+            });
+        }
+    }
+    COLLECTED_FEES.update(storage, &fee.denom, |collected| -> StdResult<_> {
+        let mut collected = collected.unwrap_or_default();
+        collected.treasury += treasury_amount;
+        collected.relayer_pool += relayer_amount;
+        Ok(collected)
+    })?;
+    let fee_collected = FeeCollected {
+        payer: payer.to_string(),
+        channel_id: channel_id.to_string(),
+        fee: fee.to_string(),
+        treasury_amount: treasury_amount.to_string(),
+        relayer_amount: relayer_amount.to_string(),
+    };
+    Ok((msgs, Some(fee_collected)))
+}
+
+fn execute_prepay_fees(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    if info.funds.is_empty() {
+        return Err(ContractError::NoFunds {});
+    }
+    let payer = info.sender.as_str();
+    for coin in &info.funds {
+        PREPAID_FEES.update(
+            deps.storage,
+            (payer, &coin.denom),
+            |balance| -> StdResult<_> { Ok(balance.unwrap_or_default() + coin.amount) },
+        )?;
+    }
+    let funds: Vec<String> = info.funds.iter().map(Coin::to_string).collect();
+    Ok(Response::default()
+        .add_attribute("method", "execute_prepay_fees")
+        .add_attribute("payer", payer)
+        .add_attribute("amount", funds.join(",")))
+}
+
+fn execute_withdraw_prepaid_fees(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Coin,
+) -> Result<Response, ContractError> {
+    if amount.amount.is_zero() {
+        return Err(ContractError::NoFunds {});
+    }
+    let payer = info.sender.as_str();
+    let balance = PREPAID_FEES
+        .may_load(deps.storage, (payer, &amount.denom))?
+        .unwrap_or_default();
+    if balance < amount.amount {
+        return Err(ContractError::InsufficientPrepaidFees {
+            address: payer.to_string(),
+            balance: Coin::new(balance.u128(), &amount.denom).to_string(),
+            required: amount.to_string(),
+        });
+    }
+    PREPAID_FEES.save(
+        deps.storage,
+        (payer, &amount.denom),
+        &(balance - amount.amount),
+    )?;
+    Ok(Response::default()
+        .add_message(BankMsg::Send {
+            to_address: payer.to_string(),
+            amount: vec![amount.clone()],
+        })
+        .add_attribute("method", "execute_withdraw_prepaid_fees")
+        .add_attribute("payer", payer)
+        .add_attribute("amount", amount.to_string()))
+}
+
+fn execute_pay_packet_incentive(
+    deps: DepsMut,
+    info: MessageInfo,
+    cw721: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    if info.funds.is_empty() {
+        return Err(ContractError::NoFunds {});
+    }
+    // incentives can't be released without relayer pool address
+    FeeConfig::load(deps.storage)?
+        .relayer_pool
+        .ok_or_else(|| StdError::not_found("relayer_pool"))?;
+    if !OUTGOING_TRANSFERS.has(deps.storage, (&cw721, &token_id)) {
+        return Err(ContractError::NoOutgoingTransfer { cw721, token_id });
+    }
+    let payer = info.sender.as_str();
+    PACKET_INCENTIVES.update(
+        deps.storage,
+        (&cw721, &token_id, payer),
+        |incentive| -> StdResult<_> {
+            let mut incentive = incentive.unwrap_or_default();
+            add_coins(&mut incentive, &info.funds);
+            Ok(incentive)
+        },
+    )?;
+    let funds: Vec<String> = info.funds.iter().map(Coin::to_string).collect();
+    Ok(Response::default()
+        .add_attribute("method", "execute_pay_packet_incentive")
+        .add_attribute("cw721", cw721)
+        .add_attribute("token_id", token_id)
+        .add_attribute("payer", payer)
+        .add_attribute("amount", funds.join(",")))
+}
+
+/// Adds coins to total, merging coins of same denom.
+fn add_coins(total: &mut Vec<Coin>, coins: &[Coin]) {
+    for coin in coins {
+        match total.iter_mut().find(|total| total.denom == coin.denom) {
+            Some(total) => total.amount += coin.amount,
+            None => total.push(coin.clone()),
+        }
+    }
+}
+
+/// Removes outgoing transfer and its incentives. On success, incentives are released to relayer pool address,
+/// otherwise they are refunded to their payers.
+fn settle_outgoing_transfer(
+    storage: &mut dyn Storage,
+    cw721: &str,
+    token_id: &str,
+    success: bool,
+) -> StdResult<(Vec<BankMsg>, Vec<Event>)> {
+    OUTGOING_TRANSFERS.remove(storage, (cw721, token_id));
+    let incentives = PACKET_INCENTIVES
+        .prefix((cw721, token_id))
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, Vec<Coin>)>>>()?;
+    for (payer, _) in &incentives {
+        PACKET_INCENTIVES.remove(storage, (cw721, token_id, payer));
+    }
+    if incentives.is_empty() {
+        return Ok((vec![], vec![]));
+    }
+    let to_string = |coins: &[Coin]| {
+        coins
+            .iter()
+            .map(Coin::to_string)
+            .collect::<Vec<String>>()
+            .join(",")
+    };
+    let relayer_pool = FeeConfig::load(storage)?.relayer_pool;
+    match (success, relayer_pool) {
+        (true, Some(relayer_pool)) => {
+            let mut amount = vec![];
+            for (_, incentive) in &incentives {
+                add_coins(&mut amount, incentive);
+            }
+            let event = IncentiveReleased {
+                cw721: cw721.to_string(),
+                token_id: token_id.to_string(),
+                relayer_pool: relayer_pool.to_string(),
+                amount: to_string(&amount),
+            };
+            let msg = BankMsg::Send {
+                to_address: relayer_pool.to_string(),
+                amount,
+            };
+            Ok((vec![msg], vec![event.into()]))
+        }
+        _ => Ok(incentives
+            .into_iter()
+            .map(|(payer, amount)| {
+                let event = IncentiveRefunded {
+                    cw721: cw721.to_string(),
+                    token_id: token_id.to_string(),
+                    payer: payer.clone(),
+                    amount: to_string(&amount),
+                };
+                let msg = BankMsg::Send {
+                    to_address: payer,
+                    amount,
+                };
+                (msg, Event::from(event))
+            })
+            .unzip()),
+    }
+}
+
+fn execute_update_fee_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    treasury: Option<String>,
+    relayer_pool: Option<String>,
+    relayer_share: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let mut fee_config = FeeConfig::load(deps.storage)?;
+    let mut response = Response::default().add_attribute("method", "execute_update_fee_config");
+    if let Some(treasury) = treasury {
+        let treasury = deps.api.addr_validate(&treasury)?;
+        response = response.add_attribute("treasury", treasury.to_string());
+        fee_config.treasury = Some(treasury);
+    }
+    if let Some(relayer_pool) = relayer_pool {
+        let relayer_pool = deps.api.addr_validate(&relayer_pool)?;
+        response = response.add_attribute("relayer_pool", relayer_pool.to_string());
+        fee_config.relayer_pool = Some(relayer_pool);
+    }
+    if let Some(relayer_share) = relayer_share {
+        if relayer_share > Decimal::one() {
+            return Err(ContractError::InvalidRelayerShare {});
+        }
+        response = response.add_attribute("relayer_share", relayer_share.to_string());
+        fee_config.relayer_share = relayer_share;
+    }
+    FEE_CONFIG.save(deps.storage, &fee_config)?;
+    Ok(response)
+}
+
+fn execute_set_channel_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    channel_id: String,
+    fee: Option<Coin>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let response = Response::default()
+        .add_attribute("method", "execute_set_channel_fee")
+        .add_attribute("channel_id", channel_id.clone());
+    match fee {
+        Some(fee) if !fee.amount.is_zero() => {
+            // fees can't be collected without treasury
+            FeeConfig::load(deps.storage)?.treasury()?;
+            CHANNEL_FEES.save(deps.storage, &channel_id, &fee)?;
+            Ok(response.add_attribute("fee", fee.to_string()))
+        }
+        // zero fee is the same as no fee
+        _ => {
+            CHANNEL_FEES.remove(deps.storage, &channel_id);
+            Ok(response.add_attribute("fee", "none"))
+        }
+    }
 }
 
-fn create_memo(
-    storage: &dyn Storage,
+/// Memo of an outgoing transfer, with ack callback to this contract and receive callback to counterparty contract - if set.
+pub fn create_memo(
     env: Env,
     sender: String,
     token_id: String,
+    metadata_policy: MetadataPolicy,
+    home_metadata: HomeMetadata,
+    counterparty_contract: Option<String>,
 ) -> Result<Ics721Memo, ContractError> {
-    let default_token_uri = DEFAULT_TOKEN_URI.load(storage)?;
-    let escrowed_token_uri = ESCROWED_TOKEN_URI.load(storage)?;
-    let transferred_token_uri = TRANSFERRED_TOKEN_URI.load(storage)?;
     let callback_data = CallbackData {
         sender,
         token_id,
-        default_token_uri,
-        escrowed_token_uri,
-        transferred_token_uri,
-    };
-    let mut callbacks = Ics721Callbacks {
-        ack_callback_data: Some(to_json_binary(&callback_data)?),
-        ack_callback_addr: Some(env.contract.address.to_string()),
-        receive_callback_data: None,
-        receive_callback_addr: None,
+        default_token_uri: home_metadata.default_token_uri,
+        escrowed_token_uri: home_metadata.escrowed_token_uri,
+        transferred_token_uri: home_metadata.transferred_token_uri,
+        royalty_info: home_metadata.royalty_info,
+        collection_metadata: home_metadata.collection_metadata,
+        metadata_policy,
     };
-    if let Some(counterparty_contract) = COUNTERPARTY_CONTRACT.may_load(storage)? {
-        callbacks.receive_callback_data = Some(to_json_binary(&callback_data)?);
-        callbacks.receive_callback_addr = Some(counterparty_contract); // here we need to set contract addr, since receiver is NFT receiver
+    Ok(callback_data.to_memo(env.contract.address.to_string(), counterparty_contract)?)
+}
+
+/// Passport implementation of ics721 callbacks. Sender check and decoding of callback data is done by `cw-ics721-callback`.
+pub struct PassportCallbacks;
+
+impl Ics721CallbackHandler for PassportCallbacks {
+    type CallbackData = CallbackData;
+    type Error = ContractError;
+
+    fn ics721(&self, deps: Deps) -> StdResult<Addr> {
+        CONFIG.load(deps.storage)?.ics721()
+    }
+
+    fn on_receive(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        msg: Ics721ReceiveCallbackMsg,
+        callback_data: CallbackData,
+    ) -> Result<Response, ContractError> {
+        execute_receive_callback(deps, env, msg, callback_data)
+    }
+
+    fn on_ack_success(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        msg: Ics721AckCallbackMsg,
+        callback_data: CallbackData,
+    ) -> Result<Response, ContractError> {
+        execute_ack_success(deps, msg, callback_data)
+    }
+
+    /// Also called on timeout, NFT is returned to its owner in both cases.
+    fn on_ack_failure(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        msg: Ics721AckCallbackMsg,
+        callback_data: CallbackData,
+        error: String,
+    ) -> Result<Response, ContractError> {
+        execute_ack_failure(deps, msg, callback_data, error)
     }
-    Ok(Ics721Memo {
-        callbacks: Some(callbacks),
-    })
 }
 
 fn execute_receive_callback(
     deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
+    env: Env,
     msg: Ics721ReceiveCallbackMsg,
+    callback_data: CallbackData,
 ) -> Result<Response, ContractError> {
-    // only ics721 can execute callback
-    let ics721 = ADDR_ICS721.load(deps.storage)?;
-    if info.sender != ics721 {
-        return Err(ContractError::UnauthorizedCallback {});
-    }
+    let config = CONFIG.load(deps.storage)?;
 
-    // receive callback does two things:
-    // 1. change token uri
+    // receive callback does three things:
+    // 1. change token uri or stamp voucher, depending on metadata policy
     // 2. mints a poap to the receiver
+    // 3. sets collection info of voucher collection, on first receive
 
-    // ========= 1. change token uri
-    let callback_data: CallbackData = from_json(msg.msg)?;
-    let (update_nft_info, old_token_uri, new_token_uri) = create_update_nft_info_msg(
+    // memo is set by packet sender, so callback data is only trusted when sent by counterparty passport of channel
+    let channel_id = receiving_channel(deps.as_ref(), &config, &msg)?;
+    assert_counterparty(
         deps.as_ref(),
-        msg.nft_contract,
-        callback_data.clone(),
-        false,
+        &config,
+        channel_id.as_deref(),
+        &msg.original_packet.sender,
     )?;
-    let update_sub_msg = SubMsg::reply_on_success(update_nft_info, UPDATE_NFT_REPLY_ID); // revert TX if it fails
+    for token_id in &msg.original_packet.token_ids {
+        OUTGOING_CHANNELS.remove(deps.storage, (&msg.nft_contract, &token_id.to_string()));
+    }
 
-    // ========= 2. mint poap
-    let poap = ADDR_POAP.load(deps.storage)?;
-    let sub_msgs = vec![
-        update_sub_msg,
-        create_mint_msg(deps, poap, msg.original_packet.receiver)?,
-    ];
+    // passport and allowed collections on home chain, all others are voucher collections created by ics721
+    let is_voucher = msg.nft_contract != config.cw721()?.as_str()
+        && !COLLECTION_POLICIES.has(deps.storage, &msg.nft_contract);
+    if is_voucher {
+        assert_voucher_creator(deps.as_ref(), &env, &msg.nft_contract)?;
+        // back transfer and next hop of voucher use same policy and metadata
+        VOUCHER_POLICIES.save(
+            deps.storage,
+            &msg.nft_contract,
+            &callback_data.metadata_policy,
+        )?;
+        VOUCHER_METADATA.save(
+            deps.storage,
+            &msg.nft_contract,
+            &callback_data.home_metadata(),
+        )?;
+    }
+    // passports are back on home chain, tokens of packet are received regardless of callback data
+    if msg.nft_contract == config.cw721()?.as_str() {
+        for token_id in &msg.original_packet.token_ids {
+            REMOTE_LOCATIONS.remove(deps.storage, &token_id.to_string());
+        }
+    }
+
+    // ========= 1. change token uri or stamp voucher
+    let mut sub_msgs = vec![];
+    let mut events: Vec<Event> = vec![];
+    let mut metadata_attributes = vec![];
+    match callback_data.metadata_policy {
+        MetadataPolicy::Passport => {
+            let location = match is_voucher {
+                true => PassportLocation::Voucher,
+                false => PassportLocation::Home,
+            };
+            let (update_nft_info, old_token_uri, new_token_uri) = create_update_nft_info_msg(
+                deps.as_ref(),
+                msg.nft_contract.clone(),
+                callback_data.clone(),
+                location,
+            )?;
+            sub_msgs.push(SubMsg::reply_on_success(
+                update_nft_info,
+                UPDATE_NFT_REPLY_ID,
+            )); // revert TX if it fails
+            events.push(
+                MetadataUpdated {
+                    cw721: msg.nft_contract.clone(),
+                    token_id: callback_data.token_id.clone(),
+                    old_token_uri: old_token_uri.clone(),
+                    new_token_uri: new_token_uri.clone(),
+                }
+                .into(),
+            );
+            metadata_attributes.push(("new_token_uri", new_token_uri));
+            metadata_attributes.push(("old_token_uri", old_token_uri));
+        }
+        // home collections other than passport may not be updated by this contract, so only vouchers are stamped
+        MetadataPolicy::Stamp if is_voucher => {
+            let stamp_msg = create_stamp_msg(
+                deps.as_ref(),
+                msg.nft_contract.clone(),
+                callback_data.token_id.clone(),
+                callback_data.transferred_token_uri.clone(),
+            )?;
+            sub_msgs.push(SubMsg::reply_on_success(stamp_msg, UPDATE_NFT_REPLY_ID)); // revert TX if it fails
+            metadata_attributes.push(("stamp", callback_data.transferred_token_uri.clone()));
+        }
+        _ => {}
+    }
+
+    // ========= 2. mint poap, depending on poap policy and unless paused
+    let receiver = msg.original_packet.receiver;
+    let poap_paused = paused_operations(deps.as_ref(), &env)?.poap;
+    let mint_poap = !poap_paused
+        && match config.poap_policy {
+            PoapPolicy::Always => true,
+            PoapPolicy::OncePerReceiver => !POAP_RECEIVERS.has(deps.storage, &receiver),
+            PoapPolicy::Disabled => false,
+        };
+    if mint_poap {
+        let poap = config.poap()?;
+        let (mint_msg, token_id) =
+            create_mint_msg(deps.as_ref(), &config, poap.clone(), &receiver)?;
+        sub_msgs.push(mint_msg);
+        events.push(
+            PoapMinted {
+                poap: poap.to_string(),
+                token_id,
+                receiver: receiver.clone(),
+            }
+            .into(),
+        );
+        POAP_RECEIVERS.save(deps.storage, &receiver, &Empty {})?;
+        COUNTERS.update(deps.storage, |mut counters| -> StdResult<_> {
+            counters.poaps_minted += 1;
+            Ok(counters)
+        })?;
+    }
+
+    // ========= 3. sync collection info, passport collection on home chain is never touched.
+    // Collection info is the one of passport collection, so vouchers of other collections are not synced.
+    let sync_collection = match callback_data.collection_metadata.clone() {
+        Some(collection_metadata)
+            if is_voucher
+                && callback_data.metadata_policy == MetadataPolicy::Passport
+                && !SYNCED_COLLECTIONS.has(deps.storage, &msg.nft_contract) =>
+        {
+            // marked as synced in reply on success only, so a failed sync is retried on next receive
+            SYNCING_COLLECTION.save(deps.storage, &msg.nft_contract)?;
+            let update_collection_info =
+                create_update_collection_info_msg(msg.nft_contract.clone(), collection_metadata)?;
+            // invalid collection info must not fail the transfer
+            sub_msgs.push(SubMsg::reply_always(
+                update_collection_info,
+                SYNC_COLLECTION_REPLY_ID,
+            ));
+            true
+        }
+        _ => false,
+    };
 
     Ok(Response::default()
         .add_attribute("method", "execute_receive_callback")
+        .add_attribute("mint_poap", mint_poap.to_string())
+        .add_attribute("poap_paused", poap_paused.to_string())
+        .add_attribute("sync_collection", sync_collection.to_string())
+        .add_attribute(
+            "metadata_policy",
+            to_json_string(&callback_data.metadata_policy)?,
+        )
         .add_attribute("token_id", callback_data.token_id)
         .add_attribute("sender", callback_data.sender)
         .add_submessages(sub_msgs)
-        .add_attribute("new_token_uri", new_token_uri)
-        .add_attribute("old_token_uri", old_token_uri.clone())
+        .add_events(events)
+        .add_attributes(metadata_attributes)
         .add_attribute("default_token_uri", callback_data.default_token_uri.clone())
         .add_attribute(
             "escrowed_token_uri",
@@ -289,19 +1438,33 @@ fn execute_receive_callback(
         ))
 }
 
+/// Whether NFT exists, vouchers burned by ics721 on back transfer don't.
+fn nft_exists(deps: Deps, cw721: &str, token_id: &str) -> bool {
+    deps.querier
+        .query_wasm_smart::<OwnerOfResponse>(
+            cw721,
+            &cw721_base::msg::QueryMsg::<
+                DefaultOptionalNftExtensionMsg,
+                DefaultOptionalCollectionExtensionMsg,
+                Empty,
+            >::OwnerOf {
+                token_id: token_id.to_string(),
+                include_expired: None,
+            },
+        )
+        .is_ok()
+}
+
 /// Updates NftInfo with new token uri on both, source (ack) and target (receive) chain.
-/// This is executed as a message (not sub message) allowing global TX to succeed and not to roll back, for 2 reasons:
-/// - back transfer/on ack: NFT is burned and may error and this is fine
-/// - on initial transfer: ics721 is creator of voucher collection on target chain. So this contract cant update NFT Info.
-///
-/// In future releases of ics721 this may change, allowing to pass creator of voucher collection to ics721.
+/// - on ack: executed as a message (not sub message), only for escrowed NFTs. Vouchers burned on back transfer are skipped.
+/// - on receive: executed as sub message reverting the TX on failure. This contract is cw721 creator of voucher collections
+///   (injected into ics721 on instantiate, and asserted by `assert_voucher_creator`), so vouchers can always be updated.
 fn create_update_nft_info_msg(
     deps: Deps,
     cw721: String,
     callback_data: CallbackData,
-    use_escrowed_uri: bool,
+    location: PassportLocation,
 ) -> Result<(WasmMsg, String, String), ContractError> {
-    // check if token uri is unchanged ( holds default token uri)
     let nft_info: NftInfoResponse<DefaultOptionalNftExtension> = deps.querier.query_wasm_smart(
         cw721.clone(),
         &cw721_base::msg::QueryMsg::<
@@ -312,37 +1475,37 @@ fn create_update_nft_info_msg(
             token_id: callback_data.token_id.clone(),
         },
     )?;
-    // currently ics721 does not store onchain metadata, so source for URIs are:
-    // - forward transfer: URIs in callback
-    // - back transfer: URIs in nft extension/onchain metadata
+    // currently ics721 does not store onchain metadata, so source for URIs and royalty are:
+    // - forward transfer: URIs and royalty in callback
+    // - back transfer: URIs and royalty in nft extension/onchain metadata
+    let attributes = nft_info
+        .extension
+        .and_then(|extension| extension.attributes);
+    // royalty is kept on every update, passports minted before royalties were introduced have none
+    let royalty_info = attributes
+        .as_deref()
+        .and_then(royalty_info_from_traits)
+        .or(callback_data.royalty_info.clone());
     let (default_token_uri, escrowed_token_uri, transferred_token_uri) =
-        if let Some(extension) = nft_info.extension {
-            if let Some(attributes) = extension.attributes {
-                let default_token_uri = attributes
-                    .clone()
-                    .into_iter()
-                    .find(|attribute| attribute.trait_type == "default_uri")
-                    .map(|a| a.value)
-                    .unwrap_or(callback_data.default_token_uri.clone());
-                let escrowed_token_uri = attributes
-                    .clone()
-                    .into_iter()
-                    .find(|attribute| attribute.trait_type == "escrowed_uri")
-                    .map(|a| a.value)
-                    .unwrap_or(callback_data.escrowed_token_uri.clone());
-                let transferred_token_uri = attributes
-                    .into_iter()
-                    .find(|attribute| attribute.trait_type == "transferred_uri")
-                    .map(|a| a.value)
-                    .unwrap_or(callback_data.transferred_token_uri.clone());
-                (default_token_uri, escrowed_token_uri, transferred_token_uri)
-            } else {
-                (
-                    callback_data.default_token_uri.clone(),
-                    callback_data.escrowed_token_uri.clone(),
-                    callback_data.transferred_token_uri.clone(),
-                )
-            }
+        if let Some(attributes) = attributes {
+            let default_token_uri = attributes
+                .clone()
+                .into_iter()
+                .find(|attribute| attribute.trait_type == "default_uri")
+                .map(|a| a.value)
+                .unwrap_or(callback_data.default_token_uri.clone());
+            let escrowed_token_uri = attributes
+                .clone()
+                .into_iter()
+                .find(|attribute| attribute.trait_type == "escrowed_uri")
+                .map(|a| a.value)
+                .unwrap_or(callback_data.escrowed_token_uri.clone());
+            let transferred_token_uri = attributes
+                .into_iter()
+                .find(|attribute| attribute.trait_type == "transferred_uri")
+                .map(|a| a.value)
+                .unwrap_or(callback_data.transferred_token_uri.clone());
+            (default_token_uri, escrowed_token_uri, transferred_token_uri)
         } else {
             (
                 callback_data.default_token_uri.clone(),
@@ -350,78 +1513,319 @@ fn create_update_nft_info_msg(
                 callback_data.transferred_token_uri.clone(),
             )
         };
-    let current_token_uri = nft_info.token_uri.unwrap(); // safe to unwrap, since it is set in mint
-    let new_token_uri = if current_token_uri == default_token_uri {
-        if use_escrowed_uri {
-            escrowed_token_uri.clone()
-        } else {
-            transferred_token_uri.clone()
-        }
-    } else {
-        default_token_uri.clone()
-    };
+    // token uri is set on mint, but vouchers are minted by ics721 and may have none
+    let current_token_uri = nft_info
+        .token_uri
+        .ok_or_else(|| ContractError::MissingTokenUri {
+            cw721: cw721.clone(),
+            token_id: callback_data.token_id.clone(),
+        })?;
+    let new_token_uri = next_token_uri(
+        location,
+        &default_token_uri,
+        &escrowed_token_uri,
+        &transferred_token_uri,
+    );
+    let extension = create_nft_extension(
+        new_token_uri.clone(),
+        default_token_uri,
+        escrowed_token_uri,
+        transferred_token_uri,
+        royalty_info,
+    );
+    // - set new token uri
+    let update_nft_info = create_update_nft_info_wasm_msg(
+        cw721,
+        callback_data.token_id.clone(),
+        Some(new_token_uri.clone()),
+        extension,
+    )?;
+    Ok((update_nft_info, current_token_uri, new_token_uri))
+}
+
+/// Where a passport is after a transfer, on any hop between home chain and other chains.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PassportLocation {
+    /// escrowed by ics721 on source chain, on ack
+    Escrowed,
+    /// voucher on target chain, on receive
+    Voucher,
+    /// back on home chain, on receive
+    Home,
+}
+
+/// Next token uri of a passport only depends on its location, so it is independent of the path taken:
+/// escrowed on source chain, transferred as voucher on target chain and default on home chain.
+fn next_token_uri(
+    location: PassportLocation,
+    default_token_uri: &str,
+    escrowed_token_uri: &str,
+    transferred_token_uri: &str,
+) -> String {
+    match location {
+        PassportLocation::Escrowed => escrowed_token_uri,
+        PassportLocation::Voucher => transferred_token_uri,
+        PassportLocation::Home => default_token_uri,
+    }
+    .to_string()
+}
+
+/// Onchain metadata of a passport: token uri is used as image, and all passport uris and royalty are stored as traits.
+fn create_nft_extension(
+    token_uri: String,
+    default_token_uri: String,
+    escrowed_token_uri: String,
+    transferred_token_uri: String,
+    royalty_info: Option<RoyaltyInfo>,
+) -> DefaultOptionalNftExtensionMsg {
     let trait_token_uri = Trait {
         display_type: None,
         trait_type: "token_uri".to_string(),
-        value: new_token_uri.clone(),
+        value: token_uri.clone(),
     };
     let trait_default_uri = Trait {
         display_type: None,
         trait_type: "default_uri".to_string(),
-        value: default_token_uri.clone(),
+        value: default_token_uri,
     };
     let trait_escrowed_uri = Trait {
         display_type: None,
         trait_type: "escrowed_uri".to_string(),
-        value: escrowed_token_uri.clone(),
+        value: escrowed_token_uri,
     };
     let trait_transferred_uri = Trait {
         display_type: None,
         trait_type: "transferred_uri".to_string(),
-        value: transferred_token_uri.clone(),
+        value: transferred_token_uri,
     };
-    let extension = Some(NftExtensionMsg {
-        image: Some(new_token_uri.clone()),
-        attributes: Some(vec![
-            trait_token_uri,
-            trait_default_uri,
-            trait_escrowed_uri,
-            trait_transferred_uri,
-        ]),
+    let mut attributes = vec![
+        trait_token_uri,
+        trait_default_uri,
+        trait_escrowed_uri,
+        trait_transferred_uri,
+    ];
+    if let Some(royalty_info) = royalty_info {
+        attributes.push(Trait {
+            display_type: None,
+            trait_type: "royalty_payment_address".to_string(),
+            value: royalty_info.payment_address,
+        });
+        attributes.push(Trait {
+            display_type: None,
+            trait_type: "royalty_share".to_string(),
+            value: royalty_info.share.to_string(),
+        });
+    }
+    Some(NftExtensionMsg {
+        image: Some(token_uri),
+        attributes: Some(attributes),
         ..Default::default()
-    });
-    // - set new token uri
-    let update_nft_info: WasmMsg = WasmMsg::Execute {
+    })
+}
+
+/// Royalty stored in traits, none if any royalty trait is missing or invalid.
+pub(crate) fn royalty_info_from_traits(attributes: &[Trait]) -> Option<RoyaltyInfo> {
+    let value = |trait_type: &str| {
+        attributes
+            .iter()
+            .find(|attribute| attribute.trait_type == trait_type)
+            .map(|attribute| attribute.value.clone())
+    };
+    Some(RoyaltyInfo {
+        payment_address: value("royalty_payment_address")?,
+        share: value("royalty_share")?.parse().ok()?,
+    })
+}
+
+fn create_update_nft_info_wasm_msg(
+    cw721: String,
+    token_id: String,
+    token_uri: Option<String>,
+    extension: DefaultOptionalNftExtensionMsg,
+) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
         contract_addr: cw721,
         msg: to_json_binary(&cw721_base::msg::ExecuteMsg::<
             DefaultOptionalNftExtensionMsg,
             DefaultOptionalCollectionExtensionMsg,
             Empty,
         >::UpdateNftInfo {
-            token_id: callback_data.token_id.clone(),
-            token_uri: Some(new_token_uri.clone()),
+            token_id,
+            token_uri,
             extension,
         })?,
         funds: vec![],
+    })
+}
+
+/// Stamps a voucher by setting `stamp` trait, token uri, image and other traits are kept.
+fn create_stamp_msg(
+    deps: Deps,
+    cw721: String,
+    token_id: String,
+    stamp: String,
+) -> StdResult<WasmMsg> {
+    let nft_info: NftInfoResponse<DefaultOptionalNftExtension> = deps.querier.query_wasm_smart(
+        cw721.clone(),
+        &cw721_base::msg::QueryMsg::<
+            DefaultOptionalNftExtensionMsg,
+            DefaultOptionalCollectionExtensionMsg,
+            Empty,
+        >::NftInfo {
+            token_id: token_id.clone(),
+        },
+    )?;
+    let (image, attributes) = nft_info
+        .extension
+        .map(|extension| (extension.image, extension.attributes))
+        .unwrap_or_default();
+    let mut attributes: Vec<Trait> = attributes
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|attribute| attribute.trait_type != "stamp")
+        .collect();
+    attributes.push(Trait {
+        display_type: None,
+        trait_type: "stamp".to_string(),
+        value: stamp,
+    });
+    let extension = Some(NftExtensionMsg {
+        image,
+        attributes: Some(attributes),
+        ..Default::default()
+    });
+    create_update_nft_info_wasm_msg(cw721, token_id, nft_info.token_uri, extension)
+}
+
+/// cw721 creator of a collection, None if creator ownership has been renounced.
+fn query_creator(deps: Deps, cw721: &str) -> StdResult<Option<Addr>> {
+    let creator: Ownership<Addr> = deps.querier.query_wasm_smart(
+        cw721,
+        &cw721_base::msg::QueryMsg::<
+            DefaultOptionalNftExtensionMsg,
+            DefaultOptionalCollectionExtensionMsg,
+            Empty,
+        >::GetCreatorOwnership {},
+    )?;
+    Ok(creator.owner)
+}
+
+/// Passport must be cw721 creator of voucher collections, otherwise vouchers and their collection info can't be updated.
+/// Fails the receive callback, and hence the transfer, instead of silently leaving vouchers un-updated.
+fn assert_voucher_creator(deps: Deps, env: &Env, cw721: &str) -> Result<(), ContractError> {
+    let creator = query_creator(deps, cw721)?;
+    if creator.as_ref() != Some(&env.contract.address) {
+        return Err(ContractError::NotVoucherCreator {
+            cw721: cw721.to_string(),
+            creator: creator
+                .map(|owner| owner.to_string())
+                .unwrap_or("none".to_string()),
+        });
+    }
+    Ok(())
+}
+
+/// Channel a packet has been received on. Forward transfers are received on channel of voucher class id,
+/// back transfers on channel NFT has been transferred out on. None for NFTs transferred out before channels were recorded.
+fn receiving_channel(
+    deps: Deps,
+    config: &Config,
+    msg: &Ics721ReceiveCallbackMsg,
+) -> StdResult<Option<String>> {
+    if let Some((channel_id, home_class_id)) = voucher_channel(deps, config, &msg.nft_contract)? {
+        if home_class_id == msg.original_packet.class_id.to_string() {
+            return Ok(Some(channel_id));
+        }
+    }
+    let Some(token_id) = msg.original_packet.token_ids.first() else {
+        return Ok(None);
     };
-    Ok((update_nft_info, current_token_uri, new_token_uri))
+    OUTGOING_CHANNELS.may_load(deps.storage, (&msg.nft_contract, &token_id.to_string()))
 }
 
-fn execute_ack_callback(
-    deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    msg: Ics721AckCallbackMsg,
-) -> Result<Response, ContractError> {
-    // only ics721 can execute callback
-    let ics721 = ADDR_ICS721.load(deps.storage)?;
-    if info.sender != ics721 {
-        return Err(ContractError::UnauthorizedCallback {});
+/// Packet sender must be counterparty passport of receiving channel, or of config for channels without counterparty.
+/// Anyone may send NFTs to ics721 with a memo calling back this contract, forged callback data would otherwise
+/// overwrite policy and metadata of voucher collections. Counterparties of other channels are not trusted either.
+fn assert_counterparty(
+    deps: Deps,
+    config: &Config,
+    channel_id: Option<&str>,
+    sender: &str,
+) -> Result<(), ContractError> {
+    let channel_counterparty = match channel_id {
+        Some(channel_id) => CHANNEL_COUNTERPARTIES.may_load(deps.storage, channel_id)?,
+        None => None,
+    };
+    let counterparty = channel_counterparty.or(config.counterparty_contract.clone());
+    if counterparty.as_deref() != Some(sender) {
+        return Err(ContractError::UnauthorizedPacketSender {
+            sender: sender.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Sets description, image and external link of a voucher collection, other collection info is kept.
+fn create_update_collection_info_msg(
+    cw721: String,
+    collection_metadata: CollectionMetadata,
+) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: cw721,
+        msg: to_json_binary(&cw721_base::msg::ExecuteMsg::<
+            DefaultOptionalNftExtensionMsg,
+            DefaultOptionalCollectionExtensionMsg,
+            Empty,
+        >::UpdateCollectionInfo {
+            collection_info: CollectionInfoMsg {
+                name: None,
+                symbol: None,
+                extension: Some(CollectionExtensionMsg {
+                    description: collection_metadata.description,
+                    image: collection_metadata.image,
+                    external_link: collection_metadata.external_link,
+                    ..Default::default()
+                }),
+            },
+        })?,
+        funds: vec![],
+    })
+}
+
+/// Token of callback data must be part of acked packet, so an ack never moves or settles NFTs of other packets.
+fn assert_packet_token(
+    msg: &Ics721AckCallbackMsg,
+    callback_data: &CallbackData,
+) -> Result<(), ContractError> {
+    if !msg
+        .original_packet
+        .token_ids
+        .iter()
+        .any(|token_id| token_id.to_string() == callback_data.token_id)
+    {
+        return Err(ContractError::TokenNotInPacket {
+            token_id: callback_data.token_id.clone(),
+        });
     }
+    Ok(())
+}
 
-    let callback_data: CallbackData = from_json(&msg.msg)?;
+/// Settles outgoing transfer and adds attributes common to ack success and failure.
+fn ack_response(
+    storage: &mut dyn Storage,
+    msg: &Ics721AckCallbackMsg,
+    callback_data: &CallbackData,
+) -> StdResult<Response> {
+    let (incentive_msgs, incentive_events) = settle_outgoing_transfer(
+        storage,
+        &msg.nft_contract,
+        &callback_data.token_id,
+        msg.status == Ics721Status::Success,
+    )?;
 
-    let res = Response::default()
+    Ok(Response::default()
+        .add_messages(incentive_msgs)
+        .add_events(incentive_events)
         .add_attribute("method", "execute_ack_callback")
         .add_attribute("default_token_uri", callback_data.default_token_uri.clone())
         .add_attribute(
@@ -433,93 +1837,422 @@ fn execute_ack_callback(
             callback_data.transferred_token_uri.clone(),
         )
         .add_attribute("token_id", callback_data.token_id.clone())
-        .add_attribute("sender", callback_data.sender.clone());
-    match msg.status {
-        Ics721Status::Success => {
-            let (update_nft_info, old_token_uri, new_token_uri) = create_update_nft_info_msg(
-                deps.as_ref(),
-                msg.nft_contract,
-                callback_data.clone(),
-                true,
-            )?;
-            Ok(res
-                .add_message(update_nft_info)
-                .add_attribute("ics721_status", "ack_success")
-                .add_attribute("old_token_uri", old_token_uri)
-                .add_attribute("new_token_uri", new_token_uri))
-        }
-        Ics721Status::Failed(error) => {
-            let transfer_msg = WasmMsg::Execute {
-                contract_addr: msg.nft_contract.to_string(),
-                msg: to_json_binary(&cw721_base::msg::ExecuteMsg::<
-                    DefaultOptionalNftExtensionMsg,
-                    DefaultOptionalCollectionExtensionMsg,
-                    Empty,
-                >::TransferNft {
-                    recipient: callback_data.sender.clone(),
-                    token_id: callback_data.token_id.clone(),
-                })?,
-                funds: vec![],
-            };
+        .add_attribute("sender", callback_data.sender.clone()))
+}
 
-            Ok(res
-                .add_message(transfer_msg)
-                .add_attribute("ack_error", error)
-                .add_attribute("ics721_status", "ack_fail")
-                .add_attribute("owner", callback_data.sender)
-                .add_attribute("token_id", callback_data.token_id))
+fn execute_ack_success(
+    deps: DepsMut,
+    msg: Ics721AckCallbackMsg,
+    callback_data: CallbackData,
+) -> Result<Response, ContractError> {
+    // token uri, outgoing transfer and remote location are updated for token of callback data
+    assert_packet_token(&msg, &callback_data)?;
+    // channel is only known by outgoing transfer (not tracked for packets sent before)
+    if let Some(outgoing_transfer) =
+        OUTGOING_TRANSFERS.may_load(deps.storage, (&msg.nft_contract, &callback_data.token_id))?
+    {
+        // back transfer is only received on this channel
+        OUTGOING_CHANNELS.save(
+            deps.storage,
+            (&msg.nft_contract, &callback_data.token_id),
+            &outgoing_transfer.channel_id,
+        )?;
+        // passport escrowed on home chain
+        if msg.nft_contract == CONFIG.load(deps.storage)?.cw721()?.as_str() {
+            REMOTE_LOCATIONS.save(
+                deps.storage,
+                &callback_data.token_id,
+                &RemoteLocation {
+                    channel_id: outgoing_transfer.channel_id,
+                    class_id: msg.original_packet.class_id.to_string(),
+                    receiver: msg.original_packet.receiver.clone(),
+                },
+            )?;
         }
     }
+    let res = ack_response(deps.storage, &msg, &callback_data)?;
+    COUNTERS.update(deps.storage, |mut counters| -> StdResult<_> {
+        counters.transfers_acked += 1;
+        Ok(counters)
+    })?;
+    let res = res
+        .add_attribute("ics721_status", "ack_success")
+        .add_event(TransferAcked {
+            cw721: msg.nft_contract.clone(),
+            token_id: callback_data.token_id.clone(),
+            sender: callback_data.sender.clone(),
+        });
+    // escrowed NFT is only updated for passport policy, other policies keep metadata on source chain
+    if callback_data.metadata_policy != MetadataPolicy::Passport {
+        return Ok(res);
+    }
+    // on back transfer ics721 burns the voucher, so there is no escrowed NFT to update.
+    // Failing here would revert settlement of the outgoing transfer, since ics721 ignores callback errors.
+    if !nft_exists(deps.as_ref(), &msg.nft_contract, &callback_data.token_id) {
+        return Ok(res.add_attribute("nft_burned", "true"));
+    }
+    let (update_nft_info, old_token_uri, new_token_uri) = create_update_nft_info_msg(
+        deps.as_ref(),
+        msg.nft_contract.clone(),
+        callback_data.clone(),
+        PassportLocation::Escrowed,
+    )?;
+    Ok(res
+        .add_message(update_nft_info)
+        .add_attribute("old_token_uri", old_token_uri.clone())
+        .add_attribute("new_token_uri", new_token_uri.clone())
+        .add_event(MetadataUpdated {
+            cw721: msg.nft_contract,
+            token_id: callback_data.token_id,
+            old_token_uri,
+            new_token_uri,
+        }))
+}
+
+fn execute_ack_failure(
+    deps: DepsMut,
+    msg: Ics721AckCallbackMsg,
+    callback_data: CallbackData,
+    error: String,
+) -> Result<Response, ContractError> {
+    // returned NFT is transferred to sender of callback data, so callback data must match the packet
+    assert_packet_token(&msg, &callback_data)?;
+    let res = ack_response(deps.storage, &msg, &callback_data)?;
+    let transfer_msg = WasmMsg::Execute {
+        contract_addr: msg.nft_contract.to_string(),
+        msg: to_json_binary(&cw721_base::msg::ExecuteMsg::<
+            DefaultOptionalNftExtensionMsg,
+            DefaultOptionalCollectionExtensionMsg,
+            Empty,
+        >::TransferNft {
+            recipient: callback_data.sender.clone(),
+            token_id: callback_data.token_id.clone(),
+        })?,
+        funds: vec![],
+    };
+    COUNTERS.update(deps.storage, |mut counters| -> StdResult<_> {
+        counters.transfers_failed += 1;
+        Ok(counters)
+    })?;
+    let res = res.add_message(transfer_msg);
+
+    // passport is back home, but may have been resynced to escrowed uri while transfer was pending.
+    // Reset is skipped on error, since ics721 ignores callback errors and NFT must be returned anyway.
+    let res = match CONFIG.load(deps.storage)?.cw721()?.as_str() == msg.nft_contract {
+        true => match create_update_nft_info_msg(
+            deps.as_ref(),
+            msg.nft_contract.clone(),
+            callback_data.clone(),
+            PassportLocation::Home,
+        ) {
+            Ok((update_nft_info, old_token_uri, new_token_uri))
+                if old_token_uri != new_token_uri =>
+            {
+                res.add_message(update_nft_info).add_event(MetadataUpdated {
+                    cw721: msg.nft_contract.clone(),
+                    token_id: callback_data.token_id.clone(),
+                    old_token_uri,
+                    new_token_uri,
+                })
+            }
+            _ => res,
+        },
+        false => res,
+    };
+
+    Ok(res
+        .add_attribute("ack_error", error.clone())
+        .add_attribute("ics721_status", "ack_fail")
+        .add_attribute("owner", callback_data.sender.clone())
+        .add_attribute("token_id", callback_data.token_id.clone())
+        .add_event(TransferFailed {
+            cw721: msg.nft_contract,
+            token_id: callback_data.token_id,
+            sender: callback_data.sender,
+            error,
+        }))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let config = CONFIG.load(deps.storage)?;
     match msg {
-        QueryMsg::CounterPartyContract {} => {
-            to_json_binary(&COUNTERPARTY_CONTRACT.load(deps.storage)?)
+        QueryMsg::Config {} => to_json_binary(&config),
+        QueryMsg::ContractInfo {} => to_json_binary(&query_contract_info(deps, config)?),
+        QueryMsg::CounterPartyContract {} => to_json_binary(&config.counterparty_contract),
+        QueryMsg::ChannelCounterparty { channel_id } => {
+            to_json_binary(&config.channel_counterparty(deps.storage, &channel_id)?)
+        }
+        QueryMsg::Poap {} => to_json_binary(&config.addr_poap),
+        QueryMsg::CW721 {} => to_json_binary(&config.addr_cw721),
+        QueryMsg::ICS721 {} => to_json_binary(&config.addr_ics721),
+        QueryMsg::DefaultTokenUri {} => to_json_binary(&config.default_token_uri),
+        QueryMsg::EscrowedTokenUri {} => to_json_binary(&config.escrowed_token_uri),
+        QueryMsg::TransferredTokenUri {} => to_json_binary(&config.transferred_token_uri),
+        QueryMsg::Counters {} => to_json_binary(&COUNTERS.load(deps.storage)?),
+        QueryMsg::Ownership {} => to_json_binary(&cw_ownable::get_ownership(deps.storage)?),
+        QueryMsg::RateLimitQuota { sender, channel_id } => to_json_binary(&query_rate_limit_quota(
+            deps,
+            env,
+            config.rate_limit_policy,
+            sender,
+            channel_id,
+        )?),
+        QueryMsg::Fees {} => to_json_binary(&query_fees(deps)?),
+        QueryMsg::ChannelFee { channel_id } => {
+            to_json_binary(&CHANNEL_FEES.may_load(deps.storage, &channel_id)?)
         }
-        QueryMsg::Poap {} => to_json_binary(&ADDR_POAP.load(deps.storage)?),
-        QueryMsg::CW721 {} => to_json_binary(&ADDR_CW721.load(deps.storage)?),
-        QueryMsg::ICS721 {} => to_json_binary(&ADDR_ICS721.load(deps.storage)?),
-        QueryMsg::DefaultTokenUri {} => to_json_binary(&DEFAULT_TOKEN_URI.load(deps.storage)?),
-        QueryMsg::EscrowedTokenUri {} => to_json_binary(&ESCROWED_TOKEN_URI.load(deps.storage)?),
-        QueryMsg::TransferredTokenUri {} => {
-            to_json_binary(&TRANSFERRED_TOKEN_URI.load(deps.storage)?)
+        QueryMsg::PrepaidFees { address } => to_json_binary(&query_prepaid_fees(deps, address)?),
+        QueryMsg::CollectedFees {} => to_json_binary(&query_collected_fees(deps)?),
+        QueryMsg::OutgoingTransfer { cw721, token_id } => {
+            to_json_binary(&OUTGOING_TRANSFERS.may_load(deps.storage, (&cw721, &token_id))?)
         }
+        QueryMsg::CollectionPolicy { cw721 } => {
+            to_json_binary(&collection_policy(deps, &env, &config, &cw721)?)
+        }
+        QueryMsg::CollectionPolicies {} => to_json_binary(&query_collection_policies(deps)?),
+        QueryMsg::PacketIncentives { cw721, token_id } => {
+            to_json_binary(&query_packet_incentives(deps, cw721, token_id)?)
+        }
+        QueryMsg::RemoteLocation { token_id } => {
+            to_json_binary(&query_remote_location(deps, &config, token_id)?)
+        }
+        QueryMsg::HomeLocation { cw721, token_id } => {
+            to_json_binary(&query_home_location(deps, &config, cw721, token_id)?)
+        }
+        QueryMsg::PauseStatus {} => to_json_binary(&query_pause_status(deps, env)?),
     }
 }
 
+fn query_rate_limit_quota(
+    deps: Deps,
+    env: Env,
+    policy: RateLimitPolicy,
+    sender: String,
+    channel_id: String,
+) -> StdResult<RateLimitQuotaResponse> {
+    let transfers = RECENT_TRANSFERS
+        .may_load(deps.storage, (&sender, &channel_id))?
+        .unwrap_or_default();
+    let transfers = policy.in_window(transfers, &TransferPoint::from(&env.block));
+    let used = transfers.len() as u32;
+    let remaining = policy
+        .limit()
+        .map(|(max_transfers, _)| max_transfers.saturating_sub(used));
+    Ok(RateLimitQuotaResponse {
+        retry_at: policy.retry_at(&transfers),
+        policy,
+        used,
+        remaining,
+    })
+}
+
+fn query_fees(deps: Deps) -> StdResult<FeesResponse> {
+    let channel_fees = CHANNEL_FEES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(channel_id, fee)| ChannelFee { channel_id, fee }))
+        .collect::<StdResult<_>>()?;
+    Ok(FeesResponse {
+        fee_config: FeeConfig::load(deps.storage)?,
+        channel_fees,
+    })
+}
+
+fn query_prepaid_fees(deps: Deps, address: String) -> StdResult<Vec<Coin>> {
+    PREPAID_FEES
+        .prefix(&address)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+        .collect()
+}
+
+fn query_collected_fees(deps: Deps) -> StdResult<Vec<CollectedFeesResponse>> {
+    COLLECTED_FEES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            item.map(|(denom, collected)| CollectedFeesResponse {
+                denom,
+                treasury: collected.treasury,
+                relayer_pool: collected.relayer_pool,
+            })
+        })
+        .collect()
+}
+
+fn query_collection_policies(deps: Deps) -> StdResult<Vec<CollectionPolicyResponse>> {
+    COLLECTION_POLICIES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (cw721, policy) = item?;
+            Ok(CollectionPolicyResponse { cw721, policy })
+        })
+        .collect()
+}
+
+fn query_packet_incentives(
+    deps: Deps,
+    cw721: String,
+    token_id: String,
+) -> StdResult<Vec<PacketIncentive>> {
+    PACKET_INCENTIVES
+        .prefix((&cw721, &token_id))
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(payer, amount)| PacketIncentive { payer, amount }))
+        .collect()
+}
+
+/// Port of ics721 contract, same on all wasm chains.
+fn ics721_port(ics721: &Addr) -> String {
+    format!("wasm.{ics721}")
+}
+
+/// Voucher class id is derived from counterparty endpoint of recorded channel, like ics721 does on receive.
+fn query_remote_location(
+    deps: Deps,
+    config: &Config,
+    token_id: String,
+) -> StdResult<Option<RemoteLocationResponse>> {
+    let Some(location) = REMOTE_LOCATIONS.may_load(deps.storage, &token_id)? else {
+        return Ok(None);
+    };
+    let channel_response: ChannelResponse =
+        deps.querier.query(&QueryRequest::Ibc(IbcQuery::Channel {
+            channel_id: location.channel_id.clone(),
+            port_id: Some(ics721_port(&config.ics721()?)),
+        }))?;
+    let counterparty = channel_response
+        .channel
+        .ok_or_else(|| StdError::not_found(format!("channel {}", location.channel_id)))?
+        .counterparty_endpoint;
+    Ok(Some(RemoteLocationResponse {
+        channel_id: location.channel_id,
+        voucher_class_id: format!(
+            "{}/{}/{}",
+            counterparty.port_id, counterparty.channel_id, location.class_id
+        ),
+        counterparty_channel_id: counterparty.channel_id,
+        token_id,
+        receiver: location.receiver,
+    }))
+}
+
+/// Channel on this chain and class id on previous chain of a voucher collection, None for collections native to this chain.
+/// Voucher class id is `{port}/{channel}/{class id on previous chain}`, with port and channel on this chain.
+fn voucher_channel(
+    deps: Deps,
+    config: &Config,
+    cw721: &str,
+) -> StdResult<Option<(String, String)>> {
+    let ics721 = config.ics721()?;
+    let class_id: Option<ClassId> = deps.querier.query_wasm_smart(
+        ics721.clone(),
+        &ics721::msg::QueryMsg::ClassId {
+            contract: cw721.to_string(),
+        },
+    )?;
+    let Some(class_id) = class_id else {
+        return Ok(None);
+    };
+    // class ids of collections native to this chain have no prefix
+    Ok(class_id
+        .strip_prefix(&format!("{}/", ics721_port(&ics721)))
+        .and_then(|path| path.split_once('/'))
+        .map(|(channel_id, class_id)| (channel_id.to_string(), class_id.to_string())))
+}
+
+fn query_home_location(
+    deps: Deps,
+    config: &Config,
+    cw721: String,
+    token_id: String,
+) -> StdResult<Option<HomeLocationResponse>> {
+    Ok(
+        voucher_channel(deps, config, &cw721)?.map(|(channel_id, class_id)| HomeLocationResponse {
+            channel_id,
+            class_id,
+            token_id,
+        }),
+    )
+}
+
+fn query_pause_status(deps: Deps, env: Env) -> StdResult<PauseStatusResponse> {
+    let pause = PauseState::load(deps.storage)?;
+    let paused = pause.paused_at(env.block.height);
+    let unpause_at_height = pause
+        .unpause_at_height
+        .filter(|height| *height > env.block.height);
+    Ok(PauseStatusResponse {
+        pauser: pause.pauser,
+        paused,
+        unpause_at_height,
+    })
+}
+
+fn query_contract_info(deps: Deps, config: Config) -> StdResult<ContractInfoResponse> {
+    let (ics721_outgoing_proxy, ics721_incoming_proxy) = match config.addr_ics721.clone() {
+        Some(ics721) => (
+            deps.querier
+                .query_wasm_smart(ics721.clone(), &ics721::msg::QueryMsg::OutgoingProxy {})?,
+            deps.querier
+                .query_wasm_smart(ics721, &ics721::msg::QueryMsg::IncomingProxy {})?,
+        ),
+        None => (None, None),
+    };
+    let linked_contracts = LinkedContracts {
+        cw721: config.addr_cw721.clone(),
+        poap: config.addr_poap.clone(),
+        ics721: config.addr_ics721.clone(),
+        ics721_outgoing_proxy,
+        ics721_incoming_proxy,
+        counterparty_contract: config.counterparty_contract.clone(),
+    };
+    Ok(ContractInfoResponse {
+        contract_version: get_contract_version(deps.storage)?,
+        ownership: cw_ownable::get_ownership(deps.storage)?,
+        config,
+        linked_contracts,
+        counters: COUNTERS.load(deps.storage)?,
+    })
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
-    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+pub fn migrate(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    // run state upgrades first, so updates below are applied to latest state
+    let upgrade_attributes = upgrades::upgrade(deps.branch(), &env)?;
     let response: Response<Empty> = Response::default()
         .add_attribute("method", "migrate")
         .add_attribute("contract_name", CONTRACT_NAME)
-        .add_attribute("contract_version", CONTRACT_VERSION);
+        .add_attribute("contract_version", CONTRACT_VERSION)
+        .add_attributes(upgrade_attributes);
     match msg {
         MigrateMsg::WithUpdate {
             default_token_uri,
             escrowed_token_uri,
             transferred_token_uri,
         } => {
+            let mut config = CONFIG.load(deps.storage)?;
             let response = if let Some(token_uri) = default_token_uri {
-                DEFAULT_TOKEN_URI.save(deps.storage, &token_uri)?;
+                validate_token_uri(&token_uri)?;
+                config.default_token_uri = token_uri.clone();
                 response.add_attribute("default_token_uri", token_uri)
             } else {
                 response
             };
             let response = if let Some(token_uri) = escrowed_token_uri {
-                ESCROWED_TOKEN_URI.save(deps.storage, &token_uri)?;
+                validate_token_uri(&token_uri)?;
+                config.escrowed_token_uri = token_uri.clone();
                 response.add_attribute("escrowed_token_uri", token_uri)
             } else {
                 response
             };
             let response = if let Some(token_uri) = transferred_token_uri {
-                TRANSFERRED_TOKEN_URI.save(deps.storage, &token_uri)?;
+                validate_token_uri(&token_uri)?;
+                config.transferred_token_uri = token_uri.clone();
                 response.add_attribute("transferred_token_uri", token_uri)
             } else {
                 response
             };
+            CONFIG.save(deps.storage, &config)?;
             Ok(response)
         }
     }
@@ -534,7 +2267,10 @@ pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, Contrac
         INSTANTIATE_POAP_REPLY_ID => {
             let res = parse_reply_instantiate_data(reply.clone())?;
             let poap = deps.api.addr_validate(&res.contract_address)?;
-            ADDR_POAP.save(deps.storage, &poap)?;
+            CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+                config.addr_poap = Some(poap.clone());
+                Ok(config)
+            })?;
             Ok(response
                 .add_attribute("method", "sub_msg_instantiate_cw721")
                 .add_attribute("addr_poap", poap))
@@ -542,7 +2278,10 @@ pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, Contrac
         INSTANTIATE_CW721_REPLY_ID => {
             let res = parse_reply_instantiate_data(reply.clone())?;
             let cw721 = deps.api.addr_validate(&res.contract_address)?;
-            ADDR_CW721.save(deps.storage, &cw721)?;
+            CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+                config.addr_cw721 = Some(cw721.clone());
+                Ok(config)
+            })?;
             Ok(response
                 .add_attribute("method", "sub_msg_instantiate_cw721")
                 .add_attribute("addr_cw721", cw721))
@@ -550,13 +2289,33 @@ pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, Contrac
         INSTANTIATE_ICS721_REPLY_ID => {
             let res = parse_reply_instantiate_data(reply.clone())?;
             let ics721 = deps.api.addr_validate(&res.contract_address)?;
-            ADDR_ICS721.save(deps.storage, &ics721)?;
+            CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+                config.addr_ics721 = Some(ics721.clone());
+                Ok(config)
+            })?;
             Ok(response
                 .add_attribute("method", "sub_msg_instantiate_ics721")
                 .add_attribute("addr_ics721", ics721))
         }
         MINT_NFT_REPLY_ID => Ok(response.add_attribute("method", "sub_msg_mint_nft")),
         UPDATE_NFT_REPLY_ID => Ok(response.add_attribute("method", "sub_msg_update_nft")),
+        SYNC_COLLECTION_REPLY_ID => {
+            let cw721 = SYNCING_COLLECTION.load(deps.storage)?;
+            SYNCING_COLLECTION.remove(deps.storage);
+            let response = response
+                .add_attribute("method", "sub_msg_sync_collection")
+                .add_attribute("cw721", cw721.clone());
+            match reply.result.into_result() {
+                Ok(_) => {
+                    SYNCED_COLLECTIONS.save(deps.storage, &cw721, &Empty {})?;
+                    Ok(response.add_attribute("synced", "true"))
+                }
+                // receive callback succeeds without collection info
+                Err(error) => Ok(response
+                    .add_attribute("synced", "false")
+                    .add_attribute("error", error)),
+            }
+        }
         _ => Err(ContractError::UnrecognisedReplyId {}),
     }
 }
@@ -1,13 +1,16 @@
 pub mod error;
+pub mod events;
 pub mod execute;
 pub mod msg;
 pub mod state;
+mod upgrades;
 
-pub(crate) const INSTANTIATE_CW721_REPLY_ID: u64 = 0;
-pub(crate) const INSTANTIATE_POAP_REPLY_ID: u64 = 1;
-pub(crate) const INSTANTIATE_ICS721_REPLY_ID: u64 = 2;
-pub(crate) const MINT_NFT_REPLY_ID: u64 = 3;
-pub(crate) const UPDATE_NFT_REPLY_ID: u64 = 4;
+pub const INSTANTIATE_CW721_REPLY_ID: u64 = 0;
+pub const INSTANTIATE_POAP_REPLY_ID: u64 = 1;
+pub const INSTANTIATE_ICS721_REPLY_ID: u64 = 2;
+pub const MINT_NFT_REPLY_ID: u64 = 3;
+pub const UPDATE_NFT_REPLY_ID: u64 = 4;
+pub const SYNC_COLLECTION_REPLY_ID: u64 = 5;
 
 #[cfg(test)]
 pub mod testing;
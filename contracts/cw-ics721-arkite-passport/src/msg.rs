@@ -1,8 +1,19 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Coin, Decimal, StdResult, Uint128};
+use cw2::ContractVersion;
 use cw721_base::receiver::Cw721ReceiveMsg;
 use cw_cii::ContractInstantiateInfo;
-use ics721_types::types::{Ics721AckCallbackMsg, Ics721ReceiveCallbackMsg};
+use cw_ics721_callback::callback_memo;
+use cw_ownable::{cw_ownable_execute, cw_ownable_query, Ownership};
+use ics721_types::{
+    ibc_types::IbcOutgoingMsg,
+    types::{Ics721AckCallbackMsg, Ics721Memo, Ics721ReceiveCallbackMsg},
+};
+
+use crate::state::{
+    CollectionMetadata, Config, Counters, FeeConfig, HomeMetadata, MetadataPolicy,
+    OutgoingTransfer, PausedOperations, PoapPolicy, RateLimitPolicy, RoyaltyInfo, TimeoutPolicy,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -14,27 +25,120 @@ pub struct InstantiateMsg {
     pub cw721_poap: ContractInstantiateInfo,
 }
 
+#[cw_ownable_execute]
 #[cw_serde]
 pub enum ExecuteMsg {
     Mint {},
+    /// Outgoing transfer via `send_nft`, bridge fee is paid from prepaid balance of NFT owner
     ReceiveNft(Cw721ReceiveMsg),
+    /// Outgoing transfer of an NFT owned by sender, bridge fee is paid by attached funds.
+    /// Sender must approve this contract for the NFT beforehand.
+    Transfer {
+        cw721: String,
+        token_id: String,
+        msg: IbcOutgoingMsg,
+    },
+    /// Adds attached funds to prepaid fee balance of sender
+    PrepayFees {},
+    /// Withdraws from prepaid fee balance of sender
+    WithdrawPrepaidFees {
+        amount: Coin,
+    },
+    /// Owner only
     CounterPartyContract {
         addr: String,
     },
+    /// Owner only, sets counterparty contract for transfers on a channel or removes it in case of `None`.
+    /// Needed on chains with channels to several passport chains, others use `CounterPartyContract`.
+    SetChannelCounterparty {
+        channel_id: String,
+        counterparty_contract: Option<String>,
+    },
+    /// Owner only, updates provided fields only
+    UpdateConfig {
+        default_token_uri: Option<String>,
+        escrowed_token_uri: Option<String>,
+        transferred_token_uri: Option<String>,
+        poap_policy: Option<PoapPolicy>,
+        timeout_policy: Option<TimeoutPolicy>,
+        rate_limit_policy: Option<RateLimitPolicy>,
+    },
+    /// Owner only, sets royalty of newly minted passports and poaps or removes it in case of `None`.
+    /// Existing passports are updated by `ResyncMetadata`.
+    SetRoyaltyInfo {
+        royalty_info: Option<RoyaltyInfo>,
+    },
+    /// Owner only, sets collection info sent to voucher collections on other chains or removes it in case of `None`.
+    /// Voucher collections are updated once, on first receive.
+    SetCollectionMetadata {
+        collection_metadata: Option<CollectionMetadata>,
+    },
+    /// Owner only, allows bridging NFTs of a collection with given metadata policy or disallows it in case of `None`.
+    /// Passport collection is always allowed, using passport policy.
+    SetCollectionPolicy {
+        cw721: String,
+        policy: Option<MetadataPolicy>,
+    },
+    /// Owner only, rewrites traits and image of home chain passports to current config.
+    /// Paginated by token id, the last token id is returned as `last_token_id` attribute.
+    ResyncMetadata {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Adds attached funds as relayer incentive for an outgoing transfer awaiting ack.
+    /// Incentives are released to relayer pool address of fee config on successful ack, and refunded to payers on failure or timeout.
+    /// They are not paid to the relayer of the packet, since ics721 callbacks don't expose it.
+    PayPacketIncentive {
+        cw721: String,
+        token_id: String,
+    },
+    /// Owner only, updates provided fields only
+    UpdateFeeConfig {
+        treasury: Option<String>,
+        relayer_pool: Option<String>,
+        relayer_share: Option<Decimal>,
+    },
+    /// Owner only, sets bridge fee of a channel or removes it in case of `None`
+    SetChannelFee {
+        channel_id: String,
+        fee: Option<Coin>,
+    },
+    /// Owner only, sets pauser or removes it in case of `None`
+    SetPauser {
+        pauser: Option<String>,
+    },
+    /// Owner or pauser, replaces paused operations. Acks are never paused, so NFTs of failed transfers are always returned.
+    /// If `unpause_at_height` is set, all operations are unpaused automatically at this block height.
+    Pause {
+        operations: PausedOperations,
+        unpause_at_height: Option<u64>,
+    },
+    /// Owner or pauser, unpauses all operations
+    Unpause {},
     /// Ack callback on source chain
     Ics721AckCallback(Ics721AckCallbackMsg),
     /// Receive callback on target chain, NOTE: if this fails, the transfer will fail and NFT is reverted back to the sender
     Ics721ReceiveCallback(Ics721ReceiveCallbackMsg),
 }
 
+#[cw_ownable_query]
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
-    #[returns(Addr)]
+    /// URIs, addresses and counterparty settings in one response
+    #[returns(Config)]
+    Config {},
+    /// Version, owner, config, linked contracts and counters in one response
+    #[returns(ContractInfoResponse)]
+    ContractInfo {},
+    /// None, until instantiate reply is processed
+    #[returns(Option<Addr>)]
     Poap {},
-    #[returns(Addr)]
+    /// None, until instantiate reply is processed
+    #[returns(Option<Addr>)]
     CW721 {},
-    #[returns(Addr)]
+    /// None, until instantiate reply is processed
+    #[returns(Option<Addr>)]
     ICS721 {},
     #[returns(String)]
     DefaultTokenUri {},
@@ -42,8 +146,144 @@ pub enum QueryMsg {
     EscrowedTokenUri {},
     #[returns(String)]
     TransferredTokenUri {},
-    #[returns(String)]
+    /// None, until counterparty contract is set
+    #[returns(Option<String>)]
     CounterPartyContract {},
+    /// Counterparty contract receiving callbacks for transfers on channel, falls back to `CounterPartyContract`
+    #[returns(Option<String>)]
+    ChannelCounterparty { channel_id: String },
+    #[returns(Counters)]
+    Counters {},
+    /// Outgoing transfers of sender on channel within window of rate limit policy, and remaining quota
+    #[returns(RateLimitQuotaResponse)]
+    RateLimitQuota { sender: String, channel_id: String },
+    /// Fee config and fees of all channels
+    #[returns(FeesResponse)]
+    Fees {},
+    /// None, if transfers on channel are free
+    #[returns(Option<Coin>)]
+    ChannelFee { channel_id: String },
+    #[returns(Vec<Coin>)]
+    PrepaidFees { address: String },
+    /// Collected fees by denom
+    #[returns(Vec<CollectedFeesResponse>)]
+    CollectedFees {},
+    /// None, if NFT is not awaiting ack or timeout of an outgoing transfer
+    #[returns(Option<OutgoingTransfer>)]
+    OutgoingTransfer { cw721: String, token_id: String },
+    /// Policy applied to NFTs of a collection on transfer, None if collection is not allowed
+    #[returns(Option<MetadataPolicy>)]
+    CollectionPolicy { cw721: String },
+    /// Allowed collections and their policy, passport collection and voucher collections are not included
+    #[returns(Vec<CollectionPolicyResponse>)]
+    CollectionPolicies {},
+    /// Relayer incentives of an outgoing transfer, by payer
+    #[returns(Vec<PacketIncentive>)]
+    PacketIncentives { cw721: String, token_id: String },
+    /// Channels, voucher class id and receiver of a passport on the other chain, None if passport is on home chain.
+    /// Only the first hop is recorded: on multi-hop transfers, the passport may have moved on from the returned chain.
+    #[returns(Option<RemoteLocationResponse>)]
+    RemoteLocation { token_id: String },
+    /// Inverse of `RemoteLocation`: channel and class id of a voucher's original on home chain, None if not a voucher
+    #[returns(Option<HomeLocationResponse>)]
+    HomeLocation { cw721: String, token_id: String },
+    /// Pauser and operations paused at current block height
+    #[returns(PauseStatusResponse)]
+    PauseStatus {},
+}
+
+#[cw_serde]
+pub struct ContractInfoResponse {
+    pub contract_version: ContractVersion,
+    pub ownership: Ownership<Addr>,
+    pub config: Config,
+    pub linked_contracts: LinkedContracts,
+    pub counters: Counters,
+}
+
+#[cw_serde]
+pub struct FeesResponse {
+    pub fee_config: FeeConfig,
+    pub channel_fees: Vec<ChannelFee>,
+}
+
+#[cw_serde]
+pub struct ChannelFee {
+    pub channel_id: String,
+    pub fee: Coin,
+}
+
+#[cw_serde]
+pub struct CollectedFeesResponse {
+    pub denom: String,
+    pub treasury: Uint128,
+    pub relayer_pool: Uint128,
+}
+
+#[cw_serde]
+pub struct CollectionPolicyResponse {
+    pub cw721: String,
+    pub policy: MetadataPolicy,
+}
+
+#[cw_serde]
+pub struct RemoteLocationResponse {
+    /// channel on home chain
+    pub channel_id: String,
+    /// channel on other chain
+    pub counterparty_channel_id: String,
+    /// class id of voucher collection on other chain
+    pub voucher_class_id: String,
+    /// token id of voucher, same as on home chain
+    pub token_id: String,
+    pub receiver: String,
+}
+
+#[cw_serde]
+pub struct HomeLocationResponse {
+    /// channel on this chain, voucher has been received through
+    pub channel_id: String,
+    /// class id on home chain, which is the cw721 address for collections native to home chain
+    pub class_id: String,
+    /// token id of original, same as voucher
+    pub token_id: String,
+}
+
+#[cw_serde]
+pub struct PacketIncentive {
+    pub payer: String,
+    pub amount: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct RateLimitQuotaResponse {
+    pub policy: RateLimitPolicy,
+    /// transfers within current window
+    pub used: u32,
+    /// None, if unlimited
+    pub remaining: Option<u32>,
+    /// block height or timestamp in seconds (depending on policy) at which quota is available again, None if quota is left
+    pub retry_at: Option<u64>,
+}
+
+#[cw_serde]
+pub struct PauseStatusResponse {
+    pub pauser: Option<Addr>,
+    pub paused: PausedOperations,
+    /// None, if no automatic unpause is pending
+    pub unpause_at_height: Option<u64>,
+}
+
+#[cw_serde]
+pub struct LinkedContracts {
+    pub cw721: Option<Addr>,
+    pub poap: Option<Addr>,
+    pub ics721: Option<Addr>,
+    /// outgoing proxy of ics721, NFTs are forwarded to it instead of ics721
+    pub ics721_outgoing_proxy: Option<Addr>,
+    /// incoming proxy of ics721
+    pub ics721_incoming_proxy: Option<Addr>,
+    pub counterparty_contract: Option<String>,
 }
 
 #[cw_serde]
@@ -64,4 +304,33 @@ pub struct CallbackData {
     pub default_token_uri: String,
     pub escrowed_token_uri: String,
     pub transferred_token_uri: String,
+    /// royalty of passport on home chain, none for packets sent before royalties were introduced
+    pub royalty_info: Option<RoyaltyInfo>,
+    /// collection info for voucher collection, set on first receive
+    pub collection_metadata: Option<CollectionMetadata>,
+    /// policy of NFT collection, defaults to passport for packets sent before policies were introduced
+    #[serde(default)]
+    pub metadata_policy: MetadataPolicy,
+}
+
+impl CallbackData {
+    /// Memo with ack callback to source contract, and receive callback to counterparty contract on target chain - if set.
+    pub fn to_memo(
+        &self,
+        ack_callback_addr: String,
+        counterparty_contract: Option<String>,
+    ) -> StdResult<Ics721Memo> {
+        // here we need to set contract addr as receive callback, since receiver is NFT receiver
+        callback_memo(self, ack_callback_addr, counterparty_contract)
+    }
+
+    pub fn home_metadata(&self) -> HomeMetadata {
+        HomeMetadata {
+            default_token_uri: self.default_token_uri.clone(),
+            escrowed_token_uri: self.escrowed_token_uri.clone(),
+            transferred_token_uri: self.transferred_token_uri.clone(),
+            royalty_info: self.royalty_info.clone(),
+            collection_metadata: self.collection_metadata.clone(),
+        }
+    }
 }
@@ -1,10 +1,361 @@
-use cosmwasm_std::Addr;
-use cw_storage_plus::Item;
-
-pub const DEFAULT_TOKEN_URI: Item<String> = Item::new("token_uri");
-pub const ESCROWED_TOKEN_URI: Item<String> = Item::new("escrowed_token_uri");
-pub const TRANSFERRED_TOKEN_URI: Item<String> = Item::new("transferred_token_uri");
-pub const ADDR_CW721: Item<Addr> = Item::new("addr_cw721");
-pub const ADDR_ICS721: Item<Addr> = Item::new("addr_ics721");
-pub const ADDR_POAP: Item<Addr> = Item::new("addr_poap");
-pub const COUNTERPARTY_CONTRACT: Item<String> = Item::new("counterpart_contract");
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, BlockInfo, Coin, Decimal, Empty, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map};
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const COUNTERS: Item<Counters> = Item::new("counters");
+/// receivers who already got a poap
+pub const POAP_RECEIVERS: Map<&str, Empty> = Map::new("poap_receivers");
+/// recent outgoing transfers by sender and channel, within window of rate limit policy
+pub const RECENT_TRANSFERS: Map<(&str, &str), Vec<TransferPoint>> = Map::new("recent_transfers");
+/// treasury and relayer pool split of bridge fees, no fees by default
+pub const FEE_CONFIG: Item<FeeConfig> = Item::new("fee_config");
+/// bridge fee per channel, outgoing transfers on other channels are free
+pub const CHANNEL_FEES: Map<&str, Coin> = Map::new("channel_fees");
+/// prepaid fee balances by address and denom, used for transfers via `send_nft`
+pub const PREPAID_FEES: Map<(&str, &str), Uint128> = Map::new("prepaid_fees");
+/// collected fees by denom
+pub const COLLECTED_FEES: Map<&str, CollectedFees> = Map::new("collected_fees");
+/// outgoing transfers awaiting ack or timeout, by cw721 and token id
+pub const OUTGOING_TRANSFERS: Map<(&str, &str), OutgoingTransfer> = Map::new("outgoing_transfers");
+/// relayer incentives of outgoing transfers awaiting ack or timeout, by cw721, token id and payer
+pub const PACKET_INCENTIVES: Map<(&str, &str, &str), Vec<Coin>> = Map::new("packet_incentives");
+/// voucher collections whose collection info has been set by a receive callback
+pub const SYNCED_COLLECTIONS: Map<&str, Empty> = Map::new("synced_collections");
+/// voucher collection whose collection info is synced by current receive callback, until sync is replied
+pub const SYNCING_COLLECTION: Item<String> = Item::new("syncing_collection");
+/// collections allowed for bridging next to passport collection, by cw721
+pub const COLLECTION_POLICIES: Map<&str, MetadataPolicy> = Map::new("collection_policies");
+/// metadata policy of voucher collections, as received in callback data, used on back transfer
+pub const VOUCHER_POLICIES: Map<&str, MetadataPolicy> = Map::new("voucher_policies");
+/// metadata of home collection by voucher collection, as received in callback data, sent along on next hop
+pub const VOUCHER_METADATA: Map<&str, HomeMetadata> = Map::new("voucher_metadata");
+/// counterparty passport contract by channel, overrides `counterparty_contract` of config
+pub const CHANNEL_COUNTERPARTIES: Map<&str, String> = Map::new("channel_counterparties");
+/// passports of this chain's collection on other chains, by token id. Recorded on ack, removed on back transfer.
+pub const REMOTE_LOCATIONS: Map<&str, RemoteLocation> = Map::new("remote_locations");
+/// channel an NFT has been transferred out on, by cw721 and token id. Recorded on ack, removed on back transfer,
+/// which can only be received on this channel.
+pub const OUTGOING_CHANNELS: Map<(&str, &str), String> = Map::new("outgoing_channels");
+/// pauser and paused operations, nothing paused by default
+pub const PAUSE: Item<PauseState> = Item::new("pause");
+
+#[cw_serde]
+pub struct Config {
+    pub default_token_uri: String,
+    pub escrowed_token_uri: String,
+    pub transferred_token_uri: String,
+    /// passport collection, set on instantiate reply
+    pub addr_cw721: Option<Addr>,
+    /// poap collection, set on instantiate reply
+    pub addr_poap: Option<Addr>,
+    /// ics721 contract, set on instantiate reply
+    pub addr_ics721: Option<Addr>,
+    /// passport contract on the other chain, used as receive callback address
+    pub counterparty_contract: Option<String>,
+    pub poap_policy: PoapPolicy,
+    pub timeout_policy: TimeoutPolicy,
+    /// defaults to unlimited, for configs stored before rate limits were introduced
+    #[serde(default)]
+    pub rate_limit_policy: RateLimitPolicy,
+    /// royalty of minted passports and poaps, carried to vouchers on other chains
+    #[serde(default)]
+    pub royalty_info: Option<RoyaltyInfo>,
+    /// collection info of voucher collections on other chains
+    #[serde(default)]
+    pub collection_metadata: Option<CollectionMetadata>,
+}
+
+/// Defines whether a poap is minted on receive callback.
+#[cw_serde]
+pub enum PoapPolicy {
+    /// mint a poap on each receive
+    Always,
+    /// mint a poap only on first receive for each receiver
+    OncePerReceiver,
+    /// never mint poaps
+    Disabled,
+}
+
+/// Bounds for timeouts of outgoing transfers, relative to current block time.
+/// If any bound is set, timeout must be a timestamp.
+#[cw_serde]
+#[derive(Default)]
+pub struct TimeoutPolicy {
+    pub min_seconds: Option<u64>,
+    pub max_seconds: Option<u64>,
+}
+
+/// Royalty paid by marketplaces on sales, stored as `royalty_payment_address` and `royalty_share` traits.
+#[cw_serde]
+pub struct RoyaltyInfo {
+    pub payment_address: String,
+    /// share of sale price, e.g. 0.05 for 5%
+    pub share: Decimal,
+}
+
+/// Collection info set on voucher collections by first receive callback, fields not provided are kept.
+#[cw_serde]
+#[derive(Default)]
+pub struct CollectionMetadata {
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub external_link: Option<String>,
+}
+
+/// Defines how NFT metadata of a collection is changed by callbacks.
+#[cw_serde]
+#[derive(Default)]
+pub enum MetadataPolicy {
+    /// token uri is swapped between default, escrowed and transferred uri on both chains, like passports.
+    /// Contract must be creator of the collection on home chain.
+    #[default]
+    Passport,
+    /// metadata is never changed
+    PassThrough,
+    /// token uri is kept, vouchers get a `stamp` trait holding the transferred uri
+    Stamp,
+}
+
+/// URIs, royalty and collection info of a home collection, sent in callback data on every hop.
+#[cw_serde]
+pub struct HomeMetadata {
+    pub default_token_uri: String,
+    pub escrowed_token_uri: String,
+    pub transferred_token_uri: String,
+    pub royalty_info: Option<RoyaltyInfo>,
+    pub collection_metadata: Option<CollectionMetadata>,
+}
+
+impl From<&Config> for HomeMetadata {
+    fn from(config: &Config) -> Self {
+        Self {
+            default_token_uri: config.default_token_uri.clone(),
+            escrowed_token_uri: config.escrowed_token_uri.clone(),
+            transferred_token_uri: config.transferred_token_uri.clone(),
+            royalty_info: config.royalty_info.clone(),
+            collection_metadata: config.collection_metadata.clone(),
+        }
+    }
+}
+
+/// Limits outgoing transfers per sender and channel, within a sliding window of blocks or seconds.
+/// Quota is per (sender, channel) pair, so a sender's transfers on one channel never limit other channels or senders.
+#[cw_serde]
+#[derive(Default)]
+pub enum RateLimitPolicy {
+    #[default]
+    Unlimited,
+    /// at most `max_transfers` within the last `blocks` blocks
+    PerBlocks { max_transfers: u32, blocks: u64 },
+    /// at most `max_transfers` within the last `seconds` seconds
+    PerSeconds { max_transfers: u32, seconds: u64 },
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct FeeConfig {
+    /// receives bridge fees, minus relayer pool share
+    pub treasury: Option<Addr>,
+    /// pool receiving share of bridge fees and packet incentives, distributed to relayers off-chain
+    #[serde(alias = "relayer_incentive")]
+    pub relayer_pool: Option<Addr>,
+    /// share of bridge fees sent to relayer pool address, at most 1
+    pub relayer_share: Decimal,
+}
+
+impl FeeConfig {
+    pub fn load(storage: &dyn Storage) -> StdResult<Self> {
+        Ok(FEE_CONFIG.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn treasury(&self) -> StdResult<Addr> {
+        self.treasury
+            .clone()
+            .ok_or_else(|| StdError::not_found("treasury"))
+    }
+
+    /// Splits fee amount into treasury and relayer pool amount. Relayer pool share goes to treasury, if no address is set.
+    pub fn split(&self, amount: Uint128) -> (Uint128, Uint128) {
+        if self.relayer_pool.is_none() {
+            return (amount, Uint128::zero());
+        }
+        let relayer_amount = amount.mul_floor(self.relayer_share);
+        (amount - relayer_amount, relayer_amount)
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct CollectedFees {
+    pub treasury: Uint128,
+    #[serde(alias = "relayer_incentive")]
+    pub relayer_pool: Uint128,
+}
+
+#[cw_serde]
+pub struct OutgoingTransfer {
+    /// NFT owner, receives NFT and incentives back on failure or timeout
+    pub sender: String,
+    pub receiver: String,
+    pub channel_id: String,
+}
+
+/// Escrowed passport on home chain, transferred to `receiver` on other chain.
+#[cw_serde]
+pub struct RemoteLocation {
+    /// channel on home chain
+    pub channel_id: String,
+    /// class id of passport collection on home chain, voucher class id is prefixed by counterparty port and channel
+    pub class_id: String,
+    pub receiver: String,
+}
+
+/// Block height and time of an outgoing transfer.
+#[cw_serde]
+pub struct TransferPoint {
+    pub height: u64,
+    pub seconds: u64,
+}
+
+impl From<&BlockInfo> for TransferPoint {
+    fn from(block: &BlockInfo) -> Self {
+        Self {
+            height: block.height,
+            seconds: block.time.seconds(),
+        }
+    }
+}
+
+impl RateLimitPolicy {
+    /// Max transfers and window size, `None` if unlimited.
+    pub fn limit(&self) -> Option<(u32, u64)> {
+        match self {
+            RateLimitPolicy::Unlimited => None,
+            RateLimitPolicy::PerBlocks {
+                max_transfers,
+                blocks,
+            } => Some((*max_transfers, *blocks)),
+            RateLimitPolicy::PerSeconds {
+                max_transfers,
+                seconds,
+            } => Some((*max_transfers, *seconds)),
+        }
+    }
+
+    /// Position of a transfer in units of the window, either block height or seconds.
+    pub fn position(&self, point: &TransferPoint) -> u64 {
+        match self {
+            RateLimitPolicy::PerSeconds { .. } => point.seconds,
+            _ => point.height,
+        }
+    }
+
+    /// Transfers still within window at `now`, oldest first.
+    pub fn in_window(
+        &self,
+        transfers: Vec<TransferPoint>,
+        now: &TransferPoint,
+    ) -> Vec<TransferPoint> {
+        let Some((_, window)) = self.limit() else {
+            return vec![];
+        };
+        transfers
+            .into_iter()
+            .filter(|transfer| self.position(transfer) + window > self.position(now))
+            .collect()
+    }
+
+    /// Block height or timestamp in seconds, at which `transfers` within window allow another transfer.
+    /// `None` if there is quota left.
+    pub fn retry_at(&self, transfers: &[TransferPoint]) -> Option<u64> {
+        let (max_transfers, window) = self.limit()?;
+        let max_transfers = max_transfers as usize;
+        if transfers.len() < max_transfers {
+            return None;
+        }
+        // once this transfer leaves window, transfers are below max
+        let transfer = &transfers[transfers.len() - max_transfers];
+        Some(self.position(transfer) + window)
+    }
+}
+
+impl Config {
+    pub fn cw721(&self) -> StdResult<Addr> {
+        self.addr_cw721
+            .clone()
+            .ok_or_else(|| StdError::not_found("cw721"))
+    }
+
+    pub fn poap(&self) -> StdResult<Addr> {
+        self.addr_poap
+            .clone()
+            .ok_or_else(|| StdError::not_found("poap"))
+    }
+
+    pub fn ics721(&self) -> StdResult<Addr> {
+        self.addr_ics721
+            .clone()
+            .ok_or_else(|| StdError::not_found("ics721"))
+    }
+
+    /// Receive callback address for transfers on channel, channel specific counterparty takes precedence.
+    pub fn channel_counterparty(
+        &self,
+        storage: &dyn Storage,
+        channel_id: &str,
+    ) -> StdResult<Option<String>> {
+        Ok(CHANNEL_COUNTERPARTIES
+            .may_load(storage, channel_id)?
+            .or_else(|| self.counterparty_contract.clone()))
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Counters {
+    /// passports minted by this contract
+    pub passports_minted: u64,
+    /// poaps minted on receive callbacks
+    pub poaps_minted: u64,
+    /// outgoing transfers with a successful ack
+    pub transfers_acked: u64,
+    /// outgoing transfers with a failed ack, NFT returned to sender
+    pub transfers_failed: u64,
+}
+
+/// Operations, which can be paused separately.
+#[cw_serde]
+#[derive(Default)]
+pub struct PausedOperations {
+    /// minting passports on home chain
+    pub mint: bool,
+    /// forwarding received NFTs to ics721, for outgoing transfers
+    pub transfer: bool,
+    /// minting poaps on receive callback, incoming transfers are still processed
+    pub poap: bool,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct PauseState {
+    /// besides owner, pauser can pause and unpause operations
+    pub pauser: Option<Addr>,
+    pub paused: PausedOperations,
+    /// block height, at which all operations are unpaused automatically
+    pub unpause_at_height: Option<u64>,
+}
+
+impl PauseState {
+    pub fn load(storage: &dyn Storage) -> StdResult<Self> {
+        Ok(PAUSE.may_load(storage)?.unwrap_or_default())
+    }
+
+    /// Paused operations at given block height, nothing is paused once unpause height is reached.
+    pub fn paused_at(&self, height: u64) -> PausedOperations {
+        match self.unpause_at_height {
+            Some(unpause_at_height) if height >= unpause_at_height => PausedOperations::default(),
+            _ => self.paused.clone(),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use cosmwasm_std::{DepsMut, Empty, Env, Reply, Response};
+use cw_multi_test::{Contract, ContractWrapper};
+use ics721::ContractError as Ics721ContractError;
+
+use crate::execute;
+
+pub fn arkite_passport_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(execute::execute, execute::instantiate, execute::query)
+        .with_reply(execute::reply)
+        .with_migrate(execute::migrate);
+    Box::new(contract)
+}
+
+pub fn cw721_base_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw721_base::entry::execute,
+        cw721_base::entry::instantiate,
+        cw721_base::entry::query,
+    );
+    Box::new(contract)
+}
+
+pub fn ics721_contract() -> Box<dyn Contract<Empty>> {
+    // need to wrap method in function for testing
+    fn ibc_reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, Ics721ContractError> {
+        ics721_base::reply(deps, env, reply)
+    }
+
+    let contract = ContractWrapper::new(
+        ics721_base::execute,
+        ics721_base::instantiate,
+        ics721_base::query,
+    )
+    .with_migrate(ics721_base::migrate)
+    .with_reply(ibc_reply);
+    Box::new(contract)
+}
+
+pub fn incoming_proxy_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw_ics721_incoming_proxy_base::contract::execute,
+        cw_ics721_incoming_proxy_base::contract::instantiate,
+        cw_ics721_incoming_proxy_base::contract::query,
+    );
+    Box::new(contract)
+}
+
+pub fn outgoing_proxy_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw_ics721_outgoing_proxy_rate_limit::contract::execute,
+        cw_ics721_outgoing_proxy_rate_limit::contract::instantiate,
+        cw_ics721_outgoing_proxy_rate_limit::contract::query,
+    )
+    .with_reply(cw_ics721_outgoing_proxy_rate_limit::contract::reply);
+    Box::new(contract)
+}
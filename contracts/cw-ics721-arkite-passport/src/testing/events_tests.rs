@@ -0,0 +1,96 @@
+use cosmwasm_std::Event;
+
+use crate::events::{
+    parse_events, PassportEvent, PassportMinted, PoapMinted, TransferFailed, TransferInitiated,
+};
+
+fn passport_minted() -> PassportMinted {
+    PassportMinted {
+        cw721: "cw721".to_string(),
+        token_id: "0".to_string(),
+        owner: "owner".to_string(),
+    }
+}
+
+#[test]
+fn test_event_round_trip() {
+    let events = vec![
+        PassportEvent::PassportMinted(passport_minted()),
+        PassportEvent::TransferInitiated(TransferInitiated {
+            cw721: "cw721".to_string(),
+            token_id: "0".to_string(),
+            sender: "sender".to_string(),
+            receiver: "receiver".to_string(),
+            channel_id: "channel-0".to_string(),
+        }),
+        PassportEvent::TransferFailed(TransferFailed {
+            cw721: "cw721".to_string(),
+            token_id: "0".to_string(),
+            sender: "sender".to_string(),
+            error: "error".to_string(),
+        }),
+        PassportEvent::PoapMinted(PoapMinted {
+            poap: "poap".to_string(),
+            token_id: "0".to_string(),
+            receiver: "receiver".to_string(),
+        }),
+    ];
+    let raw_events: Vec<Event> = events.iter().cloned().map(Event::from).collect();
+    assert_eq!(raw_events[0].ty, PassportMinted::EVENT_TYPE);
+    assert_eq!(parse_events(&raw_events).unwrap(), events);
+}
+
+#[test]
+fn test_parse_events() {
+    let event: Event = passport_minted().into();
+
+    // case 1: event type with wasm prefix, as emitted on chain
+    {
+        let mut prefixed = event.clone();
+        prefixed.ty = format!("wasm-{}", PassportMinted::EVENT_TYPE);
+        assert_eq!(
+            PassportEvent::parse(&prefixed).unwrap(),
+            Some(PassportEvent::PassportMinted(passport_minted()))
+        );
+        assert_eq!(
+            PassportMinted::try_from(&prefixed).unwrap(),
+            passport_minted()
+        );
+    }
+
+    // case 2: other events are skipped
+    {
+        let events = vec![
+            Event::new("wasm").add_attribute("_contract_address", "passport"),
+            event.clone(),
+            Event::new("wasm-other").add_attribute("token_id", "0"),
+        ];
+        assert_eq!(
+            parse_events(&events).unwrap(),
+            vec![PassportEvent::PassportMinted(passport_minted())]
+        );
+    }
+
+    // case 3: missing attribute
+    {
+        let mut incomplete = event.clone();
+        incomplete
+            .attributes
+            .retain(|attribute| attribute.key != "owner");
+        let err = PassportEvent::parse(&incomplete).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: no owner in event arkite_passport_minted"
+        );
+        parse_events(&[incomplete]).unwrap_err();
+    }
+
+    // case 4: wrong event type
+    {
+        let err = TransferInitiated::try_from(&event).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: expected event arkite_transfer_initiated, got arkite_passport_minted"
+        );
+    }
+}
@@ -0,0 +1,432 @@
+use cosmwasm_std::{
+    from_json,
+    testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+        MOCK_CONTRACT_ADDR,
+    },
+    to_json_binary, Addr, Binary, ContractResult, Decimal, Empty, OwnedDeps, SystemError,
+    SystemResult, WasmQuery,
+};
+use cw721_base::{
+    msg::{NftInfoResponse, NumTokensResponse, OwnerOfResponse},
+    state::{NftExtension, Trait},
+    DefaultOptionalCollectionExtension, DefaultOptionalNftExtension, Ownership,
+};
+use ics721::{ClassId, NonFungibleTokenPacketData, TokenId};
+use ics721_types::types::{Ics721AckCallbackMsg, Ics721ReceiveCallbackMsg, Ics721Status};
+use proptest::prelude::*;
+
+use crate::{
+    error::ContractError,
+    execute::execute,
+    msg::{CallbackData, ExecuteMsg},
+    state::{
+        CollectionMetadata, Config, Counters, MetadataPolicy, PoapPolicy, RateLimitPolicy,
+        RoyaltyInfo, TimeoutPolicy, CONFIG, COUNTERS,
+    },
+};
+
+const ICS721: &str = "ics721";
+const CW721: &str = "cw721";
+/// voucher collection created by ics721, with passport as creator
+const VOUCHER: &str = "voucher";
+const POAP: &str = "poap";
+const COUNTERPARTY: &str = "counterparty";
+
+type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>;
+
+/// Contract with linked contracts, where passport and voucher collection return given nft info. `None` means nft does not exist.
+fn setup(nft_info: Option<NftInfoResponse<DefaultOptionalNftExtension>>) -> MockDeps {
+    let mut deps = mock_dependencies();
+    CONFIG
+        .save(
+            deps.as_mut().storage,
+            &Config {
+                default_token_uri: "ipfs://default".to_string(),
+                escrowed_token_uri: "ipfs://escrowed".to_string(),
+                transferred_token_uri: "ipfs://transferred".to_string(),
+                addr_cw721: Some(Addr::unchecked(CW721)),
+                addr_poap: Some(Addr::unchecked(POAP)),
+                addr_ics721: Some(Addr::unchecked(ICS721)),
+                counterparty_contract: Some(COUNTERPARTY.to_string()),
+                poap_policy: PoapPolicy::Always,
+                timeout_policy: TimeoutPolicy::default(),
+                rate_limit_policy: RateLimitPolicy::default(),
+                royalty_info: None,
+                collection_metadata: None,
+            },
+        )
+        .unwrap();
+    COUNTERS
+        .save(deps.as_mut().storage, &Counters::default())
+        .unwrap();
+    deps.querier.update_wasm(move |query| match query {
+        // voucher is received on channel-0, from collection of counterparty
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == ICS721 => {
+            match from_json::<ics721::msg::QueryMsg>(msg) {
+                Ok(ics721::msg::QueryMsg::ClassId { contract }) => {
+                    let class_id = (contract == VOUCHER)
+                        .then(|| ClassId::new(format!("wasm.{ICS721}/channel-0/{CW721}")));
+                    SystemResult::Ok(ContractResult::Ok(to_json_binary(&class_id).unwrap()))
+                }
+                _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "query".to_string(),
+                }),
+            }
+        }
+        WasmQuery::Smart { msg, .. } => {
+            match from_json::<
+                cw721_base::msg::QueryMsg<
+                    DefaultOptionalNftExtension,
+                    DefaultOptionalCollectionExtension,
+                    Empty,
+                >,
+            >(msg)
+            {
+                Ok(cw721_base::msg::QueryMsg::NftInfo { .. }) => match nft_info.clone() {
+                    Some(nft_info) => {
+                        SystemResult::Ok(ContractResult::Ok(to_json_binary(&nft_info).unwrap()))
+                    }
+                    None => SystemResult::Ok(ContractResult::Err("token not found".to_string())),
+                },
+                // ack success skips update of burned vouchers
+                Ok(cw721_base::msg::QueryMsg::OwnerOf { .. }) => match nft_info {
+                    Some(_) => SystemResult::Ok(ContractResult::Ok(
+                        to_json_binary(&OwnerOfResponse {
+                            owner: ICS721.to_string(),
+                            approvals: vec![],
+                        })
+                        .unwrap(),
+                    )),
+                    None => SystemResult::Ok(ContractResult::Err("token not found".to_string())),
+                },
+                Ok(cw721_base::msg::QueryMsg::NumTokens {}) => SystemResult::Ok(
+                    ContractResult::Ok(to_json_binary(&NumTokensResponse { count: 0 }).unwrap()),
+                ),
+                Ok(cw721_base::msg::QueryMsg::GetCreatorOwnership {}) => {
+                    SystemResult::Ok(ContractResult::Ok(
+                        to_json_binary(&Ownership::<Addr> {
+                            owner: Some(Addr::unchecked(MOCK_CONTRACT_ADDR)),
+                            pending_owner: None,
+                            pending_expiry: None,
+                        })
+                        .unwrap(),
+                    ))
+                }
+                _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "query".to_string(),
+                }),
+            }
+        }
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "query".to_string(),
+        }),
+    });
+    deps
+}
+
+fn trait_strategy() -> impl Strategy<Value = Trait> {
+    (
+        prop_oneof![
+            Just("token_uri".to_string()),
+            Just("default_uri".to_string()),
+            Just("escrowed_uri".to_string()),
+            Just("transferred_uri".to_string()),
+            Just("royalty_payment_address".to_string()),
+            Just("royalty_share".to_string()),
+            Just("stamp".to_string()),
+            "\\PC{0,16}",
+        ],
+        // royalty share is parsed from trait value
+        prop_oneof![Just("0.05".to_string()), "\\PC{0,32}"],
+    )
+        .prop_map(|(trait_type, value)| Trait {
+            display_type: None,
+            trait_type,
+            value,
+        })
+}
+
+/// Any nft info shape: with or without token uri, extension and traits.
+fn nft_info_strategy() -> impl Strategy<Value = Option<NftInfoResponse<DefaultOptionalNftExtension>>>
+{
+    let extension = prop::option::of(
+        prop::option::of(prop::collection::vec(trait_strategy(), 0..6)).prop_map(|attributes| {
+            NftExtension {
+                attributes,
+                ..Default::default()
+            }
+        }),
+    );
+    prop::option::of(
+        (
+            prop::option::of(prop_oneof![
+                Just("ipfs://default".to_string()),
+                "\\PC{0,32}",
+            ]),
+            extension,
+        )
+            .prop_map(|(token_uri, extension)| NftInfoResponse {
+                token_uri,
+                extension,
+            }),
+    )
+}
+
+fn metadata_policy_strategy() -> impl Strategy<Value = MetadataPolicy> {
+    prop_oneof![
+        Just(MetadataPolicy::Passport),
+        Just(MetadataPolicy::PassThrough),
+        Just(MetadataPolicy::Stamp),
+    ]
+}
+
+fn royalty_info_strategy() -> impl Strategy<Value = RoyaltyInfo> {
+    ("\\PC{0,32}", 0..=1000u64).prop_map(|(payment_address, permille)| RoyaltyInfo {
+        payment_address,
+        share: Decimal::permille(permille),
+    })
+}
+
+fn collection_metadata_strategy() -> impl Strategy<Value = CollectionMetadata> {
+    (
+        prop::option::of("\\PC{0,32}"),
+        prop::option::of("\\PC{0,32}"),
+        prop::option::of("\\PC{0,32}"),
+    )
+        .prop_map(|(description, image, external_link)| CollectionMetadata {
+            description,
+            image,
+            external_link,
+        })
+}
+
+/// Any callback data: of any metadata policy, with or without royalty and collection metadata.
+fn callback_data_strategy() -> impl Strategy<Value = CallbackData> {
+    (
+        // token of packet, or any other
+        prop_oneof![Just("0".to_string()), "\\PC{0,16}"],
+        "\\PC{0,16}",
+        "\\PC{0,32}",
+        "\\PC{0,32}",
+        "\\PC{0,32}",
+        prop::option::of(royalty_info_strategy()),
+        prop::option::of(collection_metadata_strategy()),
+        metadata_policy_strategy(),
+    )
+        .prop_map(
+            |(
+                token_id,
+                sender,
+                default_token_uri,
+                escrowed_token_uri,
+                transferred_token_uri,
+                royalty_info,
+                collection_metadata,
+                metadata_policy,
+            )| CallbackData {
+                token_id,
+                sender,
+                default_token_uri,
+                escrowed_token_uri,
+                transferred_token_uri,
+                royalty_info,
+                collection_metadata,
+                metadata_policy,
+            },
+        )
+}
+
+/// Either valid callback data, or arbitrary bytes.
+fn callback_payload_strategy() -> impl Strategy<Value = Binary> {
+    prop_oneof![
+        callback_data_strategy().prop_map(|callback_data| to_json_binary(&callback_data).unwrap()),
+        "\\PC{0,64}".prop_map(|json| Binary::from(json.as_bytes())),
+        prop::collection::vec(any::<u8>(), 0..128).prop_map(Binary::from),
+    ]
+}
+
+fn status_strategy() -> impl Strategy<Value = Ics721Status> {
+    prop_oneof![
+        Just(Ics721Status::Success),
+        "\\PC{0,32}".prop_map(Ics721Status::Failed),
+    ]
+}
+
+/// Passport on home chain, or voucher on other chain.
+fn nft_contract_strategy() -> impl Strategy<Value = String> {
+    prop_oneof![Just(CW721.to_string()), Just(VOUCHER.to_string())]
+}
+
+fn packet(receiver: String) -> NonFungibleTokenPacketData {
+    NonFungibleTokenPacketData {
+        class_id: ClassId::new(CW721),
+        class_uri: None,
+        class_data: None,
+        token_ids: vec![TokenId::new("0")],
+        token_uris: None,
+        token_data: None,
+        sender: COUNTERPARTY.to_string(),
+        receiver,
+        memo: None,
+    }
+}
+
+/// Expected error for a callback from ics721, given payload and nft info.
+/// Nft info is only queried for passport policy and for stamping vouchers, only passport policy needs a token uri.
+fn assert_expected_error(
+    err: ContractError,
+    payload: &Binary,
+    nft_contract: &str,
+    nft_info: &Option<NftInfoResponse<DefaultOptionalNftExtension>>,
+) {
+    match from_json::<CallbackData>(payload) {
+        Err(_) => assert!(
+            matches!(err, ContractError::Std(_)),
+            "malformed callback data must be a parse error, got {err:?}"
+        ),
+        Ok(callback_data) => match nft_info {
+            None => assert!(
+                matches!(err, ContractError::Std(_))
+                    && (callback_data.metadata_policy == MetadataPolicy::Passport
+                        || callback_data.metadata_policy == MetadataPolicy::Stamp
+                            && nft_contract == VOUCHER),
+                "got {err:?}"
+            ),
+            Some(nft_info)
+                if nft_info.token_uri.is_none()
+                    && callback_data.metadata_policy == MetadataPolicy::Passport =>
+            {
+                assert_eq!(
+                    err,
+                    ContractError::MissingTokenUri {
+                        cw721: nft_contract.to_string(),
+                        token_id: callback_data.token_id,
+                    }
+                )
+            }
+            Some(_) => panic!("unexpected error {err:?}"),
+        },
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_fuzz_receive_callback(
+        payload in callback_payload_strategy(),
+        nft_info in nft_info_strategy(),
+        nft_contract in nft_contract_strategy(),
+        receiver in "\\PC{0,32}",
+        from_ics721 in any::<bool>(),
+    ) {
+        let mut deps = setup(nft_info.clone());
+        let sender = if from_ics721 { ICS721 } else { "other" };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::Ics721ReceiveCallback(Ics721ReceiveCallbackMsg {
+                msg: payload.clone(),
+                nft_contract: nft_contract.clone(),
+                original_packet: packet(receiver),
+            }),
+        );
+        match res {
+            Ok(_) => prop_assert!(from_ics721),
+            Err(err) if !from_ics721 => {
+                prop_assert_eq!(err, ContractError::UnauthorizedCallback {})
+            }
+            Err(err) => assert_expected_error(err, &payload, &nft_contract, &nft_info),
+        }
+    }
+
+    #[test]
+    fn test_fuzz_ack_callback(
+        payload in callback_payload_strategy(),
+        nft_info in nft_info_strategy(),
+        nft_contract in nft_contract_strategy(),
+        status in status_strategy(),
+        from_ics721 in any::<bool>(),
+    ) {
+        let mut deps = setup(nft_info.clone());
+        let sender = if from_ics721 { ICS721 } else { "other" };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::Ics721AckCallback(Ics721AckCallbackMsg {
+                status: status.clone(),
+                msg: payload.clone(),
+                nft_contract: nft_contract.clone(),
+                // acks are only handled for packets sent by passport
+                original_packet: NonFungibleTokenPacketData {
+                    sender: MOCK_CONTRACT_ADDR.to_string(),
+                    ..packet("receiver".to_string())
+                },
+            }),
+        );
+        match res {
+            // acks are only handled for token of packet
+            Ok(_) => prop_assert!(
+                from_ics721
+                    && from_json::<CallbackData>(&payload)
+                        .is_ok_and(|callback_data| callback_data.token_id == "0")
+            ),
+            Err(err) if !from_ics721 => {
+                prop_assert_eq!(err, ContractError::UnauthorizedCallback {})
+            }
+            // callback data of a token other than the packet's is rejected
+            Err(ContractError::TokenNotInPacket { token_id }) => prop_assert!(
+                token_id != "0"
+                    && from_json::<CallbackData>(&payload)
+                        .is_ok_and(|callback_data| callback_data.token_id == token_id)
+            ),
+            // on failed transfer nft info is not queried, on success burned vouchers are skipped
+            Err(err) if status != Ics721Status::Success || nft_info.is_none() => prop_assert!(
+                matches!(err, ContractError::Std(_))
+                    && from_json::<CallbackData>(&payload).is_err(),
+                "got {:?}",
+                err
+            ),
+            Err(err) => assert_expected_error(err, &payload, &nft_contract, &nft_info),
+        }
+    }
+
+    /// Callback messages sent by ics721 decode to the same message, callback data included.
+    #[test]
+    fn test_fuzz_execute_msg_round_trip(
+        callback_data in callback_data_strategy(),
+        nft_contract in nft_contract_strategy(),
+        receiver in "\\PC{0,32}",
+        status in status_strategy(),
+    ) {
+        let payload = to_json_binary(&callback_data).unwrap();
+        prop_assert_eq!(from_json::<CallbackData>(&payload).unwrap(), callback_data);
+        let msgs = [
+            ExecuteMsg::Ics721ReceiveCallback(Ics721ReceiveCallbackMsg {
+                msg: payload.clone(),
+                nft_contract: nft_contract.clone(),
+                original_packet: packet(receiver.clone()),
+            }),
+            ExecuteMsg::Ics721AckCallback(Ics721AckCallbackMsg {
+                status,
+                msg: payload,
+                nft_contract,
+                original_packet: packet(receiver),
+            }),
+        ];
+        for msg in msgs {
+            let bytes = to_json_binary(&msg).unwrap();
+            prop_assert_eq!(from_json::<ExecuteMsg>(&bytes).unwrap(), msg);
+        }
+    }
+
+    /// Raw bytes either fail decoding, or decode to a message which round-trips unchanged.
+    #[test]
+    fn test_fuzz_execute_msg_decoding(bytes in prop::collection::vec(any::<u8>(), 0..256)) {
+        if let Ok(msg) = from_json::<ExecuteMsg>(&bytes) {
+            let reencoded = to_json_binary(&msg).unwrap();
+            prop_assert_eq!(from_json::<ExecuteMsg>(&reencoded).unwrap(), msg);
+        }
+    }
+}
@@ -0,0 +1,975 @@
+//! Two-chain test harness, extended to three chains for multi-hop tests. Each chain is a separate multi-test app with
+//! its own passport, cw721, poap and ics721 contracts. Packets sent by ics721 are recorded and relayed in-process to
+//! the other chain, including acks and timeouts.
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, BlockInfo, Coin, CosmosMsg, DepsMut, Empty, Env,
+    Event, IbcAcknowledgement, IbcEndpoint, IbcMsg, IbcPacket, IbcPacketAckMsg,
+    IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcTimeout, MessageInfo, Order, Reply, Response,
+    StdResult, Storage,
+};
+use cw721_base::{
+    msg::{
+        AllNftInfoResponse, CollectionInfoAndExtensionResponse,
+        InstantiateMsg as Cw721InstantiateMsg, NumTokensResponse,
+    },
+    DefaultOptionalCollectionExtension, DefaultOptionalCollectionExtensionMsg,
+    DefaultOptionalNftExtension, DefaultOptionalNftExtensionMsg, Ownership,
+};
+use cw_cii::{Admin, ContractInstantiateInfo};
+use cw_ics721_callback::multi_test::{mock_app, MockApp};
+use cw_multi_test::{AppResponse, Contract, ContractWrapper, Executor};
+use cw_storage_plus::{Item, Map};
+use ics721::{
+    msg::{
+        ExecuteMsg as Ics721ExecuteMsg, InstantiateMsg as Ics721InstantiateMsg,
+        MigrateMsg as Ics721MigrateMsg,
+    },
+    ContractError as Ics721ContractError,
+};
+use ics721_types::ibc_types::IbcOutgoingMsg;
+
+use super::common::{arkite_passport_contract, cw721_base_contract};
+use crate::{
+    msg::{
+        ExecuteMsg, HomeLocationResponse, InstantiateMsg, MigrateMsg, PacketIncentive, QueryMsg,
+    },
+    state::{
+        CollectionMetadata, Counters, MetadataPolicy, OutgoingTransfer, RemoteLocation,
+        RoyaltyInfo, REMOTE_LOCATIONS,
+    },
+};
+
+pub const DEFAULT_TOKEN_URI: &str = "ipfs://interchain.passport";
+pub const ESCROWED_TOKEN_URI: &str = "ipfs://interchain.escrowed";
+pub const TRANSFERRED_TOKEN_URI: &str = "ipfs://interchain.transferred";
+/// timeout used for transfers, relative to block time of sending chain
+pub const TRANSFER_TIMEOUT_SECONDS: u64 = 1000;
+
+/// Packet sent by ics721, waiting to be relayed.
+#[cw_serde]
+pub struct SentPacket {
+    pub sequence: u64,
+    pub channel_id: String,
+    pub data: Binary,
+    pub timeout: IbcTimeout,
+}
+
+// stored in ics721 contract storage, this way sent packets are reverted in case TX fails
+const SENT_PACKETS: Map<u64, SentPacket> = Map::new("harness_sent_packets");
+const PACKET_SEQUENCE: Item<u64> = Item::new("harness_packet_sequence");
+
+/// IBC entry points of ics721, called by relayer.
+#[cw_serde]
+pub enum RelayMsg {
+    ReceivePacket { packet: IbcPacket },
+    AckPacket { packet: IbcPacket, ack: Binary },
+    TimeoutPacket { packet: IbcPacket },
+}
+
+/// Multi-test does not support IBC entry points, so ics721 is wrapped by a contract,
+/// which handles relay messages next to regular ics721 messages.
+#[derive(cosmwasm_schema::serde::Deserialize, Debug)]
+#[serde(crate = "cosmwasm_schema::serde", untagged)]
+enum HarnessExecuteMsg {
+    Relay(RelayMsg),
+    Ics721(Ics721ExecuteMsg),
+}
+
+/// Ack as returned by ics721.
+#[cw_serde]
+pub enum PacketAck {
+    Result(Binary),
+    Error(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PacketOutcome {
+    Ack(PacketAck),
+    Timeout,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelayedPacket {
+    /// chain id of the sending chain
+    pub src_chain: String,
+    pub packet: IbcPacket,
+    pub outcome: PacketOutcome,
+    /// events of receive on `dest` chain, followed by events of ack or timeout on `src` chain
+    pub events: Vec<Event>,
+}
+
+fn ics721_harness_execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: HarnessExecuteMsg,
+) -> Result<Response, Ics721ContractError> {
+    match msg {
+        HarnessExecuteMsg::Ics721(msg) => {
+            let mut res = ics721_base::execute(deps.branch(), env, info, msg)?;
+            record_sent_packets(deps.storage, &mut res)?;
+            Ok(res)
+        }
+        HarnessExecuteMsg::Relay(RelayMsg::ReceivePacket { packet }) => {
+            let msg = IbcPacketReceiveMsg::new(packet, info.sender);
+            let res = ics721_base::ibc::ibc_packet_receive(deps, env, msg)
+                .unwrap_or_else(|never| match never {});
+            // ack is returned as data, reply of ics721 may override it with an error ack
+            Ok(Response::default()
+                .add_submessages(res.messages)
+                .add_attributes(res.attributes)
+                .add_events(res.events)
+                .set_data(res.acknowledgement))
+        }
+        HarnessExecuteMsg::Relay(RelayMsg::AckPacket { packet, ack }) => {
+            let msg = IbcPacketAckMsg::new(IbcAcknowledgement::new(ack), packet, info.sender);
+            let res = ics721_base::ibc::ibc_packet_ack(deps, env, msg)?;
+            Ok(Response::default()
+                .add_submessages(res.messages)
+                .add_attributes(res.attributes)
+                .add_events(res.events))
+        }
+        HarnessExecuteMsg::Relay(RelayMsg::TimeoutPacket { packet }) => {
+            let msg = IbcPacketTimeoutMsg::new(packet, info.sender);
+            let res = ics721_base::ibc::ibc_packet_timeout(deps, env, msg)?;
+            Ok(Response::default()
+                .add_submessages(res.messages)
+                .add_attributes(res.attributes)
+                .add_events(res.events))
+        }
+    }
+}
+
+/// Moves `SendPacket` messages from response into storage, for relayer to pick them up.
+fn record_sent_packets(storage: &mut dyn Storage, res: &mut Response) -> StdResult<()> {
+    let mut messages = vec![];
+    for sub_msg in res.messages.drain(..) {
+        if let CosmosMsg::Ibc(IbcMsg::SendPacket {
+            channel_id,
+            data,
+            timeout,
+        }) = &sub_msg.msg
+        {
+            let sequence = PACKET_SEQUENCE.may_load(storage)?.unwrap_or_default() + 1;
+            PACKET_SEQUENCE.save(storage, &sequence)?;
+            SENT_PACKETS.save(
+                storage,
+                sequence,
+                &SentPacket {
+                    sequence,
+                    channel_id: channel_id.clone(),
+                    data: data.clone(),
+                    timeout: timeout.clone(),
+                },
+            )?;
+        } else {
+            messages.push(sub_msg);
+        }
+    }
+    res.messages = messages;
+    Ok(())
+}
+
+fn ics721_harness_contract() -> Box<dyn Contract<Empty>> {
+    // need to wrap method in function for testing
+    fn ibc_reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, Ics721ContractError> {
+        ics721_base::reply(deps, env, reply)
+    }
+
+    let contract = ContractWrapper::new(
+        ics721_harness_execute,
+        ics721_base::instantiate,
+        ics721_base::query,
+    )
+    .with_migrate(ics721_base::migrate)
+    .with_reply(ibc_reply);
+    Box::new(contract)
+}
+
+fn is_timed_out(timeout: &IbcTimeout, block: &BlockInfo) -> bool {
+    let timestamp_expired = timeout
+        .timestamp()
+        .is_some_and(|timestamp| block.time >= timestamp);
+    let height_expired = timeout
+        .block()
+        .is_some_and(|timeout_block| block.height >= timeout_block.height);
+    timestamp_expired || height_expired
+}
+
+/// A chain with passport contract, its cw721, poap and ics721 contracts, and a default channel to the other chain.
+pub struct Chain {
+    pub app: MockApp,
+    pub chain_id: String,
+    pub channel_id: String,
+    pub creator: Addr,
+    pub relayer: Addr,
+    pub code_id_arkite_passport: u64,
+    pub addr_arkite_contract: Addr,
+    pub addr_cw721_contract: Addr,
+    pub addr_poap_contract: Addr,
+    pub addr_ics721_contract: Addr,
+}
+
+impl Chain {
+    pub fn new(chain_id: &str, bech32_prefix: &'static str, channel_id: &str) -> Self {
+        let mut app = mock_app(bech32_prefix);
+        app.update_block(|block| block.chain_id = chain_id.to_string());
+        let code_id_arkite_passport = app.store_code(arkite_passport_contract());
+        let code_id_cw721 = app.store_code(cw721_base_contract());
+        let code_id_ics721 = app.store_code(ics721_harness_contract());
+
+        let creator = app.api().addr_make("arkite");
+        let relayer = app.api().addr_make("relayer");
+        let cw721_instantiate_info = |name: &str| ContractInstantiateInfo {
+            admin: Some(Admin::Instantiator {}),
+            msg: to_json_binary(
+                &Cw721InstantiateMsg::<DefaultOptionalCollectionExtensionMsg> {
+                    name: name.to_string(),
+                    symbol: name.to_string(),
+                    collection_info_extension: None,
+                    minter: None,  // none = sender/arkite is minter
+                    creator: None, // none = sender/arkite is creator
+                    withdraw_address: None,
+                },
+            )
+            .unwrap(),
+            code_id: code_id_cw721,
+            label: name.to_string(),
+        };
+        let addr_arkite_contract = app
+            .instantiate_contract(
+                code_id_arkite_passport,
+                creator.clone(),
+                &InstantiateMsg {
+                    default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+                    escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+                    transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                    cw721_poap: cw721_instantiate_info("poap"),
+                    cw721_base: cw721_instantiate_info("passport"),
+                    ics721_base: ContractInstantiateInfo {
+                        admin: Some(Admin::Address {
+                            addr: creator.to_string(),
+                        }),
+                        msg: to_json_binary(&Ics721InstantiateMsg {
+                            cw721_base_code_id: code_id_cw721,
+                            incoming_proxy: None,
+                            outgoing_proxy: None,
+                            pauser: Some(creator.to_string()),
+                            cw721_admin: None,
+                            cw721_creator: None,
+                            contract_addr_length: None,
+                        })
+                        .unwrap(),
+                        code_id: code_id_ics721,
+                        label: "ics721".to_string(),
+                    },
+                },
+                &[],
+                "arkite passport",
+                Some(creator.to_string()),
+            )
+            .unwrap();
+
+        let query_addr = |app: &MockApp, msg: &QueryMsg| -> Addr {
+            let addr: Option<Addr> = app
+                .wrap()
+                .query_wasm_smart(addr_arkite_contract.clone(), msg)
+                .unwrap();
+            addr.unwrap()
+        };
+        let addr_cw721_contract = query_addr(&app, &QueryMsg::CW721 {});
+        let addr_poap_contract = query_addr(&app, &QueryMsg::Poap {});
+        let addr_ics721_contract = query_addr(&app, &QueryMsg::ICS721 {});
+
+        Self {
+            app,
+            chain_id: chain_id.to_string(),
+            channel_id: channel_id.to_string(),
+            creator,
+            relayer,
+            code_id_arkite_passport,
+            addr_arkite_contract,
+            addr_cw721_contract,
+            addr_poap_contract,
+            addr_ics721_contract,
+        }
+    }
+
+    pub fn port_id(&self) -> String {
+        format!("wasm.{}", self.addr_ics721_contract)
+    }
+
+    pub fn addr_make(&self, name: &str) -> Addr {
+        self.app.api().addr_make(name)
+    }
+
+    /// Removes and returns all packets sent by ics721 on channel, which have not been relayed yet.
+    fn take_sent_packets(&mut self, channel_id: &str) -> Vec<SentPacket> {
+        let mut storage = self.app.contract_storage_mut(&self.addr_ics721_contract);
+        let packets = SENT_PACKETS
+            .range(storage.as_ref(), None, None, Order::Ascending)
+            .map(|item| item.map(|(_, packet)| packet))
+            .filter(|packet| {
+                packet
+                    .as_ref()
+                    .map_or(true, |packet| packet.channel_id == channel_id)
+            })
+            .collect::<StdResult<Vec<SentPacket>>>()
+            .unwrap();
+        for packet in packets.iter() {
+            SENT_PACKETS.remove(storage.as_mut(), packet.sequence);
+        }
+        packets
+    }
+
+    pub fn execute_passport_mint(&mut self, owner: Addr) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            owner,
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::Mint {},
+            &[],
+        )
+    }
+
+    pub fn execute_counter_party_contract(
+        &mut self,
+        addr: String,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            self.creator.clone(),
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::CounterPartyContract { addr },
+            &[],
+        )
+    }
+
+    pub fn execute_set_channel_counterparty(
+        &mut self,
+        channel_id: &str,
+        counterparty_contract: Option<String>,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            self.creator.clone(),
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::SetChannelCounterparty {
+                channel_id: channel_id.to_string(),
+                counterparty_contract,
+            },
+            &[],
+        )
+    }
+
+    pub fn migrate_arkite_contract(
+        &mut self,
+        msg: &MigrateMsg,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.migrate_contract(
+            self.creator.clone(),
+            self.addr_arkite_contract.clone(),
+            msg,
+            self.code_id_arkite_passport,
+        )
+    }
+
+    /// Sets cw721 creator of voucher collections instantiated by ics721 from now on.
+    pub fn migrate_ics721_cw721_creator(
+        &mut self,
+        cw721_creator: String,
+    ) -> Result<AppResponse, anyhow::Error> {
+        let code_id = self
+            .app
+            .wrap()
+            .query_wasm_contract_info(self.addr_ics721_contract.clone())?
+            .code_id;
+        self.app.migrate_contract(
+            self.creator.clone(),
+            self.addr_ics721_contract.clone(),
+            &Ics721MigrateMsg::WithUpdate {
+                cw721_base_code_id: None,
+                incoming_proxy: None,
+                outgoing_proxy: None,
+                pauser: None,
+                cw721_admin: None,
+                cw721_creator: Some(cw721_creator),
+                contract_addr_length: None,
+            },
+            code_id,
+        )
+    }
+
+    /// Sends NFT to passport contract, which forwards it to ics721 with callbacks.
+    pub fn execute_transfer_nft(
+        &mut self,
+        owner: Addr,
+        cw721: Addr,
+        token_id: &str,
+        receiver: String,
+    ) -> Result<AppResponse, anyhow::Error> {
+        let channel_id = self.channel_id.clone();
+        self.execute_transfer_nft_on_channel(owner, cw721, token_id, receiver, &channel_id)
+    }
+
+    /// Like `execute_transfer_nft`, for chains with more than one channel.
+    pub fn execute_transfer_nft_on_channel(
+        &mut self,
+        owner: Addr,
+        cw721: Addr,
+        token_id: &str,
+        receiver: String,
+        channel_id: &str,
+    ) -> Result<AppResponse, anyhow::Error> {
+        let ibc_outgoing_msg = IbcOutgoingMsg {
+            receiver,
+            channel_id: channel_id.to_string(),
+            timeout: IbcTimeout::with_timestamp(
+                self.app
+                    .block_info()
+                    .time
+                    .plus_seconds(TRANSFER_TIMEOUT_SECONDS),
+            ),
+            memo: None,
+        };
+        self.app.execute_contract(
+            owner,
+            cw721,
+            &cw721_base::msg::ExecuteMsg::<
+                DefaultOptionalNftExtensionMsg,
+                DefaultOptionalCollectionExtensionMsg,
+                Empty,
+            >::SendNft {
+                contract: self.addr_arkite_contract.to_string(),
+                token_id: token_id.to_string(),
+                msg: to_json_binary(&ibc_outgoing_msg).unwrap(),
+            },
+            &[],
+        )
+    }
+
+    /// Instantiates a collection other than passport collection, with chain creator as minter and creator.
+    pub fn instantiate_cw721(&mut self, name: &str) -> Addr {
+        let code_id = self
+            .app
+            .wrap()
+            .query_wasm_contract_info(self.addr_cw721_contract.clone())
+            .unwrap()
+            .code_id;
+        self.app
+            .instantiate_contract(
+                code_id,
+                self.creator.clone(),
+                &Cw721InstantiateMsg::<DefaultOptionalCollectionExtensionMsg> {
+                    name: name.to_string(),
+                    symbol: name.to_string(),
+                    collection_info_extension: None,
+                    minter: None,
+                    creator: None,
+                    withdraw_address: None,
+                },
+                &[],
+                name,
+                None,
+            )
+            .unwrap()
+    }
+
+    pub fn execute_cw721_mint(
+        &mut self,
+        cw721: Addr,
+        owner: Addr,
+        token_id: &str,
+        token_uri: &str,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            self.creator.clone(),
+            cw721,
+            &cw721_base::msg::ExecuteMsg::<
+                DefaultOptionalNftExtensionMsg,
+                DefaultOptionalCollectionExtensionMsg,
+                Empty,
+            >::Mint {
+                token_id: token_id.to_string(),
+                owner: owner.to_string(),
+                token_uri: Some(token_uri.to_string()),
+                extension: None,
+            },
+            &[],
+        )
+    }
+
+    pub fn execute_set_collection_policy(
+        &mut self,
+        cw721: Addr,
+        policy: Option<MetadataPolicy>,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            self.creator.clone(),
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::SetCollectionPolicy {
+                cw721: cw721.to_string(),
+                policy,
+            },
+            &[],
+        )
+    }
+
+    pub fn query_collection_policy(&self, cw721: &Addr) -> Option<MetadataPolicy> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.addr_arkite_contract.clone(),
+                &QueryMsg::CollectionPolicy {
+                    cw721: cw721.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    /// Mints native funds to address.
+    pub fn fund(&mut self, addr: &Addr, funds: Vec<Coin>) {
+        self.app.init_modules(|router, _, storage| {
+            router.bank.init_balance(storage, addr, funds).unwrap()
+        });
+    }
+
+    pub fn execute_set_royalty_info(
+        &mut self,
+        royalty_info: Option<RoyaltyInfo>,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            self.creator.clone(),
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::SetRoyaltyInfo { royalty_info },
+            &[],
+        )
+    }
+
+    pub fn execute_set_collection_metadata(
+        &mut self,
+        collection_metadata: Option<CollectionMetadata>,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            self.creator.clone(),
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::SetCollectionMetadata {
+                collection_metadata,
+            },
+            &[],
+        )
+    }
+
+    pub fn execute_set_relayer_pool(
+        &mut self,
+        relayer_pool: Addr,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            self.creator.clone(),
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::UpdateFeeConfig {
+                treasury: None,
+                relayer_pool: Some(relayer_pool.to_string()),
+                relayer_share: None,
+            },
+            &[],
+        )
+    }
+
+    pub fn execute_pay_packet_incentive(
+        &mut self,
+        payer: Addr,
+        cw721: Addr,
+        token_id: &str,
+        funds: &[Coin],
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            payer,
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::PayPacketIncentive {
+                cw721: cw721.to_string(),
+                token_id: token_id.to_string(),
+            },
+            funds,
+        )
+    }
+
+    pub fn query_packet_incentives(&self, cw721: &Addr, token_id: &str) -> Vec<PacketIncentive> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.addr_arkite_contract.clone(),
+                &QueryMsg::PacketIncentives {
+                    cw721: cw721.to_string(),
+                    token_id: token_id.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_outgoing_transfer(
+        &self,
+        cw721: &Addr,
+        token_id: &str,
+    ) -> Option<OutgoingTransfer> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.addr_arkite_contract.clone(),
+                &QueryMsg::OutgoingTransfer {
+                    cw721: cw721.to_string(),
+                    token_id: token_id.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    /// Raw remote location, since multi-test does not support channel queries needed by `RemoteLocation` query.
+    pub fn query_remote_location_record(&self, token_id: &str) -> Option<RemoteLocation> {
+        REMOTE_LOCATIONS
+            .query(
+                &self.app.wrap(),
+                self.addr_arkite_contract.clone(),
+                token_id,
+            )
+            .unwrap()
+    }
+
+    pub fn query_home_location(
+        &self,
+        cw721: &Addr,
+        token_id: &str,
+    ) -> Option<HomeLocationResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.addr_arkite_contract.clone(),
+                &QueryMsg::HomeLocation {
+                    cw721: cw721.to_string(),
+                    token_id: token_id.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    pub fn query_balance(&self, addr: &Addr, denom: &str) -> u128 {
+        self.app
+            .wrap()
+            .query_balance(addr, denom)
+            .unwrap()
+            .amount
+            .u128()
+    }
+
+    pub fn query_counters(&self) -> Counters {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.addr_arkite_contract.clone(), &QueryMsg::Counters {})
+            .unwrap()
+    }
+
+    /// Voucher collection created by ics721 for given class id.
+    pub fn query_nft_contract(&self, class_id: String) -> Option<Addr> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.addr_ics721_contract.clone(),
+                &ics721::msg::QueryMsg::NftContract { class_id },
+            )
+            .unwrap()
+    }
+
+    pub fn query_cw721_num_tokens(&self, cw721: Addr) -> u64 {
+        let res: NumTokensResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                cw721,
+                &cw721_base::msg::QueryMsg::<
+                    DefaultOptionalNftExtension,
+                    DefaultOptionalCollectionExtension,
+                    Empty,
+                >::NumTokens {},
+            )
+            .unwrap();
+        res.count
+    }
+
+    pub fn query_cw721_collection_info(
+        &self,
+        cw721: Addr,
+    ) -> CollectionInfoAndExtensionResponse<DefaultOptionalCollectionExtension> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                cw721,
+                &cw721_base::msg::QueryMsg::<
+                    DefaultOptionalNftExtension,
+                    DefaultOptionalCollectionExtension,
+                    Empty,
+                >::GetCollectionInfoAndExtension {},
+            )
+            .unwrap()
+    }
+
+    pub fn query_cw721_creator_ownership(&self, cw721: Addr) -> Ownership<Addr> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                cw721,
+                &cw721_base::msg::QueryMsg::<
+                    DefaultOptionalNftExtension,
+                    DefaultOptionalCollectionExtension,
+                    Empty,
+                >::GetCreatorOwnership {},
+            )
+            .unwrap()
+    }
+
+    pub fn query_cw721_all_nft_info(
+        &self,
+        cw721: Addr,
+        token_id: &str,
+    ) -> StdResult<AllNftInfoResponse<DefaultOptionalNftExtension>> {
+        self.app.wrap().query_wasm_smart(
+            cw721,
+            &cw721_base::msg::QueryMsg::<
+                DefaultOptionalNftExtension,
+                DefaultOptionalCollectionExtension,
+                Empty,
+            >::AllNftInfo {
+                token_id: token_id.to_string(),
+                include_expired: None,
+            },
+        )
+    }
+}
+
+/// Relays packets sent on `src` chain to `dest` chain, and acks or timeouts back to `src` chain.
+fn relay(src: &mut Chain, dest: &mut Chain) -> Vec<RelayedPacket> {
+    let (src_channel, dest_channel) = (src.channel_id.clone(), dest.channel_id.clone());
+    relay_on_channel(src, &src_channel, dest, &dest_channel)
+}
+
+/// Like `relay`, for packets on given channel between `src` and `dest` chain.
+fn relay_on_channel(
+    src: &mut Chain,
+    src_channel: &str,
+    dest: &mut Chain,
+    dest_channel: &str,
+) -> Vec<RelayedPacket> {
+    let mut relayed_packets = vec![];
+    for sent_packet in src.take_sent_packets(src_channel) {
+        let packet = IbcPacket::new(
+            sent_packet.data,
+            IbcEndpoint {
+                port_id: src.port_id(),
+                channel_id: src_channel.to_string(),
+            },
+            IbcEndpoint {
+                port_id: dest.port_id(),
+                channel_id: dest_channel.to_string(),
+            },
+            sent_packet.sequence,
+            sent_packet.timeout,
+        );
+
+        if is_timed_out(&packet.timeout, &dest.app.block_info()) {
+            let res = src
+                .app
+                .execute_contract(
+                    src.relayer.clone(),
+                    src.addr_ics721_contract.clone(),
+                    &RelayMsg::TimeoutPacket {
+                        packet: packet.clone(),
+                    },
+                    &[],
+                )
+                .unwrap();
+            relayed_packets.push(RelayedPacket {
+                src_chain: src.chain_id.clone(),
+                packet,
+                outcome: PacketOutcome::Timeout,
+                events: res.events,
+            });
+            continue;
+        }
+
+        let res = dest
+            .app
+            .execute_contract(
+                dest.relayer.clone(),
+                dest.addr_ics721_contract.clone(),
+                &RelayMsg::ReceivePacket {
+                    packet: packet.clone(),
+                },
+                &[],
+            )
+            .unwrap();
+        let ack = res.data.expect("ics721 returns an ack on receive");
+        let mut events = res.events;
+        let res = src
+            .app
+            .execute_contract(
+                src.relayer.clone(),
+                src.addr_ics721_contract.clone(),
+                &RelayMsg::AckPacket {
+                    packet: packet.clone(),
+                    ack: ack.clone(),
+                },
+                &[],
+            )
+            .unwrap();
+        events.extend(res.events);
+        relayed_packets.push(RelayedPacket {
+            src_chain: src.chain_id.clone(),
+            packet,
+            outcome: PacketOutcome::Ack(from_json(ack).unwrap()),
+            events,
+        });
+    }
+    relayed_packets
+}
+
+/// Two chains, where passport contracts are counterparties of each other.
+pub struct InterchainTest {
+    pub chain_a: Chain,
+    pub chain_b: Chain,
+}
+
+impl Default for InterchainTest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InterchainTest {
+    pub fn new() -> Self {
+        let mut chain_a = Chain::new("osmo-test-5", "osmo", "channel-0");
+        let mut chain_b = Chain::new("elgafar-1", "stars", "channel-1");
+        chain_a
+            .execute_counter_party_contract(chain_b.addr_arkite_contract.to_string())
+            .unwrap();
+        chain_b
+            .execute_counter_party_contract(chain_a.addr_arkite_contract.to_string())
+            .unwrap();
+        Self { chain_a, chain_b }
+    }
+
+    /// Relays packets in both directions, until there are no more packets left.
+    pub fn relay_packets(&mut self) -> Vec<RelayedPacket> {
+        let mut relayed_packets = vec![];
+        loop {
+            let mut relayed = relay(&mut self.chain_a, &mut self.chain_b);
+            relayed.extend(relay(&mut self.chain_b, &mut self.chain_a));
+            if relayed.is_empty() {
+                return relayed_packets;
+            }
+            relayed_packets.extend(relayed);
+        }
+    }
+
+    /// Class id of voucher collection on chain B, for a collection on chain A.
+    pub fn class_id_on_chain_b(&self, cw721_on_chain_a: &Addr) -> String {
+        format!(
+            "{}/{}/{}",
+            self.chain_b.port_id(),
+            self.chain_b.channel_id,
+            cw721_on_chain_a
+        )
+    }
+}
+
+/// Channel of chain B to chain C, next to its channel to chain A.
+pub const CHANNEL_B_TO_C: &str = "channel-2";
+
+/// Three chains A - B - C, where chain B has a channel to each of the others, with passport contracts as counterparties.
+/// Passports minted on chain A reach chain C via chain B.
+pub struct MultiHopTest {
+    pub chain_a: Chain,
+    pub chain_b: Chain,
+    pub chain_c: Chain,
+}
+
+impl Default for MultiHopTest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MultiHopTest {
+    pub fn new() -> Self {
+        let InterchainTest {
+            chain_a,
+            mut chain_b,
+        } = InterchainTest::new();
+        let mut chain_c = Chain::new("uni-6", "juno", "channel-3");
+        chain_b
+            .execute_set_channel_counterparty(
+                CHANNEL_B_TO_C,
+                Some(chain_c.addr_arkite_contract.to_string()),
+            )
+            .unwrap();
+        chain_c
+            .execute_counter_party_contract(chain_b.addr_arkite_contract.to_string())
+            .unwrap();
+        Self {
+            chain_a,
+            chain_b,
+            chain_c,
+        }
+    }
+
+    /// Relays packets on all channels, until there are no more packets left.
+    pub fn relay_packets(&mut self) -> Vec<RelayedPacket> {
+        let channel_c = self.chain_c.channel_id.clone();
+        let mut relayed_packets = vec![];
+        loop {
+            let mut relayed = relay(&mut self.chain_a, &mut self.chain_b);
+            relayed.extend(relay(&mut self.chain_b, &mut self.chain_a));
+            relayed.extend(relay_on_channel(
+                &mut self.chain_b,
+                CHANNEL_B_TO_C,
+                &mut self.chain_c,
+                &channel_c,
+            ));
+            relayed.extend(relay_on_channel(
+                &mut self.chain_c,
+                &channel_c,
+                &mut self.chain_b,
+                CHANNEL_B_TO_C,
+            ));
+            if relayed.is_empty() {
+                return relayed_packets;
+            }
+            relayed_packets.extend(relayed);
+        }
+    }
+
+    /// Relays packets of chain C on given channel to given channel of chain B, e.g. a second channel between both chains.
+    pub fn relay_packets_on_channel(
+        &mut self,
+        channel_c: &str,
+        channel_b: &str,
+    ) -> Vec<RelayedPacket> {
+        relay_on_channel(&mut self.chain_c, channel_c, &mut self.chain_b, channel_b)
+    }
+
+    /// Class id of voucher collection on chain B, for a collection on chain A.
+    pub fn class_id_on_chain_b(&self, cw721_on_chain_a: &Addr) -> String {
+        format!(
+            "{}/{}/{}",
+            self.chain_b.port_id(),
+            self.chain_b.channel_id,
+            cw721_on_chain_a
+        )
+    }
+
+    /// Class id of voucher collection on chain C, for a collection on chain A bridged via chain B.
+    pub fn class_id_on_chain_c(&self, cw721_on_chain_a: &Addr) -> String {
+        format!(
+            "{}/{}/{}",
+            self.chain_c.port_id(),
+            self.chain_c.channel_id,
+            self.class_id_on_chain_b(cw721_on_chain_a)
+        )
+    }
+}
@@ -1,7 +1,25 @@
-use anyhow::Result;
+use super::common::{
+    arkite_passport_contract, cw721_base_contract, ics721_contract, incoming_proxy_contract,
+    outgoing_proxy_contract,
+};
+use crate::{
+    error::ContractError,
+    execute::royalty_info_from_traits,
+    msg::{
+        CallbackData, ChannelFee, CollectedFeesResponse, CollectionPolicyResponse,
+        ContractInfoResponse, ExecuteMsg, FeesResponse, InstantiateMsg, LinkedContracts,
+        MigrateMsg, PauseStatusResponse, QueryMsg, RateLimitQuotaResponse,
+    },
+    state::{
+        CollectionMetadata, Config, Counters, FeeConfig, MetadataPolicy, PauseState,
+        PausedOperations, PoapPolicy, RateLimitPolicy, RemoteLocation, RoyaltyInfo, TimeoutPolicy,
+        CONFIG, COUNTERS, FEE_CONFIG, PAUSE, REMOTE_LOCATIONS,
+    },
+    upgrades::legacy,
+};
 use cosmwasm_std::{
-    instantiate2_address, to_json_binary, Addr, Api, CanonicalAddr, DepsMut, Empty, Env, GovMsg,
-    IbcTimeout, MemoryStorage, Reply, Response, Storage, Timestamp,
+    coin, coins, to_json_binary, Addr, Coin, Decimal, Empty, IbcTimeout, StdError, StdResult,
+    Timestamp, Uint128,
 };
 use cw721_base::{
     msg::{AllNftInfoResponse, InstantiateMsg as Cw721InstantiateMsg, NumTokensResponse},
@@ -10,23 +28,14 @@ use cw721_base::{
     DefaultOptionalNftExtension, DefaultOptionalNftExtensionMsg, Ownership,
 };
 use cw_cii::{Admin, ContractInstantiateInfo};
-use cw_multi_test::{
-    addons::MockApiBech32, AddressGenerator, App, AppBuilder, AppResponse, BankKeeper, Contract,
-    ContractWrapper, DistributionKeeper, Executor, FailingModule, IbcAcceptingModule, Router,
-    StakeKeeper, StargateFailing, WasmKeeper,
-};
-use ics721::{ClassId, ContractError as Ics721ContractError, NonFungibleTokenPacketData, TokenId};
+use cw_ics721_callback::multi_test::{mock_app, MockApp};
+use cw_multi_test::{AppResponse, Executor};
+use cw_ownable::OwnershipError;
+use ics721::{ClassId, NonFungibleTokenPacketData, TokenId};
 use ics721_types::{
     ibc_types::IbcOutgoingMsg,
     types::{Ics721AckCallbackMsg, Ics721ReceiveCallbackMsg, Ics721Status},
 };
-use sha2::{digest::Update, Digest, Sha256};
-
-use crate::{
-    error::ContractError,
-    execute,
-    msg::{CallbackData, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg},
-};
 
 use ics721::msg::{InstantiateMsg as Ics721InstantiateMsg, MigrateMsg as Ics721MigrateMsg};
 
@@ -39,76 +48,7 @@ const COUNTERPARTY_CONTRACT: &str = "counterparty_contract";
 const DEFAULT_TOKEN_URI: &str = "ipfs://interchain.passport";
 const ESCROWED_TOKEN_URI: &str = "ipfs://interchain.escrowed";
 const TRANSFERRED_TOKEN_URI: &str = "ipfs://interchain.transferred";
-
-type MockRouter = Router<
-    BankKeeper,
-    FailingModule<Empty, Empty, Empty>,
-    WasmKeeper<Empty, Empty>,
-    StakeKeeper,
-    DistributionKeeper,
-    IbcAcceptingModule,
-    FailingModule<GovMsg, Empty, Empty>,
-    StargateFailing,
->;
-
-type MockApp = App<
-    BankKeeper,
-    MockApiBech32,
-    MemoryStorage,
-    FailingModule<Empty, Empty, Empty>,
-    WasmKeeper<Empty, Empty>,
-    StakeKeeper,
-    DistributionKeeper,
-    IbcAcceptingModule,
->;
-
-#[derive(Default)]
-pub struct MockAddressGenerator;
-
-impl AddressGenerator for MockAddressGenerator {
-    fn contract_address(
-        &self,
-        api: &dyn Api,
-        _storage: &mut dyn Storage,
-        code_id: u64,
-        instance_id: u64,
-    ) -> Result<Addr> {
-        let canonical_addr = Self::instantiate_address(code_id, instance_id);
-        Ok(Addr::unchecked(api.addr_humanize(&canonical_addr)?))
-    }
-
-    fn predictable_contract_address(
-        &self,
-        api: &dyn Api,
-        _storage: &mut dyn Storage,
-        _code_id: u64,
-        _instance_id: u64,
-        checksum: &[u8],
-        creator: &CanonicalAddr,
-        salt: &[u8],
-    ) -> Result<Addr> {
-        let canonical_addr = instantiate2_address(checksum, creator, salt)?;
-        Ok(Addr::unchecked(api.addr_humanize(&canonical_addr)?))
-    }
-}
-
-impl MockAddressGenerator {
-    // non-predictable contract address generator, see `BuildContractAddressClassic`
-    // implementation in wasmd: https://github.com/CosmWasm/wasmd/blob/main/x/wasm/keeper/addresses.go#L35-L42
-    fn instantiate_address(code_id: u64, instance_id: u64) -> CanonicalAddr {
-        let mut key = Vec::<u8>::new();
-        key.extend_from_slice(b"wasm\0");
-        key.extend_from_slice(&code_id.to_be_bytes());
-        key.extend_from_slice(&instance_id.to_be_bytes());
-        let module = Sha256::digest("module".as_bytes());
-        Sha256::new()
-            .chain(module)
-            .chain(key)
-            .finalize()
-            .to_vec()
-            .into()
-    }
-}
+const FEE_DENOM: &str = "uark";
 
 struct Test {
     app: MockApp,
@@ -120,20 +60,14 @@ struct Test {
     addr_poap_contract: Addr,
     addr_cw721_contract: Addr,
     addr_ics721_contract: Addr,
+    addr_outgoing_proxy_contract: Addr,
+    addr_incoming_proxy_contract: Addr,
 }
 
-fn no_init(_router: &mut MockRouter, _api: &dyn Api, _storage: &mut dyn Storage) {}
-
 impl Test {
     /// Test setup with optional pauser and proxy contracts.
     fn new() -> Self {
-        let mut app = AppBuilder::new()
-            .with_wasm::<WasmKeeper<Empty, Empty>>(
-                WasmKeeper::new().with_address_generator(MockAddressGenerator),
-            )
-            .with_ibc(IbcAcceptingModule::default())
-            .with_api(MockApiBech32::new(BECH32_PREFIX_HRP))
-            .build(no_init);
+        let mut app = mock_app(BECH32_PREFIX_HRP);
         let code_id_arkite_passport = app.store_code(arkite_passport_contract());
         let code_id_cw721 = app.store_code(cw721_base_contract());
         let code_id_ics721 = app.store_code(ics721_contract());
@@ -203,20 +137,23 @@ impl Test {
             )
             .unwrap();
 
-        let addr_poap_contract = app
+        let addr_poap_contract: Option<Addr> = app
             .wrap()
             .query_wasm_smart(addr_arkite_contract.clone(), &QueryMsg::Poap {})
             .unwrap();
+        let addr_poap_contract = addr_poap_contract.unwrap();
 
-        let addr_cw721_contract = app
+        let addr_cw721_contract: Option<Addr> = app
             .wrap()
             .query_wasm_smart(addr_arkite_contract.clone(), &QueryMsg::CW721 {})
             .unwrap();
+        let addr_cw721_contract = addr_cw721_contract.unwrap();
 
-        let addr_ics721_contract: Addr = app
+        let addr_ics721_contract: Option<Addr> = app
             .wrap()
             .query_wasm_smart(addr_arkite_contract.clone(), &QueryMsg::ICS721 {})
             .unwrap();
+        let addr_ics721_contract = addr_ics721_contract.unwrap();
 
         let code_id_outgoing_proxy = app.store_code(outgoing_proxy_contract());
         let addr_outgoing_proxy_contract = app
@@ -276,6 +213,8 @@ impl Test {
             addr_poap_contract,
             addr_cw721_contract,
             addr_ics721_contract,
+            addr_outgoing_proxy_contract,
+            addr_incoming_proxy_contract,
         };
         test.execute_counter_party_contract(COUNTERPARTY_CONTRACT.to_string())
             .unwrap();
@@ -312,28 +251,28 @@ impl Test {
             .unwrap()
     }
 
-    fn query_poap(&mut self) -> Addr {
+    fn query_poap(&mut self) -> Option<Addr> {
         self.app
             .wrap()
             .query_wasm_smart(self.addr_arkite_contract.clone(), &QueryMsg::Poap {})
             .unwrap()
     }
 
-    fn query_cw721(&mut self) -> Addr {
+    fn query_cw721(&mut self) -> Option<Addr> {
         self.app
             .wrap()
             .query_wasm_smart(self.addr_arkite_contract.clone(), &QueryMsg::CW721 {})
             .unwrap()
     }
 
-    fn query_ics721(&mut self) -> Addr {
+    fn query_ics721(&mut self) -> Option<Addr> {
         self.app
             .wrap()
             .query_wasm_smart(self.addr_arkite_contract.clone(), &QueryMsg::ICS721 {})
             .unwrap()
     }
 
-    fn query_counter_party_contract(&mut self) -> String {
+    fn query_counter_party_contract(&mut self) -> Option<String> {
         self.app
             .wrap()
             .query_wasm_smart(
@@ -343,6 +282,25 @@ impl Test {
             .unwrap()
     }
 
+    fn query_channel_counterparty(&mut self, channel_id: &str) -> Option<String> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.addr_arkite_contract.clone(),
+                &QueryMsg::ChannelCounterparty {
+                    channel_id: channel_id.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    fn query_counters(&mut self) -> Counters {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.addr_arkite_contract.clone(), &QueryMsg::Counters {})
+            .unwrap()
+    }
+
     fn query_cw721_num_tokens(&mut self, cw721: Addr) -> NumTokensResponse {
         self.app
             .wrap()
@@ -427,6 +385,23 @@ impl Test {
         )
     }
 
+    fn execute_set_channel_counterparty(
+        &mut self,
+        sender: Addr,
+        channel_id: &str,
+        counterparty_contract: Option<String>,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            sender,
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::SetChannelCounterparty {
+                channel_id: channel_id.to_string(),
+                counterparty_contract,
+            },
+            &[],
+        )
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn execute_ack_callback(
         &mut self,
@@ -548,87 +523,395 @@ impl Test {
         )
     }
 
-    fn migrate_arkite_contract(
+    /// Pretend contract has been instantiated with another name or version, by overriding cw2 contract info.
+    fn set_contract_version(&mut self, contract_name: &str, contract_version: &str) {
+        let mut storage = self.app.contract_storage_mut(&self.addr_arkite_contract);
+        cw2::set_contract_version(storage.as_mut(), contract_name, contract_version).unwrap();
+    }
+
+    /// Pretend contract is in state of an older version, by replacing config and ownership with legacy storage.
+    fn set_legacy_state(&mut self, contract_version: &str) {
+        self.set_contract_version("crates.io:arkite-passport", contract_version);
+        let mut storage = self.app.contract_storage_mut(&self.addr_arkite_contract);
+        let config = CONFIG.load(storage.as_ref()).unwrap();
+        let version = semver::Version::parse(contract_version).unwrap();
+        // no pause state and fee config prior to v0.5.0
+        PAUSE.remove(storage.as_mut());
+        FEE_CONFIG.remove(storage.as_mut());
+        if version >= semver::Version::new(0, 4, 0) {
+            legacy::CONFIG_V0_4_0
+                .save(
+                    storage.as_mut(),
+                    &legacy::ConfigV0_4_0 {
+                        default_token_uri: config.default_token_uri,
+                        escrowed_token_uri: config.escrowed_token_uri,
+                        transferred_token_uri: config.transferred_token_uri,
+                        addr_cw721: config.addr_cw721,
+                        addr_poap: config.addr_poap,
+                        addr_ics721: config.addr_ics721,
+                        counterparty_contract: config.counterparty_contract,
+                        poap_policy: config.poap_policy,
+                        timeout_policy: config.timeout_policy,
+                    },
+                )
+                .unwrap();
+            return;
+        }
+        // no ownership prior to v0.4.0
+        storage.remove(b"ownership");
+        if version >= semver::Version::new(0, 3, 0) {
+            legacy::CONFIG_V0_3_0
+                .save(
+                    storage.as_mut(),
+                    &legacy::ConfigV0_3_0 {
+                        default_token_uri: config.default_token_uri,
+                        escrowed_token_uri: config.escrowed_token_uri,
+                        transferred_token_uri: config.transferred_token_uri,
+                        addr_cw721: config.addr_cw721,
+                        addr_poap: config.addr_poap,
+                        addr_ics721: config.addr_ics721,
+                        counterparty_contract: config.counterparty_contract,
+                    },
+                )
+                .unwrap();
+            return;
+        }
+        legacy::DEFAULT_TOKEN_URI
+            .save(storage.as_mut(), &config.default_token_uri)
+            .unwrap();
+        legacy::ESCROWED_TOKEN_URI
+            .save(storage.as_mut(), &config.escrowed_token_uri)
+            .unwrap();
+        legacy::TRANSFERRED_TOKEN_URI
+            .save(storage.as_mut(), &config.transferred_token_uri)
+            .unwrap();
+        legacy::ADDR_CW721
+            .save(storage.as_mut(), &config.addr_cw721.unwrap())
+            .unwrap();
+        legacy::ADDR_POAP
+            .save(storage.as_mut(), &config.addr_poap.unwrap())
+            .unwrap();
+        legacy::ADDR_ICS721
+            .save(storage.as_mut(), &config.addr_ics721.unwrap())
+            .unwrap();
+        legacy::COUNTERPARTY_CONTRACT
+            .save(storage.as_mut(), &config.counterparty_contract.unwrap())
+            .unwrap();
+        CONFIG.remove(storage.as_mut());
+    }
+
+    fn query_ownership(&mut self) -> Ownership<Addr> {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.addr_arkite_contract.clone(), &QueryMsg::Ownership {})
+            .unwrap()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn execute_update_config(
         &mut self,
+        sender: Addr,
         default_token_uri: Option<String>,
         escrowed_token_uri: Option<String>,
         transferred_token_uri: Option<String>,
+        poap_policy: Option<PoapPolicy>,
+        timeout_policy: Option<TimeoutPolicy>,
+        rate_limit_policy: Option<RateLimitPolicy>,
     ) -> Result<AppResponse, anyhow::Error> {
-        self.app.migrate_contract(
-            self.creator.clone(),
+        self.app.execute_contract(
+            sender,
             self.addr_arkite_contract.clone(),
-            &MigrateMsg::WithUpdate {
+            &ExecuteMsg::UpdateConfig {
                 default_token_uri,
                 escrowed_token_uri,
                 transferred_token_uri,
+                poap_policy,
+                timeout_policy,
+                rate_limit_policy,
             },
-            self.code_id_arkite_passport,
+            &[],
         )
     }
-}
 
-fn arkite_passport_contract() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(execute::execute, execute::instantiate, execute::query)
-        .with_reply(execute::reply)
-        .with_migrate(execute::migrate);
-    Box::new(contract)
-}
+    fn execute_resync_metadata(
+        &mut self,
+        sender: Addr,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            sender,
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::ResyncMetadata { start_after, limit },
+            &[],
+        )
+    }
 
-fn cw721_base_contract() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(
-        cw721_base::entry::execute,
-        cw721_base::entry::instantiate,
-        cw721_base::entry::query,
-    );
-    Box::new(contract)
-}
+    fn execute_set_pauser(
+        &mut self,
+        sender: Addr,
+        pauser: Option<String>,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            sender,
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::SetPauser { pauser },
+            &[],
+        )
+    }
+
+    fn execute_pause(
+        &mut self,
+        sender: Addr,
+        operations: PausedOperations,
+        unpause_at_height: Option<u64>,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            sender,
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::Pause {
+                operations,
+                unpause_at_height,
+            },
+            &[],
+        )
+    }
 
-fn ics721_contract() -> Box<dyn Contract<Empty>> {
-    // need to wrap method in function for testing
-    fn ibc_reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, Ics721ContractError> {
-        ics721_base::reply(deps, env, reply)
+    fn execute_unpause(&mut self, sender: Addr) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            sender,
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::Unpause {},
+            &[],
+        )
     }
 
-    let contract = ContractWrapper::new(
-        ics721_base::execute,
-        ics721_base::instantiate,
-        ics721_base::query,
-    )
-    .with_migrate(ics721_base::migrate)
-    .with_reply(ibc_reply);
-    Box::new(contract)
-}
+    fn execute_update_fee_config(
+        &mut self,
+        sender: Addr,
+        treasury: Option<String>,
+        relayer_pool: Option<String>,
+        relayer_share: Option<Decimal>,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            sender,
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::UpdateFeeConfig {
+                treasury,
+                relayer_pool,
+                relayer_share,
+            },
+            &[],
+        )
+    }
 
-fn incoming_proxy_contract() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(
-        cw_ics721_incoming_proxy_base::contract::execute,
-        cw_ics721_incoming_proxy_base::contract::instantiate,
-        cw_ics721_incoming_proxy_base::contract::query,
-    );
-    Box::new(contract)
-}
+    fn execute_set_channel_fee(
+        &mut self,
+        sender: Addr,
+        channel_id: &str,
+        fee: Option<Coin>,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            sender,
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::SetChannelFee {
+                channel_id: channel_id.to_string(),
+                fee,
+            },
+            &[],
+        )
+    }
 
-fn outgoing_proxy_contract() -> Box<dyn Contract<Empty>> {
-    let contract = ContractWrapper::new(
-        cw_ics721_outgoing_proxy_rate_limit::contract::execute,
-        cw_ics721_outgoing_proxy_rate_limit::contract::instantiate,
-        cw_ics721_outgoing_proxy_rate_limit::contract::query,
-    )
-    .with_reply(cw_ics721_outgoing_proxy_rate_limit::contract::reply);
-    Box::new(contract)
+    fn execute_set_royalty_info(
+        &mut self,
+        sender: Addr,
+        royalty_info: Option<RoyaltyInfo>,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            sender,
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::SetRoyaltyInfo { royalty_info },
+            &[],
+        )
+    }
+
+    fn execute_set_collection_metadata(
+        &mut self,
+        sender: Addr,
+        collection_metadata: Option<CollectionMetadata>,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            sender,
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::SetCollectionMetadata {
+                collection_metadata,
+            },
+            &[],
+        )
+    }
+
+    fn execute_set_collection_policy(
+        &mut self,
+        sender: Addr,
+        cw721: Addr,
+        policy: Option<MetadataPolicy>,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            sender,
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::SetCollectionPolicy {
+                cw721: cw721.to_string(),
+                policy,
+            },
+            &[],
+        )
+    }
+
+    fn query_collection_policy(&mut self, cw721: Addr) -> Option<MetadataPolicy> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.addr_arkite_contract.clone(),
+                &QueryMsg::CollectionPolicy {
+                    cw721: cw721.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    fn query_collection_policies(&mut self) -> Vec<CollectionPolicyResponse> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.addr_arkite_contract.clone(),
+                &QueryMsg::CollectionPolicies {},
+            )
+            .unwrap()
+    }
+
+    fn execute_transfer(
+        &mut self,
+        sender: Addr,
+        token_id: &str,
+        funds: &[Coin],
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            sender,
+            self.addr_arkite_contract.clone(),
+            &ExecuteMsg::Transfer {
+                cw721: self.addr_cw721_contract.to_string(),
+                token_id: token_id.to_string(),
+                msg: IbcOutgoingMsg {
+                    receiver: self.other_chain_wallet.to_string(),
+                    channel_id: WHITELISTED_CHANNEL.to_string(),
+                    timeout: IbcTimeout::with_timestamp(Timestamp::from_seconds(0)),
+                    memo: None,
+                },
+            },
+            funds,
+        )
+    }
+
+    fn query_balance(&self, addr: &Addr) -> Uint128 {
+        self.app
+            .wrap()
+            .query_balance(addr, FEE_DENOM)
+            .unwrap()
+            .amount
+    }
+
+    fn query_rate_limit_quota(
+        &mut self,
+        sender: &Addr,
+        channel_id: &str,
+    ) -> RateLimitQuotaResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.addr_arkite_contract.clone(),
+                &QueryMsg::RateLimitQuota {
+                    sender: sender.to_string(),
+                    channel_id: channel_id.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    fn query_pause_status(&mut self) -> PauseStatusResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.addr_arkite_contract.clone(), &QueryMsg::PauseStatus {})
+            .unwrap()
+    }
+
+    fn query_contract_info(&mut self) -> ContractInfoResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.addr_arkite_contract.clone(),
+                &QueryMsg::ContractInfo {},
+            )
+            .unwrap()
+    }
+
+    fn query_config(&mut self) -> Config {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.addr_arkite_contract.clone(), &QueryMsg::Config {})
+            .unwrap()
+    }
+
+    fn migrate_arkite_contract(
+        &mut self,
+        default_token_uri: Option<String>,
+        escrowed_token_uri: Option<String>,
+        transferred_token_uri: Option<String>,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.migrate_contract(
+            self.creator.clone(),
+            self.addr_arkite_contract.clone(),
+            &MigrateMsg::WithUpdate {
+                default_token_uri,
+                escrowed_token_uri,
+                transferred_token_uri,
+            },
+            self.code_id_arkite_passport,
+        )
+    }
 }
 
 #[test]
 fn test_instantiate() {
     let mut test = Test::new();
 
+    let config = test.query_config();
+    assert_eq!(
+        config,
+        Config {
+            default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+            escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+            transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+            addr_cw721: Some(test.addr_cw721_contract.clone()),
+            addr_poap: Some(test.addr_poap_contract.clone()),
+            addr_ics721: Some(test.addr_ics721_contract.clone()),
+            counterparty_contract: Some(COUNTERPARTY_CONTRACT.to_string()),
+            poap_policy: PoapPolicy::Always,
+            timeout_policy: TimeoutPolicy::default(),
+            rate_limit_policy: RateLimitPolicy::default(),
+            royalty_info: None,
+            collection_metadata: None,
+        }
+    );
+    // creator is owner
+    assert_eq!(test.query_ownership().owner, Some(test.creator.clone()));
+
     // check stores are properly initialized
     let poap = test.query_poap();
-    assert_eq!(poap, test.addr_poap_contract);
+    assert_eq!(poap, Some(test.addr_poap_contract.clone()));
     let cw721 = test.query_cw721();
-    assert_eq!(cw721, test.addr_cw721_contract);
+    assert_eq!(cw721, Some(test.addr_cw721_contract.clone()));
     let ics721 = test.query_ics721();
-    assert_eq!(ics721, test.addr_ics721_contract);
+    assert_eq!(ics721, Some(test.addr_ics721_contract.clone()));
     let supply = test
         .query_cw721_num_tokens(test.addr_cw721_contract.clone())
         .count;
@@ -657,6 +940,36 @@ fn test_instantiate() {
 fn test_execute_counter_party_contract() {
     let mut test = Test::new();
 
+    // pretend counterparty contract is not set yet
+    CONFIG
+        .update(
+            test.app
+                .contract_storage_mut(&test.addr_arkite_contract)
+                .as_mut(),
+            |mut config| -> StdResult<_> {
+                config.counterparty_contract = None;
+                Ok(config)
+            },
+        )
+        .unwrap();
+    assert_eq!(test.query_counter_party_contract(), None);
+
+    // only owner
+    let err: ContractError = test
+        .app
+        .execute_contract(
+            test.nft_owner.clone(),
+            test.addr_arkite_contract.clone(),
+            &ExecuteMsg::CounterPartyContract {
+                addr: "other".to_string(),
+            },
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+
     // mint and send nft
     test.execute_counter_party_contract(COUNTERPARTY_CONTRACT.to_string())
         .unwrap();
@@ -664,20 +977,70 @@ fn test_execute_counter_party_contract() {
     // assert results
     // - nft owned by ics721
     let counter_party_contract = test.query_counter_party_contract();
-    assert_eq!(counter_party_contract, COUNTERPARTY_CONTRACT.to_string());
+    assert_eq!(
+        counter_party_contract,
+        Some(COUNTERPARTY_CONTRACT.to_string())
+    );
 }
 
 #[test]
-fn test_mint() {
+fn test_channel_counterparty() {
     let mut test = Test::new();
 
-    test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+    // case 1: channels without counterparty use counterparty contract of config
+    assert_eq!(
+        test.query_channel_counterparty("channel-1"),
+        Some(COUNTERPARTY_CONTRACT.to_string())
+    );
 
-    // assert results
-    let supply = test
-        .query_cw721_num_tokens(test.addr_cw721_contract.clone())
-        .count;
-    assert_eq!(supply, 1);
+    // case 2: only owner
+    let err: ContractError = test
+        .execute_set_channel_counterparty(
+            test.nft_owner.clone(),
+            "channel-1",
+            Some("other".to_string()),
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+
+    // case 3: channel counterparty takes precedence, other channels are unchanged
+    test.execute_set_channel_counterparty(
+        test.creator.clone(),
+        "channel-1",
+        Some("other".to_string()),
+    )
+    .unwrap();
+    assert_eq!(
+        test.query_channel_counterparty("channel-1"),
+        Some("other".to_string())
+    );
+    assert_eq!(
+        test.query_channel_counterparty("channel-2"),
+        Some(COUNTERPARTY_CONTRACT.to_string())
+    );
+
+    // case 4: removed channel counterparty falls back to config
+    test.execute_set_channel_counterparty(test.creator.clone(), "channel-1", None)
+        .unwrap();
+    assert_eq!(
+        test.query_channel_counterparty("channel-1"),
+        Some(COUNTERPARTY_CONTRACT.to_string())
+    );
+}
+
+#[test]
+fn test_mint() {
+    let mut test = Test::new();
+
+    test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+
+    // assert results
+    let supply = test
+        .query_cw721_num_tokens(test.addr_cw721_contract.clone())
+        .count;
+    assert_eq!(supply, 1);
 
     let all_nft_info =
         test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
@@ -724,28 +1087,118 @@ fn test_receive_callback() {
                     default_token_uri: DEFAULT_TOKEN_URI.to_string(),
                     escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
                     transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                    royalty_info: None,
+                    collection_metadata: None,
+                    metadata_policy: MetadataPolicy::Passport,
                 },
                 "1".to_string(),
                 test.nft_owner.to_string(),
-                test.other_chain_wallet.to_string(),
+                COUNTERPARTY_CONTRACT.to_string(),
             )
             .unwrap_err()
             .downcast()
             .unwrap();
         assert_eq!(err, ContractError::UnauthorizedCallback {});
     }
+    // assert packet not sent by counterparty passport, e.g. nft sent directly to ics721 with forged callback data
+    {
+        let mut test = Test::new();
+        let err: ContractError = test
+            .execute_receive_callback(
+                test.addr_ics721_contract.clone(),
+                ClassId::new("some/class/id"),
+                CallbackData {
+                    sender: test.other_chain_wallet.to_string(),
+                    token_id: "1".to_string(),
+                    default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+                    escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+                    transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                    royalty_info: None,
+                    collection_metadata: None,
+                    metadata_policy: MetadataPolicy::PassThrough,
+                },
+                "1".to_string(),
+                test.nft_owner.to_string(),
+                test.other_chain_wallet.to_string(),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::UnauthorizedPacketSender {
+                sender: test.other_chain_wallet.to_string(),
+            }
+        );
+    }
+    // assert packet sent by counterparty of another channel, collection not received on channel of that counterparty
+    {
+        let mut test = Test::new();
+        test.execute_set_channel_counterparty(
+            test.creator.clone(),
+            "channel-5",
+            Some(test.other_chain_wallet.to_string()),
+        )
+        .unwrap();
+        let err: ContractError = test
+            .execute_receive_callback(
+                test.addr_ics721_contract.clone(),
+                ClassId::new("some/class/id"),
+                CallbackData {
+                    sender: test.other_chain_wallet.to_string(),
+                    token_id: "1".to_string(),
+                    default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+                    escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+                    transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                    royalty_info: None,
+                    collection_metadata: None,
+                    metadata_policy: MetadataPolicy::PassThrough,
+                },
+                "1".to_string(),
+                test.nft_owner.to_string(),
+                test.other_chain_wallet.to_string(),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::UnauthorizedPacketSender {
+                sender: test.other_chain_wallet.to_string(),
+            }
+        );
+    }
     // assert receive ok
     {
         let mut test = Test::new();
-        // pretend nft has been transferred
+        // pretend nft has been escrowed on transfer, and released to owner by back transfer
         test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        test.execute_ack_callback(
+            test.addr_ics721_contract.clone(),
+            ClassId::new(test.addr_cw721_contract.to_string()),
+            Ics721Status::Success,
+            CallbackData {
+                sender: test.nft_owner.to_string(),
+                token_id: "0".to_string(),
+                default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+                escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+                transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                royalty_info: None,
+                collection_metadata: None,
+                metadata_policy: MetadataPolicy::Passport,
+            },
+            "0".to_string(),
+            test.other_chain_wallet.to_string(),
+            test.addr_arkite_contract.to_string(),
+        )
+        .unwrap();
         // assert nft info and token uri
         let all_nft_info =
             test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
         assert_eq!(all_nft_info.access.owner, test.nft_owner);
         assert_eq!(
             all_nft_info.info.extension.unwrap().image,
-            Some(DEFAULT_TOKEN_URI.to_string())
+            Some(ESCROWED_TOKEN_URI.to_string())
         );
         // assert no poaps yet minted
         let supply = test
@@ -763,23 +1216,26 @@ fn test_receive_callback() {
                 default_token_uri: DEFAULT_TOKEN_URI.to_string(),
                 escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
                 transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                royalty_info: None,
+                collection_metadata: None,
+                metadata_policy: MetadataPolicy::Passport,
             },
             "0".to_string(),
             test.nft_owner.to_string(),
-            test.other_chain_wallet.to_string(),
+            COUNTERPARTY_CONTRACT.to_string(),
         )
         .unwrap();
-        // assert token uri has changed
+        // assert passport on home chain has default uri
         let all_nft_info =
             test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
         assert_eq!(all_nft_info.access.owner, test.nft_owner);
         assert_eq!(
             all_nft_info.info.token_uri,
-            Some(TRANSFERRED_TOKEN_URI.to_string())
+            Some(DEFAULT_TOKEN_URI.to_string())
         );
         assert_eq!(
             all_nft_info.info.extension.unwrap().image,
-            Some(TRANSFERRED_TOKEN_URI.to_string())
+            Some(DEFAULT_TOKEN_URI.to_string())
         );
         // assert one poap minted
         let supply = test
@@ -787,7 +1243,7 @@ fn test_receive_callback() {
             .count;
         assert_eq!(supply, 1);
 
-        // process receive again for testing back transfer
+        // process receive again, uri only depends on location
         test.execute_receive_callback(
             test.addr_ics721_contract.clone(),
             ClassId::new(test.addr_cw721_contract.to_string()),
@@ -797,13 +1253,16 @@ fn test_receive_callback() {
                 default_token_uri: DEFAULT_TOKEN_URI.to_string(),
                 escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
                 transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                royalty_info: None,
+                collection_metadata: None,
+                metadata_policy: MetadataPolicy::Passport,
             },
             "0".to_string(),
             test.nft_owner.to_string(),
-            test.other_chain_wallet.to_string(),
+            COUNTERPARTY_CONTRACT.to_string(),
         )
         .unwrap();
-        // assert token uri has changed
+        // assert token uri is unchanged
         let all_nft_info =
             test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
         assert_eq!(all_nft_info.access.owner, test.nft_owner);
@@ -819,6 +1278,54 @@ fn test_receive_callback() {
     }
 }
 
+#[test]
+fn test_receive_callback_remote_locations() {
+    let mut test = Test::new();
+    test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+    test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+    for token_id in ["0", "1"] {
+        REMOTE_LOCATIONS
+            .save(
+                test.app
+                    .contract_storage_mut(&test.addr_arkite_contract)
+                    .as_mut(),
+                token_id,
+                &RemoteLocation {
+                    channel_id: WHITELISTED_CHANNEL.to_string(),
+                    class_id: test.addr_cw721_contract.to_string(),
+                    receiver: test.other_chain_wallet.to_string(),
+                },
+            )
+            .unwrap();
+    }
+
+    // only locations of tokens in packet are removed, token id of callback data is ignored
+    test.execute_receive_callback(
+        test.addr_ics721_contract.clone(),
+        ClassId::new("some/class/id"),
+        CallbackData {
+            sender: test.other_chain_wallet.to_string(),
+            token_id: "1".to_string(),
+            default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+            escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+            transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+            royalty_info: None,
+            collection_metadata: None,
+            metadata_policy: MetadataPolicy::PassThrough,
+        },
+        "0".to_string(),
+        test.nft_owner.to_string(),
+        COUNTERPARTY_CONTRACT.to_string(),
+    )
+    .unwrap();
+    let storage = test.app.contract_storage(&test.addr_arkite_contract);
+    assert_eq!(
+        REMOTE_LOCATIONS.may_load(storage.as_ref(), "0").unwrap(),
+        None
+    );
+    assert!(REMOTE_LOCATIONS.has(storage.as_ref(), "1"));
+}
+
 #[test]
 fn test_ack_callback() {
     // assert unauthorized
@@ -836,10 +1343,13 @@ fn test_ack_callback() {
                     default_token_uri: DEFAULT_TOKEN_URI.to_string(),
                     escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
                     transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                    royalty_info: None,
+                    collection_metadata: None,
+                    metadata_policy: MetadataPolicy::Passport,
                 },
                 "0".to_string(),
                 test.nft_owner.to_string(),
-                test.nft_owner.to_string(),
+                test.addr_arkite_contract.to_string(),
             )
             .unwrap_err()
             .downcast()
@@ -872,10 +1382,13 @@ fn test_ack_callback() {
                 default_token_uri: DEFAULT_TOKEN_URI.to_string(),
                 escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
                 transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                royalty_info: None,
+                collection_metadata: None,
+                metadata_policy: MetadataPolicy::Passport,
             },
             "0".to_string(),
             test.nft_owner.to_string(),
-            test.nft_owner.to_string(),
+            test.addr_arkite_contract.to_string(),
         )
         .unwrap();
         // assert token uri has changed
@@ -887,7 +1400,7 @@ fn test_ack_callback() {
             Some(ESCROWED_TOKEN_URI.to_string())
         );
 
-        // process ack again
+        // process ack again, passport stays escrowed
         test.execute_ack_callback(
             test.addr_ics721_contract.clone(),
             ClassId::new(test.addr_cw721_contract.to_string()),
@@ -898,19 +1411,22 @@ fn test_ack_callback() {
                 default_token_uri: DEFAULT_TOKEN_URI.to_string(),
                 escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
                 transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                royalty_info: None,
+                collection_metadata: None,
+                metadata_policy: MetadataPolicy::Passport,
             },
             "0".to_string(),
             test.nft_owner.to_string(),
-            test.nft_owner.to_string(),
+            test.addr_arkite_contract.to_string(),
         )
         .unwrap();
-        // assert token uri has changed
+        // assert token uri is unchanged
         let all_nft_info =
             test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
         assert_eq!(all_nft_info.access.owner, test.addr_ics721_contract);
         assert_eq!(
             all_nft_info.info.extension.unwrap().image,
-            Some(DEFAULT_TOKEN_URI.to_string())
+            Some(ESCROWED_TOKEN_URI.to_string())
         );
     }
     // assert ack fail
@@ -931,10 +1447,13 @@ fn test_ack_callback() {
                 default_token_uri: DEFAULT_TOKEN_URI.to_string(),
                 escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
                 transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                royalty_info: None,
+                collection_metadata: None,
+                metadata_policy: MetadataPolicy::Passport,
             },
             "0".to_string(),
             test.nft_owner.to_string(),
-            test.nft_owner.to_string(),
+            test.addr_arkite_contract.to_string(),
         )
         .unwrap();
 
@@ -943,6 +1462,140 @@ fn test_ack_callback() {
             test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
         assert_eq!(all_nft_info.access.owner, test.nft_owner);
     }
+    // assert forged ack success, sent by third-party wallet with a memo calling back passport
+    {
+        let mut test = Test::new();
+        let attacker = test.app.api().addr_make("attacker");
+        // victim's nft, escrowed by ics721
+        test.execute_passport_mint(test.addr_ics721_contract.clone())
+            .unwrap();
+        let forged_callback_data = CallbackData {
+            sender: attacker.to_string(),
+            token_id: "0".to_string(),
+            default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+            escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+            transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+            royalty_info: None,
+            collection_metadata: None,
+            metadata_policy: MetadataPolicy::Passport,
+        };
+
+        // packet sent by attacker
+        let err: ContractError = test
+            .execute_ack_callback(
+                test.addr_ics721_contract.clone(),
+                ClassId::new(test.addr_cw721_contract.to_string()),
+                Ics721Status::Success,
+                forged_callback_data.clone(),
+                "0".to_string(),
+                attacker.to_string(),
+                attacker.to_string(),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::UnauthorizedAckPacket {
+                sender: attacker.to_string()
+            }
+        );
+
+        // token of callback data not in packet
+        let err: ContractError = test
+            .execute_ack_callback(
+                test.addr_ics721_contract.clone(),
+                ClassId::new(test.addr_cw721_contract.to_string()),
+                Ics721Status::Success,
+                forged_callback_data,
+                "1".to_string(),
+                attacker.to_string(),
+                test.addr_arkite_contract.to_string(),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::TokenNotInPacket {
+                token_id: "0".to_string()
+            }
+        );
+
+        // assert token uri unchanged and nothing acked
+        let all_nft_info =
+            test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
+        assert_eq!(
+            all_nft_info.info.extension.unwrap().image,
+            Some(DEFAULT_TOKEN_URI.to_string())
+        );
+        assert_eq!(test.query_counters().transfers_acked, 0);
+    }
+    // assert forged ack fail, sent by third-party wallet with a memo calling back passport
+    {
+        let mut test = Test::new();
+        let attacker = test.app.api().addr_make("attacker");
+        // nft held by passport, e.g. while being forwarded to ics721
+        test.execute_passport_mint(test.addr_arkite_contract.clone())
+            .unwrap();
+        let forged_callback_data = CallbackData {
+            sender: attacker.to_string(),
+            token_id: "0".to_string(),
+            default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+            escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+            transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+            royalty_info: None,
+            collection_metadata: None,
+            metadata_policy: MetadataPolicy::Passport,
+        };
+
+        // packet sent by attacker
+        let err: ContractError = test
+            .execute_ack_callback(
+                test.addr_ics721_contract.clone(),
+                ClassId::new(test.addr_cw721_contract.to_string()),
+                Ics721Status::Failed("some reason".to_string()),
+                forged_callback_data.clone(),
+                "0".to_string(),
+                test.other_chain_wallet.to_string(),
+                attacker.to_string(),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::UnauthorizedAckPacket {
+                sender: attacker.to_string()
+            }
+        );
+
+        // token of callback data not in packet
+        let err: ContractError = test
+            .execute_ack_callback(
+                test.addr_ics721_contract.clone(),
+                ClassId::new(test.addr_cw721_contract.to_string()),
+                Ics721Status::Failed("some reason".to_string()),
+                forged_callback_data,
+                "1".to_string(),
+                test.other_chain_wallet.to_string(),
+                test.addr_arkite_contract.to_string(),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::TokenNotInPacket {
+                token_id: "0".to_string()
+            }
+        );
+
+        // assert nft still held by passport
+        let all_nft_info =
+            test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
+        assert_eq!(all_nft_info.access.owner, test.addr_arkite_contract);
+    }
 }
 
 #[test]
@@ -963,21 +1616,36 @@ fn test_migrate() {
     {
         let mut test = Test::new();
         test.migrate_arkite_contract(
-            Some("new default token uri".to_string()),
-            Some("new escrowed token uri".to_string()),
-            Some("new transferred token uri".to_string()),
+            Some("ipfs://new.default".to_string()),
+            Some("ipfs://new.escrowed".to_string()),
+            Some("ipfs://new.transferred".to_string()),
         )
         .unwrap();
         // assert results
         let default_token_uri = test.query_default_token_uri();
-        assert_eq!(default_token_uri, "new default token uri".to_string());
+        assert_eq!(default_token_uri, "ipfs://new.default".to_string());
         let escrowed_token_uri = test.query_escrowed_token_uri();
-        assert_eq!(escrowed_token_uri, "new escrowed token uri".to_string());
+        assert_eq!(escrowed_token_uri, "ipfs://new.escrowed".to_string());
         let transferred_token_uri = test.query_transferred_token_uri();
+        assert_eq!(transferred_token_uri, "ipfs://new.transferred".to_string());
+    }
+    // case 3: migrate with invalid token uri fails, like instantiate and update config
+    {
+        let mut test = Test::new();
+        let err: ContractError = test
+            .migrate_arkite_contract(None, Some("javascript:alert(1)".to_string()), None)
+            .unwrap_err()
+            .downcast()
+            .unwrap();
         assert_eq!(
-            transferred_token_uri,
-            "new transferred token uri".to_string()
+            err,
+            ContractError::InvalidUriScheme {
+                uri: "javascript:alert(1)".to_string(),
+                allowed_schemes: "https, ipfs, ar".to_string(),
+            }
         );
+        let escrowed_token_uri = test.query_escrowed_token_uri();
+        assert_eq!(escrowed_token_uri, ESCROWED_TOKEN_URI.to_string());
     }
 }
 
@@ -1014,3 +1682,1818 @@ fn test_receive_nft() {
         test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "1".to_string());
     assert_eq!(all_nft_info.access.owner, test.addr_arkite_contract);
 }
+
+#[test]
+fn test_migrate_upgrades() {
+    // case 1: upgrade from v0.1.0 seeds counters
+    {
+        let mut test = Test::new();
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        // pretend v0.1.0 with no counters
+        test.set_legacy_state("0.1.0");
+        COUNTERS.remove(
+            test.app
+                .contract_storage_mut(&test.addr_arkite_contract)
+                .as_mut(),
+        );
+
+        let res = test.migrate_arkite_contract(None, None, None).unwrap();
+        let wasm_event = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+        assert!(wasm_event
+            .attributes
+            .iter()
+            .any(|a| a.key == "upgrade" && a.value == "0.2.0"));
+        assert!(wasm_event
+            .attributes
+            .iter()
+            .any(|a| a.key == "upgrade" && a.value == "0.3.0"));
+        // assert results
+        let counters = test.query_counters();
+        assert_eq!(
+            counters,
+            Counters {
+                passports_minted: 2,
+                poaps_minted: 0,
+                transfers_acked: 0,
+                transfers_failed: 0,
+            }
+        );
+        let version = cw2::query_contract_info(&test.app.wrap(), test.addr_arkite_contract.clone())
+            .unwrap()
+            .version;
+        assert_eq!(version, env!("CARGO_PKG_VERSION"));
+    }
+    // case 2: upgrade from v0.2.0 folds legacy items into config
+    {
+        let mut test = Test::new();
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        test.set_legacy_state("0.2.0");
+
+        let res = test
+            .migrate_arkite_contract(None, Some("ipfs://new.escrowed".to_string()), None)
+            .unwrap();
+        let wasm_event = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+        assert!(!wasm_event
+            .attributes
+            .iter()
+            .any(|a| a.key == "upgrade" && a.value == "0.2.0"));
+        assert!(wasm_event
+            .attributes
+            .iter()
+            .any(|a| a.key == "upgrade" && a.value == "0.3.0"));
+        // assert results
+        let config = test.query_config();
+        assert_eq!(
+            config,
+            Config {
+                default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+                escrowed_token_uri: "ipfs://new.escrowed".to_string(),
+                transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                addr_cw721: Some(test.addr_cw721_contract.clone()),
+                addr_poap: Some(test.addr_poap_contract.clone()),
+                addr_ics721: Some(test.addr_ics721_contract.clone()),
+                counterparty_contract: Some(COUNTERPARTY_CONTRACT.to_string()),
+                poap_policy: PoapPolicy::Always,
+                timeout_policy: TimeoutPolicy::default(),
+                rate_limit_policy: RateLimitPolicy::default(),
+                royalty_info: None,
+                collection_metadata: None,
+            }
+        );
+        // contract admin becomes owner
+        assert_eq!(test.query_ownership().owner, Some(test.creator.clone()));
+        // counters untouched
+        assert_eq!(test.query_counters().passports_minted, 1);
+        // legacy items removed
+        let storage = test.app.contract_storage(&test.addr_arkite_contract);
+        assert_eq!(
+            legacy::DEFAULT_TOKEN_URI
+                .may_load(storage.as_ref())
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            legacy::ADDR_ICS721.may_load(storage.as_ref()).unwrap(),
+            None
+        );
+    }
+    // case 3: upgrade from v0.3.0 adds policies and owner
+    {
+        let mut test = Test::new();
+        test.set_legacy_state("0.3.0");
+
+        let res = test.migrate_arkite_contract(None, None, None).unwrap();
+        let wasm_event = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+        assert!(!wasm_event
+            .attributes
+            .iter()
+            .any(|a| a.key == "upgrade" && a.value == "0.3.0"));
+        assert!(wasm_event
+            .attributes
+            .iter()
+            .any(|a| a.key == "upgrade" && a.value == "0.4.0"));
+        // assert results
+        let config = test.query_config();
+        assert_eq!(config.poap_policy, PoapPolicy::Always);
+        assert_eq!(config.timeout_policy, TimeoutPolicy::default());
+        assert_eq!(
+            config.counterparty_contract,
+            Some(COUNTERPARTY_CONTRACT.to_string())
+        );
+        assert_eq!(test.query_ownership().owner, Some(test.creator.clone()));
+    }
+    // case 4: upgrade from v0.4.0 adds rate limit policy, royalty and collection metadata, pause state and fee config
+    {
+        let mut test = Test::new();
+        test.set_legacy_state("0.4.0");
+
+        let res = test.migrate_arkite_contract(None, None, None).unwrap();
+        let wasm_event = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+        assert!(!wasm_event
+            .attributes
+            .iter()
+            .any(|a| a.key == "upgrade" && a.value == "0.4.0"));
+        assert!(wasm_event
+            .attributes
+            .iter()
+            .any(|a| a.key == "upgrade" && a.value == "0.5.0"));
+        // assert results
+        let config = test.query_config();
+        assert_eq!(config.rate_limit_policy, RateLimitPolicy::Unlimited);
+        assert_eq!(config.royalty_info, None);
+        assert_eq!(config.collection_metadata, None);
+        assert_eq!(
+            config.counterparty_contract,
+            Some(COUNTERPARTY_CONTRACT.to_string())
+        );
+        let storage = test.app.contract_storage(&test.addr_arkite_contract);
+        assert_eq!(
+            PAUSE.may_load(storage.as_ref()).unwrap(),
+            Some(PauseState::default())
+        );
+        assert_eq!(
+            FEE_CONFIG.may_load(storage.as_ref()).unwrap(),
+            Some(FeeConfig::default())
+        );
+        // owner is kept
+        drop(storage);
+        assert_eq!(test.query_ownership().owner, Some(test.creator.clone()));
+    }
+    // case 5: same version, no upgrades and counters unchanged
+    {
+        let mut test = Test::new();
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        let res = test.migrate_arkite_contract(None, None, None).unwrap();
+        let wasm_event = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+        assert!(!wasm_event.attributes.iter().any(|a| a.key == "upgrade"));
+        assert_eq!(test.query_counters().passports_minted, 1);
+    }
+    // case 6: refuse downgrade
+    {
+        let mut test = Test::new();
+        test.set_contract_version("crates.io:arkite-passport", "99.0.0");
+        let err: ContractError = test
+            .migrate_arkite_contract(None, None, None)
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::CannotDowngrade {
+                stored: "99.0.0".to_string(),
+                new: env!("CARGO_PKG_VERSION").to_string(),
+            }
+        );
+    }
+    // case 7: refuse migration from other contract
+    {
+        let mut test = Test::new();
+        test.set_contract_version("crates.io:cw721-base", "0.1.0");
+        let err: ContractError = test
+            .migrate_arkite_contract(None, None, None)
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::InvalidContractName {
+                expected: "crates.io:arkite-passport".to_string(),
+                actual: "crates.io:cw721-base".to_string(),
+            }
+        );
+    }
+    // case 8: upgrade from v0.1.0 without stored collections, addresses are carried as none
+    {
+        let mut test = Test::new();
+        test.set_legacy_state("0.1.0");
+        let mut storage = test.app.contract_storage_mut(&test.addr_arkite_contract);
+        legacy::ADDR_CW721.remove(storage.as_mut());
+        legacy::ADDR_POAP.remove(storage.as_mut());
+        COUNTERS.remove(storage.as_mut());
+        drop(storage);
+
+        test.migrate_arkite_contract(None, None, None).unwrap();
+        assert_eq!(test.query_counters(), Counters::default());
+        let config = test.query_config();
+        assert_eq!(config.addr_cw721, None);
+        assert_eq!(config.addr_poap, None);
+        assert_eq!(config.addr_ics721, Some(test.addr_ics721_contract.clone()));
+    }
+}
+
+#[test]
+fn test_counters() {
+    let mut test = Test::new();
+    assert_eq!(test.query_counters(), Counters::default());
+
+    test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+    test.execute_receive_callback(
+        test.addr_ics721_contract.clone(),
+        ClassId::new("some/class/id"),
+        CallbackData {
+            sender: test.other_chain_wallet.to_string(),
+            token_id: "0".to_string(),
+            default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+            escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+            transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+            royalty_info: None,
+            collection_metadata: None,
+            metadata_policy: MetadataPolicy::Passport,
+        },
+        "0".to_string(),
+        test.nft_owner.to_string(),
+        COUNTERPARTY_CONTRACT.to_string(),
+    )
+    .unwrap();
+    test.execute_ack_callback(
+        test.addr_ics721_contract.clone(),
+        ClassId::new("some/class/id"),
+        Ics721Status::Success,
+        CallbackData {
+            sender: test.nft_owner.to_string(),
+            token_id: "0".to_string(),
+            default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+            escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+            transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+            royalty_info: None,
+            collection_metadata: None,
+            metadata_policy: MetadataPolicy::Passport,
+        },
+        "0".to_string(),
+        test.nft_owner.to_string(),
+        test.addr_arkite_contract.to_string(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        test.query_counters(),
+        Counters {
+            passports_minted: 1,
+            poaps_minted: 1,
+            transfers_acked: 1,
+            transfers_failed: 0,
+        }
+    );
+}
+
+#[test]
+fn test_update_config() {
+    // assert only owner
+    {
+        let mut test = Test::new();
+        let err: ContractError = test
+            .execute_update_config(
+                test.nft_owner.clone(),
+                Some("ipfs://new.default".to_string()),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+    }
+    // assert uri validation
+    {
+        let mut test = Test::new();
+        let err: ContractError = test
+            .execute_update_config(
+                test.creator.clone(),
+                Some("javascript:alert(1)".to_string()),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::InvalidUriScheme {
+                uri: "javascript:alert(1)".to_string(),
+                allowed_schemes: "https, ipfs, ar".to_string(),
+            }
+        );
+        let too_long_uri = format!("ipfs://{}", "a".repeat(512));
+        let err: ContractError = test
+            .execute_update_config(
+                test.creator.clone(),
+                None,
+                Some(too_long_uri.clone()),
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::UriTooLong {
+                uri: too_long_uri,
+                max_length: 512,
+            }
+        );
+        let err: ContractError = test
+            .execute_update_config(
+                test.creator.clone(),
+                None,
+                None,
+                None,
+                None,
+                Some(TimeoutPolicy {
+                    min_seconds: Some(100),
+                    max_seconds: Some(10),
+                }),
+                None,
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, ContractError::InvalidTimeoutPolicy {});
+    }
+    // assert update
+    {
+        let mut test = Test::new();
+        let res = test
+            .execute_update_config(
+                test.creator.clone(),
+                Some("https://new.default".to_string()),
+                None,
+                Some("ar://new.transferred".to_string()),
+                Some(PoapPolicy::OncePerReceiver),
+                Some(TimeoutPolicy {
+                    min_seconds: Some(60),
+                    max_seconds: None,
+                }),
+                None,
+            )
+            .unwrap();
+        let wasm_event = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+        assert!(wasm_event
+            .attributes
+            .iter()
+            .any(|a| a.key == "old_default_token_uri" && a.value == DEFAULT_TOKEN_URI));
+        assert!(wasm_event
+            .attributes
+            .iter()
+            .any(|a| a.key == "new_default_token_uri" && a.value == "https://new.default"));
+        assert!(!wasm_event
+            .attributes
+            .iter()
+            .any(|a| a.key == "new_escrowed_token_uri"));
+
+        let config = test.query_config();
+        assert_eq!(config.default_token_uri, "https://new.default".to_string());
+        assert_eq!(config.escrowed_token_uri, ESCROWED_TOKEN_URI.to_string());
+        assert_eq!(
+            config.transferred_token_uri,
+            "ar://new.transferred".to_string()
+        );
+        assert_eq!(config.poap_policy, PoapPolicy::OncePerReceiver);
+        assert_eq!(
+            config.timeout_policy,
+            TimeoutPolicy {
+                min_seconds: Some(60),
+                max_seconds: None,
+            }
+        );
+
+        // new passports use new default uri
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        let all_nft_info =
+            test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
+        assert_eq!(
+            all_nft_info.info.token_uri,
+            Some("https://new.default".to_string())
+        );
+    }
+}
+
+#[test]
+fn test_poap_policy() {
+    let callback_data = |test: &Test| CallbackData {
+        sender: test.other_chain_wallet.to_string(),
+        token_id: "0".to_string(),
+        default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+        escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+        transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+        royalty_info: None,
+        collection_metadata: None,
+        metadata_policy: MetadataPolicy::Passport,
+    };
+    for (poap_policy, expected_poaps) in [
+        (PoapPolicy::Always, 2),
+        (PoapPolicy::OncePerReceiver, 1),
+        (PoapPolicy::Disabled, 0),
+    ] {
+        let mut test = Test::new();
+        test.execute_update_config(
+            test.creator.clone(),
+            None,
+            None,
+            None,
+            Some(poap_policy),
+            None,
+            None,
+        )
+        .unwrap();
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        // receive twice for same receiver
+        for _ in 0..2 {
+            test.execute_receive_callback(
+                test.addr_ics721_contract.clone(),
+                ClassId::new("some/class/id"),
+                callback_data(&test),
+                "0".to_string(),
+                test.nft_owner.to_string(),
+                COUNTERPARTY_CONTRACT.to_string(),
+            )
+            .unwrap();
+        }
+        let supply = test
+            .query_cw721_num_tokens(test.addr_poap_contract.clone())
+            .count;
+        assert_eq!(supply, expected_poaps);
+        assert_eq!(test.query_counters().poaps_minted, expected_poaps);
+    }
+}
+
+#[test]
+fn test_timeout_policy() {
+    let mut test = Test::new();
+    test.execute_update_config(
+        test.creator.clone(),
+        None,
+        None,
+        None,
+        None,
+        Some(TimeoutPolicy {
+            min_seconds: Some(60),
+            max_seconds: Some(3600),
+        }),
+        None,
+    )
+    .unwrap();
+    test.execute_passport_mint(test.addr_arkite_contract.clone())
+        .unwrap();
+
+    // timeout used in tests is at timestamp 0, so it is too short
+    let err: ContractError = test
+        .execute_arkite_receive_nft(
+            "0".to_string(),
+            test.addr_arkite_contract.to_string(),
+            WHITELISTED_CHANNEL.to_string(),
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::TimeoutTooShort { min_seconds: 60 });
+}
+
+#[test]
+fn test_resync_metadata() {
+    // assert only owner
+    {
+        let mut test = Test::new();
+        let err: ContractError = test
+            .execute_resync_metadata(test.nft_owner.clone(), None, None)
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+    }
+    // assert resync keeps home and escrowed state
+    {
+        let mut test = Test::new();
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        // pretend nft has been escrowed by ics721
+        test.execute_passport_mint(test.addr_ics721_contract.clone())
+            .unwrap();
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        test.execute_update_config(
+            test.creator.clone(),
+            Some("ipfs://new.default".to_string()),
+            Some("ipfs://new.escrowed".to_string()),
+            Some("ipfs://new.transferred".to_string()),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        // first page
+        let res = test
+            .execute_resync_metadata(test.creator.clone(), None, Some(2))
+            .unwrap();
+        let wasm_event = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+        let attribute = |key: &str| {
+            wasm_event
+                .attributes
+                .iter()
+                .find(|a| a.key == key)
+                .unwrap()
+                .value
+                .clone()
+        };
+        assert_eq!(attribute("num_resynced"), "2");
+        assert_eq!(attribute("num_escrowed"), "1");
+        assert_eq!(attribute("last_token_id"), "1");
+        assert_eq!(attribute("finished"), "false");
+        // token 2 not yet resynced
+        let all_nft_info =
+            test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "2".to_string());
+        assert_eq!(
+            all_nft_info.info.token_uri,
+            Some(DEFAULT_TOKEN_URI.to_string())
+        );
+
+        // second page
+        let res = test
+            .execute_resync_metadata(test.creator.clone(), Some("1".to_string()), Some(2))
+            .unwrap();
+        let wasm_event = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+        assert!(wasm_event
+            .attributes
+            .iter()
+            .any(|a| a.key == "finished" && a.value == "true"));
+
+        // assert results
+        for (token_id, token_uri) in [
+            ("0", "ipfs://new.default"),
+            ("1", "ipfs://new.escrowed"),
+            ("2", "ipfs://new.default"),
+        ] {
+            let all_nft_info = test
+                .query_cw721_all_nft_info(test.addr_cw721_contract.clone(), token_id.to_string());
+            assert_eq!(all_nft_info.info.token_uri, Some(token_uri.to_string()));
+            let extension = all_nft_info.info.extension.unwrap();
+            assert_eq!(extension.image, Some(token_uri.to_string()));
+            let attributes = extension.attributes.unwrap();
+            for (trait_type, value) in [
+                ("token_uri", token_uri),
+                ("default_uri", "ipfs://new.default"),
+                ("escrowed_uri", "ipfs://new.escrowed"),
+                ("transferred_uri", "ipfs://new.transferred"),
+            ] {
+                assert!(attributes
+                    .iter()
+                    .any(|a| a.trait_type == trait_type && a.value == value));
+            }
+        }
+    }
+    // assert resync while transfer is pending, failed ack resets escrowed uri of returned passport
+    {
+        let mut test = Test::new();
+        // pretend nft has been escrowed by ics721, awaiting ack
+        test.execute_passport_mint(test.addr_ics721_contract.clone())
+            .unwrap();
+        test.execute_resync_metadata(test.creator.clone(), None, None)
+            .unwrap();
+        let all_nft_info =
+            test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
+        assert_eq!(
+            all_nft_info.info.token_uri,
+            Some(ESCROWED_TOKEN_URI.to_string())
+        );
+
+        // transfer fails, ics721 returns nft to arkite
+        test.app
+            .execute_contract(
+                test.addr_ics721_contract.clone(),
+                test.addr_cw721_contract.clone(),
+                &cw721_base::msg::ExecuteMsg::<
+                    DefaultOptionalNftExtensionMsg,
+                    DefaultOptionalCollectionExtensionMsg,
+                    Empty,
+                >::TransferNft {
+                    recipient: test.addr_arkite_contract.to_string(),
+                    token_id: "0".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+        test.execute_ack_callback(
+            test.addr_ics721_contract.clone(),
+            ClassId::new(test.addr_cw721_contract.to_string()),
+            Ics721Status::Failed("timeout".to_string()),
+            CallbackData {
+                sender: test.nft_owner.to_string(),
+                token_id: "0".to_string(),
+                default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+                escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+                transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                royalty_info: None,
+                collection_metadata: None,
+                metadata_policy: MetadataPolicy::Passport,
+            },
+            "0".to_string(),
+            test.other_chain_wallet.to_string(),
+            test.addr_arkite_contract.to_string(),
+        )
+        .unwrap();
+
+        // assert results
+        let all_nft_info =
+            test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
+        assert_eq!(all_nft_info.access.owner, test.nft_owner);
+        assert_eq!(
+            all_nft_info.info.token_uri,
+            Some(DEFAULT_TOKEN_URI.to_string())
+        );
+        assert_eq!(
+            all_nft_info.info.extension.unwrap().image,
+            Some(DEFAULT_TOKEN_URI.to_string())
+        );
+    }
+}
+
+#[test]
+fn test_query_contract_info() {
+    let mut test = Test::new();
+    test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+
+    let contract_info = test.query_contract_info();
+    assert_eq!(
+        contract_info.contract_version.contract,
+        "crates.io:arkite-passport".to_string()
+    );
+    assert_eq!(
+        contract_info.contract_version.version,
+        env!("CARGO_PKG_VERSION").to_string()
+    );
+    assert_eq!(contract_info.ownership.owner, Some(test.creator.clone()));
+    assert_eq!(contract_info.config, test.query_config());
+    assert_eq!(contract_info.counters.passports_minted, 1);
+    assert_eq!(
+        contract_info.linked_contracts,
+        LinkedContracts {
+            cw721: Some(test.addr_cw721_contract.clone()),
+            poap: Some(test.addr_poap_contract.clone()),
+            ics721: Some(test.addr_ics721_contract.clone()),
+            ics721_outgoing_proxy: Some(test.addr_outgoing_proxy_contract.clone()),
+            ics721_incoming_proxy: Some(test.addr_incoming_proxy_contract.clone()),
+            counterparty_contract: Some(COUNTERPARTY_CONTRACT.to_string()),
+        }
+    );
+}
+
+#[test]
+fn test_pause() {
+    let all_operations = PausedOperations {
+        mint: true,
+        transfer: true,
+        poap: true,
+    };
+    let callback_data = |sender: &Addr| CallbackData {
+        sender: sender.to_string(),
+        token_id: "0".to_string(),
+        default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+        escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+        transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+        royalty_info: None,
+        collection_metadata: None,
+        metadata_policy: MetadataPolicy::Passport,
+    };
+
+    // case 1: only owner or pauser can pause and unpause, only owner can set pauser
+    {
+        let mut test = Test::new();
+        let pauser = test.app.api().addr_make("pauser");
+        assert_eq!(
+            test.query_pause_status(),
+            PauseStatusResponse {
+                pauser: None,
+                paused: PausedOperations::default(),
+                unpause_at_height: None,
+            }
+        );
+        let err: ContractError = test
+            .execute_pause(pauser.clone(), all_operations.clone(), None)
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, ContractError::UnauthorizedPauser {});
+        let err: ContractError = test
+            .execute_set_pauser(pauser.clone(), Some(pauser.to_string()))
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+
+        test.execute_set_pauser(test.creator.clone(), Some(pauser.to_string()))
+            .unwrap();
+        test.execute_pause(pauser.clone(), all_operations.clone(), None)
+            .unwrap();
+        assert_eq!(
+            test.query_pause_status(),
+            PauseStatusResponse {
+                pauser: Some(pauser.clone()),
+                paused: all_operations.clone(),
+                unpause_at_height: None,
+            }
+        );
+        let err: ContractError = test
+            .execute_unpause(test.nft_owner.clone())
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, ContractError::UnauthorizedPauser {});
+        test.execute_unpause(test.creator.clone()).unwrap();
+        assert_eq!(
+            test.query_pause_status().paused,
+            PausedOperations::default()
+        );
+    }
+
+    // case 2: paused mint and transfer, nft stays with owner
+    {
+        let mut test = Test::new();
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        test.execute_pause(
+            test.creator.clone(),
+            PausedOperations {
+                mint: true,
+                transfer: true,
+                poap: false,
+            },
+            None,
+        )
+        .unwrap();
+        let err: ContractError = test
+            .execute_passport_mint(test.nft_owner.clone())
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::Paused {
+                operation: "mint".to_string()
+            }
+        );
+        let err: ContractError = test
+            .execute_arkite_receive_nft(
+                "0".to_string(),
+                test.other_chain_wallet.to_string(),
+                WHITELISTED_CHANNEL.to_string(),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::Paused {
+                operation: "transfer".to_string()
+            }
+        );
+        test.execute_cw721_send_nft(
+            "0".to_string(),
+            test.other_chain_wallet.to_string(),
+            WHITELISTED_CHANNEL.to_string(),
+        )
+        .unwrap_err();
+        let all_nft_info =
+            test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
+        assert_eq!(all_nft_info.access.owner, test.nft_owner);
+    }
+
+    // case 3: paused poap, receive callback still updates token uri, but no poap is minted
+    {
+        let mut test = Test::new();
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        test.execute_pause(
+            test.creator.clone(),
+            PausedOperations {
+                mint: false,
+                transfer: false,
+                poap: true,
+            },
+            None,
+        )
+        .unwrap();
+        test.execute_receive_callback(
+            test.addr_ics721_contract.clone(),
+            ClassId::new("some/class/id"),
+            callback_data(&test.other_chain_wallet),
+            "0".to_string(),
+            test.nft_owner.to_string(),
+            COUNTERPARTY_CONTRACT.to_string(),
+        )
+        .unwrap();
+        let all_nft_info =
+            test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
+        assert_eq!(
+            all_nft_info.info.token_uri,
+            Some(TRANSFERRED_TOKEN_URI.to_string())
+        );
+        let supply = test
+            .query_cw721_num_tokens(test.addr_poap_contract.clone())
+            .count;
+        assert_eq!(supply, 0);
+        assert_eq!(test.query_counters().poaps_minted, 0);
+    }
+
+    // case 4: while paused, failed acks still return nft to owner
+    {
+        let mut test = Test::new();
+        // pretend nft has been returned to arkite
+        test.execute_passport_mint(test.addr_arkite_contract.clone())
+            .unwrap();
+        test.execute_pause(test.creator.clone(), all_operations.clone(), None)
+            .unwrap();
+        test.execute_ack_callback(
+            test.addr_ics721_contract.clone(),
+            ClassId::new("some/class/id"),
+            Ics721Status::Failed("some reason".to_string()),
+            callback_data(&test.nft_owner),
+            "0".to_string(),
+            test.nft_owner.to_string(),
+            test.addr_arkite_contract.to_string(),
+        )
+        .unwrap();
+        let all_nft_info =
+            test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
+        assert_eq!(all_nft_info.access.owner, test.nft_owner);
+    }
+
+    // case 5: automatic unpause at block height
+    {
+        let mut test = Test::new();
+        let height = test.app.block_info().height;
+        let err: ContractError = test
+            .execute_pause(test.creator.clone(), all_operations.clone(), Some(height))
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::InvalidUnpauseHeight {
+                height,
+                current_height: height
+            }
+        );
+        test.execute_pause(
+            test.creator.clone(),
+            all_operations.clone(),
+            Some(height + 10),
+        )
+        .unwrap();
+        assert_eq!(
+            test.query_pause_status(),
+            PauseStatusResponse {
+                pauser: None,
+                paused: all_operations.clone(),
+                unpause_at_height: Some(height + 10),
+            }
+        );
+        test.execute_passport_mint(test.nft_owner.clone())
+            .unwrap_err();
+
+        test.app.update_block(|block| block.height += 10);
+        assert_eq!(
+            test.query_pause_status(),
+            PauseStatusResponse {
+                pauser: None,
+                paused: PausedOperations::default(),
+                unpause_at_height: None,
+            }
+        );
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+    }
+}
+
+#[test]
+fn test_rate_limit_policy() {
+    let set_rate_limit_policy = |test: &mut Test, policy: RateLimitPolicy| {
+        test.execute_update_config(
+            test.creator.clone(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(policy),
+        )
+    };
+
+    // case 1: invalid policy
+    {
+        let mut test = Test::new();
+        let err: ContractError = set_rate_limit_policy(
+            &mut test,
+            RateLimitPolicy::PerBlocks {
+                max_transfers: 0,
+                blocks: 10,
+            },
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+        assert_eq!(err, ContractError::InvalidRateLimitPolicy {});
+    }
+
+    // case 2: sliding window over blocks, per sender and channel
+    {
+        let mut test = Test::new();
+        let policy = RateLimitPolicy::PerBlocks {
+            max_transfers: 2,
+            blocks: 10,
+        };
+        set_rate_limit_policy(&mut test, policy.clone()).unwrap();
+        for _ in 0..3 {
+            test.execute_passport_mint(test.addr_arkite_contract.clone())
+                .unwrap();
+        }
+        let nft_owner = test.nft_owner.clone();
+        assert_eq!(
+            test.query_rate_limit_quota(&nft_owner, WHITELISTED_CHANNEL),
+            RateLimitQuotaResponse {
+                policy: policy.clone(),
+                used: 0,
+                remaining: Some(2),
+                retry_at: None,
+            }
+        );
+
+        // outgoing proxy allows one transfer per block, so each transfer is in a new block
+        let start_height = test.app.block_info().height;
+        for token_id in ["0", "1"] {
+            test.execute_arkite_receive_nft(
+                token_id.to_string(),
+                test.other_chain_wallet.to_string(),
+                WHITELISTED_CHANNEL.to_string(),
+            )
+            .unwrap();
+            test.app.update_block(|block| block.height += 1);
+        }
+        assert_eq!(
+            test.query_rate_limit_quota(&nft_owner, WHITELISTED_CHANNEL),
+            RateLimitQuotaResponse {
+                policy: policy.clone(),
+                used: 2,
+                remaining: Some(0),
+                retry_at: Some(start_height + 10),
+            }
+        );
+        // quota of other channels is untouched
+        assert_eq!(
+            test.query_rate_limit_quota(&nft_owner, "other-channel")
+                .remaining,
+            Some(2)
+        );
+
+        let err: ContractError = test
+            .execute_arkite_receive_nft(
+                "2".to_string(),
+                test.other_chain_wallet.to_string(),
+                WHITELISTED_CHANNEL.to_string(),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::RateLimited {
+                sender: nft_owner.to_string(),
+                channel_id: WHITELISTED_CHANNEL.to_string(),
+                retry_at: format!("block {}", start_height + 10),
+            }
+        );
+
+        // first transfer leaves window
+        test.app
+            .update_block(|block| block.height = start_height + 10);
+        test.execute_arkite_receive_nft(
+            "2".to_string(),
+            test.other_chain_wallet.to_string(),
+            WHITELISTED_CHANNEL.to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            test.query_rate_limit_quota(&nft_owner, WHITELISTED_CHANNEL),
+            RateLimitQuotaResponse {
+                policy,
+                used: 2,
+                remaining: Some(0),
+                retry_at: Some(start_height + 11),
+            }
+        );
+    }
+
+    // case 3: sliding window over seconds
+    {
+        let mut test = Test::new();
+        set_rate_limit_policy(
+            &mut test,
+            RateLimitPolicy::PerSeconds {
+                max_transfers: 1,
+                seconds: 60,
+            },
+        )
+        .unwrap();
+        for _ in 0..2 {
+            test.execute_passport_mint(test.addr_arkite_contract.clone())
+                .unwrap();
+        }
+        let start_seconds = test.app.block_info().time.seconds();
+        test.execute_arkite_receive_nft(
+            "0".to_string(),
+            test.other_chain_wallet.to_string(),
+            WHITELISTED_CHANNEL.to_string(),
+        )
+        .unwrap();
+        test.app.update_block(|block| {
+            block.height += 1;
+            block.time = block.time.plus_seconds(30);
+        });
+        let err: ContractError = test
+            .execute_arkite_receive_nft(
+                "1".to_string(),
+                test.other_chain_wallet.to_string(),
+                WHITELISTED_CHANNEL.to_string(),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::RateLimited {
+                sender: test.nft_owner.to_string(),
+                channel_id: WHITELISTED_CHANNEL.to_string(),
+                retry_at: format!("timestamp {}", start_seconds + 60),
+            }
+        );
+        test.app
+            .update_block(|block| block.time = block.time.plus_seconds(30));
+        test.execute_arkite_receive_nft(
+            "1".to_string(),
+            test.other_chain_wallet.to_string(),
+            WHITELISTED_CHANNEL.to_string(),
+        )
+        .unwrap();
+    }
+
+    // case 4: quota is per sender and channel pair, exhausted quota neither limits other channels nor other senders
+    {
+        let mut test = Test::new();
+        set_rate_limit_policy(
+            &mut test,
+            RateLimitPolicy::PerBlocks {
+                max_transfers: 1,
+                blocks: 10,
+            },
+        )
+        .unwrap();
+        for _ in 0..3 {
+            test.execute_passport_mint(test.addr_arkite_contract.clone())
+                .unwrap();
+        }
+        let nft_owner = test.nft_owner.clone();
+        test.execute_arkite_receive_nft(
+            "0".to_string(),
+            test.other_chain_wallet.to_string(),
+            WHITELISTED_CHANNEL.to_string(),
+        )
+        .unwrap();
+        test.app.update_block(|block| block.height += 1);
+        let err: ContractError = test
+            .execute_arkite_receive_nft(
+                "1".to_string(),
+                test.other_chain_wallet.to_string(),
+                WHITELISTED_CHANNEL.to_string(),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert!(matches!(err, ContractError::RateLimited { .. }));
+
+        // same sender on other channel
+        test.execute_arkite_receive_nft(
+            "1".to_string(),
+            test.other_chain_wallet.to_string(),
+            "other-channel".to_string(),
+        )
+        .unwrap();
+        test.app.update_block(|block| block.height += 1);
+        assert_eq!(
+            test.query_rate_limit_quota(&nft_owner, "other-channel")
+                .remaining,
+            Some(0)
+        );
+
+        // other sender on same channel
+        let other_sender = test.app.api().addr_make("other_sender");
+        test.nft_owner = other_sender.clone();
+        test.execute_arkite_receive_nft(
+            "2".to_string(),
+            test.other_chain_wallet.to_string(),
+            WHITELISTED_CHANNEL.to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            test.query_rate_limit_quota(&other_sender, WHITELISTED_CHANNEL)
+                .remaining,
+            Some(0)
+        );
+        assert_eq!(
+            test.query_rate_limit_quota(&nft_owner, WHITELISTED_CHANNEL)
+                .used,
+            1
+        );
+    }
+}
+
+#[test]
+fn test_bridge_fees() {
+    let setup = || {
+        let mut test = Test::new();
+        let treasury = test.app.api().addr_make("treasury");
+        let relayer_pool = test.app.api().addr_make("relayer_pool");
+        test.execute_update_fee_config(
+            test.creator.clone(),
+            Some(treasury.to_string()),
+            Some(relayer_pool.to_string()),
+            Some(Decimal::percent(25)),
+        )
+        .unwrap();
+        test.execute_set_channel_fee(
+            test.creator.clone(),
+            WHITELISTED_CHANNEL,
+            Some(coin(100, FEE_DENOM)),
+        )
+        .unwrap();
+        let nft_owner = test.nft_owner.clone();
+        test.app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &nft_owner, coins(1000, FEE_DENOM))
+                .unwrap()
+        });
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        (test, treasury, relayer_pool)
+    };
+
+    // case 1: only owner sets fees, treasury is required and relayer share must not exceed 1
+    {
+        let mut test = Test::new();
+        let err: ContractError = test
+            .execute_set_channel_fee(
+                test.nft_owner.clone(),
+                WHITELISTED_CHANNEL,
+                Some(coin(100, FEE_DENOM)),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+        let err: ContractError = test
+            .execute_set_channel_fee(
+                test.creator.clone(),
+                WHITELISTED_CHANNEL,
+                Some(coin(100, FEE_DENOM)),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, ContractError::Std(StdError::not_found("treasury")));
+        let err: ContractError = test
+            .execute_update_fee_config(
+                test.creator.clone(),
+                None,
+                None,
+                Some(Decimal::percent(101)),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, ContractError::InvalidRelayerShare {});
+    }
+
+    // case 2: send_nft pays fee from prepaid balance
+    {
+        let (mut test, treasury, relayer_pool) = setup();
+        let fees: FeesResponse = test
+            .app
+            .wrap()
+            .query_wasm_smart(test.addr_arkite_contract.clone(), &QueryMsg::Fees {})
+            .unwrap();
+        assert_eq!(
+            fees,
+            FeesResponse {
+                fee_config: FeeConfig {
+                    treasury: Some(treasury.clone()),
+                    relayer_pool: Some(relayer_pool.clone()),
+                    relayer_share: Decimal::percent(25),
+                },
+                channel_fees: vec![ChannelFee {
+                    channel_id: WHITELISTED_CHANNEL.to_string(),
+                    fee: coin(100, FEE_DENOM),
+                }],
+            }
+        );
+
+        // no prepaid balance, nft stays with owner
+        test.execute_cw721_send_nft(
+            "0".to_string(),
+            test.other_chain_wallet.to_string(),
+            WHITELISTED_CHANNEL.to_string(),
+        )
+        .unwrap_err();
+        let all_nft_info =
+            test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
+        assert_eq!(all_nft_info.access.owner, test.nft_owner);
+
+        test.app
+            .execute_contract(
+                test.nft_owner.clone(),
+                test.addr_arkite_contract.clone(),
+                &ExecuteMsg::PrepayFees {},
+                &coins(150, FEE_DENOM),
+            )
+            .unwrap();
+        test.execute_cw721_send_nft(
+            "0".to_string(),
+            test.other_chain_wallet.to_string(),
+            WHITELISTED_CHANNEL.to_string(),
+        )
+        .unwrap();
+        let all_nft_info =
+            test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
+        assert_eq!(all_nft_info.access.owner, test.addr_ics721_contract);
+        assert_eq!(test.query_balance(&treasury), Uint128::new(75));
+        assert_eq!(test.query_balance(&relayer_pool), Uint128::new(25));
+        let prepaid: Vec<Coin> = test
+            .app
+            .wrap()
+            .query_wasm_smart(
+                test.addr_arkite_contract.clone(),
+                &QueryMsg::PrepaidFees {
+                    address: test.nft_owner.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(prepaid, coins(50, FEE_DENOM));
+        let collected: Vec<CollectedFeesResponse> = test
+            .app
+            .wrap()
+            .query_wasm_smart(
+                test.addr_arkite_contract.clone(),
+                &QueryMsg::CollectedFees {},
+            )
+            .unwrap();
+        assert_eq!(
+            collected,
+            vec![CollectedFeesResponse {
+                denom: FEE_DENOM.to_string(),
+                treasury: Uint128::new(75),
+                relayer_pool: Uint128::new(25),
+            }]
+        );
+
+        // withdraw remaining prepaid balance
+        let err: ContractError = test
+            .app
+            .execute_contract(
+                test.nft_owner.clone(),
+                test.addr_arkite_contract.clone(),
+                &ExecuteMsg::WithdrawPrepaidFees {
+                    amount: coin(51, FEE_DENOM),
+                },
+                &[],
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::InsufficientPrepaidFees {
+                address: test.nft_owner.to_string(),
+                balance: coin(50, FEE_DENOM).to_string(),
+                required: coin(51, FEE_DENOM).to_string(),
+            }
+        );
+        test.app
+            .execute_contract(
+                test.nft_owner.clone(),
+                test.addr_arkite_contract.clone(),
+                &ExecuteMsg::WithdrawPrepaidFees {
+                    amount: coin(50, FEE_DENOM),
+                },
+                &[],
+            )
+            .unwrap();
+        assert_eq!(test.query_balance(&test.nft_owner), Uint128::new(900));
+    }
+
+    // case 3: transfer pays fee with attached funds
+    {
+        let (mut test, treasury, relayer_pool) = setup();
+        // owner approves this contract for nft
+        test.app
+            .execute_contract(
+                test.nft_owner.clone(),
+                test.addr_cw721_contract.clone(),
+                &cw721_base::msg::ExecuteMsg::<
+                    DefaultOptionalNftExtensionMsg,
+                    DefaultOptionalCollectionExtensionMsg,
+                    Empty,
+                >::Approve {
+                    spender: test.addr_arkite_contract.to_string(),
+                    token_id: "0".to_string(),
+                    expires: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+        let err: ContractError = test
+            .execute_transfer(test.other_chain_wallet.clone(), "0", &[])
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::NotNftOwner {
+                token_id: "0".to_string()
+            }
+        );
+        let err: ContractError = test
+            .execute_transfer(test.nft_owner.clone(), "0", &coins(99, FEE_DENOM))
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::InvalidFeePayment {
+                expected: coin(100, FEE_DENOM).to_string()
+            }
+        );
+
+        test.execute_transfer(test.nft_owner.clone(), "0", &coins(100, FEE_DENOM))
+            .unwrap();
+        let all_nft_info =
+            test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
+        assert_eq!(all_nft_info.access.owner, test.addr_ics721_contract);
+        assert_eq!(test.query_balance(&test.nft_owner), Uint128::new(900));
+        assert_eq!(test.query_balance(&treasury), Uint128::new(75));
+        assert_eq!(test.query_balance(&relayer_pool), Uint128::new(25));
+    }
+
+    // case 4: channels without fee are free
+    {
+        let (mut test, _, _) = setup();
+        test.execute_set_channel_fee(test.creator.clone(), WHITELISTED_CHANNEL, None)
+            .unwrap();
+        test.execute_cw721_send_nft(
+            "0".to_string(),
+            test.other_chain_wallet.to_string(),
+            WHITELISTED_CHANNEL.to_string(),
+        )
+        .unwrap();
+        assert_eq!(test.query_balance(&test.nft_owner), Uint128::new(1000));
+    }
+
+    // case 5: fee config stored before relayer pool rename is still read
+    {
+        let (mut test, treasury, relayer_pool) = setup();
+        let legacy_fee_config = format!(
+            r#"{{"treasury":"{treasury}","relayer_incentive":"{relayer_pool}","relayer_share":"0.25"}}"#
+        );
+        test.app
+            .contract_storage_mut(&test.addr_arkite_contract)
+            .set(b"fee_config", legacy_fee_config.as_bytes());
+        let fees: FeesResponse = test
+            .app
+            .wrap()
+            .query_wasm_smart(test.addr_arkite_contract.clone(), &QueryMsg::Fees {})
+            .unwrap();
+        assert_eq!(
+            fees.fee_config,
+            FeeConfig {
+                treasury: Some(treasury),
+                relayer_pool: Some(relayer_pool),
+                relayer_share: Decimal::percent(25),
+            }
+        );
+    }
+}
+
+#[test]
+fn test_royalty_info() {
+    let royalty_info = |test: &Test, percent: u64| RoyaltyInfo {
+        payment_address: test.app.api().addr_make("creator_royalty").to_string(),
+        share: Decimal::percent(percent),
+    };
+    let passport_royalty_info = |test: &mut Test, token_id: &str| {
+        let all_nft_info =
+            test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), token_id.to_string());
+        royalty_info_from_traits(&all_nft_info.info.extension.unwrap().attributes.unwrap())
+    };
+
+    // case 1: only owner sets royalty, share must be greater than zero and at most one
+    {
+        let mut test = Test::new();
+        let err: ContractError = test
+            .execute_set_royalty_info(test.nft_owner.clone(), Some(royalty_info(&test, 5)))
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+        for percent in [0, 101] {
+            let err: ContractError = test
+                .execute_set_royalty_info(test.creator.clone(), Some(royalty_info(&test, percent)))
+                .unwrap_err()
+                .downcast()
+                .unwrap();
+            assert_eq!(
+                err,
+                ContractError::InvalidRoyaltyShare {
+                    share: Decimal::percent(percent)
+                }
+            );
+        }
+        test.execute_set_royalty_info(
+            test.creator.clone(),
+            Some(RoyaltyInfo {
+                payment_address: "invalid".to_string(),
+                share: Decimal::percent(5),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(test.query_config().royalty_info, None);
+    }
+
+    // case 2: royalty is set on mint and resync, removing it only affects new passports
+    {
+        let mut test = Test::new();
+        test.execute_set_royalty_info(test.creator.clone(), Some(royalty_info(&test, 5)))
+            .unwrap();
+        assert_eq!(
+            test.query_config().royalty_info,
+            Some(royalty_info(&test, 5))
+        );
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        assert_eq!(
+            passport_royalty_info(&mut test, "0"),
+            Some(royalty_info(&test, 5))
+        );
+
+        test.execute_set_royalty_info(test.creator.clone(), Some(royalty_info(&test, 10)))
+            .unwrap();
+        test.execute_resync_metadata(test.creator.clone(), None, None)
+            .unwrap();
+        assert_eq!(
+            passport_royalty_info(&mut test, "0"),
+            Some(royalty_info(&test, 10))
+        );
+
+        test.execute_set_royalty_info(test.creator.clone(), None)
+            .unwrap();
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        assert_eq!(
+            passport_royalty_info(&mut test, "0"),
+            Some(royalty_info(&test, 10))
+        );
+        assert_eq!(passport_royalty_info(&mut test, "1"), None);
+    }
+
+    // case 3: royalty is kept on uri updates, and taken from callback for nfts without royalty
+    {
+        let mut test = Test::new();
+        test.execute_set_royalty_info(test.creator.clone(), Some(royalty_info(&test, 5)))
+            .unwrap();
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        test.execute_set_royalty_info(test.creator.clone(), None)
+            .unwrap();
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        let callback_data = |test: &Test, token_id: &str| CallbackData {
+            sender: test.other_chain_wallet.to_string(),
+            token_id: token_id.to_string(),
+            default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+            escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+            transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+            royalty_info: Some(royalty_info(test, 20)),
+            collection_metadata: None,
+            metadata_policy: MetadataPolicy::Passport,
+        };
+        for token_id in ["0", "1"] {
+            test.execute_receive_callback(
+                test.addr_ics721_contract.clone(),
+                ClassId::new("some/class/id"),
+                callback_data(&test, token_id),
+                token_id.to_string(),
+                test.nft_owner.to_string(),
+                COUNTERPARTY_CONTRACT.to_string(),
+            )
+            .unwrap();
+        }
+        let all_nft_info =
+            test.query_cw721_all_nft_info(test.addr_cw721_contract.clone(), "0".to_string());
+        assert_eq!(
+            all_nft_info.info.token_uri,
+            Some(TRANSFERRED_TOKEN_URI.to_string())
+        );
+        assert_eq!(
+            passport_royalty_info(&mut test, "0"),
+            Some(royalty_info(&test, 5))
+        );
+        assert_eq!(
+            passport_royalty_info(&mut test, "1"),
+            Some(royalty_info(&test, 20))
+        );
+    }
+}
+
+#[test]
+fn test_collection_metadata() {
+    let collection_metadata = CollectionMetadata {
+        description: Some("Arkite passports".to_string()),
+        image: Some("ipfs://collection".to_string()),
+        external_link: Some("https://arkite.example".to_string()),
+    };
+
+    // case 1: only owner sets collection metadata, image and external link must be valid uris
+    {
+        let mut test = Test::new();
+        let err: ContractError = test
+            .execute_set_collection_metadata(
+                test.nft_owner.clone(),
+                Some(collection_metadata.clone()),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+        let err: ContractError = test
+            .execute_set_collection_metadata(
+                test.creator.clone(),
+                Some(CollectionMetadata {
+                    external_link: Some("ftp://arkite.example".to_string()),
+                    ..collection_metadata.clone()
+                }),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::InvalidUriScheme {
+                uri: "ftp://arkite.example".to_string(),
+                allowed_schemes: "https, ipfs, ar".to_string(),
+            }
+        );
+        test.execute_set_collection_metadata(
+            test.creator.clone(),
+            Some(collection_metadata.clone()),
+        )
+        .unwrap();
+        assert_eq!(
+            test.query_config().collection_metadata,
+            Some(collection_metadata.clone())
+        );
+        test.execute_set_collection_metadata(test.creator.clone(), None)
+            .unwrap();
+        assert_eq!(test.query_config().collection_metadata, None);
+    }
+
+    // case 2: receive on home chain does not sync passport collection
+    {
+        let mut test = Test::new();
+        test.execute_passport_mint(test.nft_owner.clone()).unwrap();
+        let res = test
+            .execute_receive_callback(
+                test.addr_ics721_contract.clone(),
+                ClassId::new("some/class/id"),
+                CallbackData {
+                    sender: test.other_chain_wallet.to_string(),
+                    token_id: "0".to_string(),
+                    default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+                    escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+                    transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                    royalty_info: None,
+                    collection_metadata: Some(collection_metadata),
+                    metadata_policy: MetadataPolicy::Passport,
+                },
+                "0".to_string(),
+                test.nft_owner.to_string(),
+                COUNTERPARTY_CONTRACT.to_string(),
+            )
+            .unwrap();
+        let wasm_event = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+        assert!(wasm_event
+            .attributes
+            .iter()
+            .any(|a| a.key == "sync_collection" && a.value == "false"));
+    }
+}
+
+#[test]
+fn test_collection_policy() {
+    let mut test = Test::new();
+    // collection not created by passport
+    let code_id_cw721 = test
+        .app
+        .wrap()
+        .query_wasm_contract_info(test.addr_cw721_contract.clone())
+        .unwrap()
+        .code_id;
+    let other_cw721 = test
+        .app
+        .instantiate_contract(
+            code_id_cw721,
+            test.creator.clone(),
+            &Cw721InstantiateMsg::<DefaultOptionalCollectionExtensionMsg> {
+                name: "other".to_string(),
+                symbol: "other".to_string(),
+                collection_info_extension: None,
+                minter: None,
+                creator: None,
+                withdraw_address: None,
+            },
+            &[],
+            "other",
+            None,
+        )
+        .unwrap();
+
+    // case 1: passport collection and collections created by passport use passport policy, others are not allowed
+    assert_eq!(
+        test.query_collection_policy(test.addr_cw721_contract.clone()),
+        Some(MetadataPolicy::Passport)
+    );
+    assert_eq!(
+        test.query_collection_policy(test.addr_poap_contract.clone()),
+        Some(MetadataPolicy::Passport)
+    );
+    assert_eq!(test.query_collection_policy(other_cw721.clone()), None);
+    let receive_msg = Cw721ReceiveMsg {
+        sender: test.nft_owner.to_string(),
+        token_id: "1".to_string(),
+        msg: to_json_binary(&IbcOutgoingMsg {
+            receiver: "receiver".to_string(),
+            channel_id: WHITELISTED_CHANNEL.to_string(),
+            timeout: IbcTimeout::with_timestamp(Timestamp::from_seconds(0)),
+            memo: None,
+        })
+        .unwrap(),
+    };
+    let err: ContractError = test
+        .app
+        .execute_contract(
+            other_cw721.clone(),
+            test.addr_arkite_contract.clone(),
+            &ExecuteMsg::ReceiveNft(receive_msg),
+            &[],
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::CollectionNotAllowed {
+            cw721: other_cw721.to_string(),
+        }
+    );
+
+    // case 2: only owner sets collection policy
+    let err: ContractError = test
+        .execute_set_collection_policy(
+            test.nft_owner.clone(),
+            other_cw721.clone(),
+            Some(MetadataPolicy::PassThrough),
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+    // passport policy requires passport to be creator of collection
+    let err: ContractError = test
+        .execute_set_collection_policy(
+            test.creator.clone(),
+            other_cw721.clone(),
+            Some(MetadataPolicy::Passport),
+        )
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::NotCollectionCreator {
+            cw721: other_cw721.to_string(),
+            creator: test.creator.to_string(),
+        }
+    );
+    assert_eq!(test.query_collection_policy(other_cw721.clone()), None);
+    test.execute_set_collection_policy(
+        test.creator.clone(),
+        other_cw721.clone(),
+        Some(MetadataPolicy::PassThrough),
+    )
+    .unwrap();
+    assert_eq!(
+        test.query_collection_policy(other_cw721.clone()),
+        Some(MetadataPolicy::PassThrough)
+    );
+    assert_eq!(
+        test.query_collection_policies(),
+        vec![CollectionPolicyResponse {
+            cw721: other_cw721.to_string(),
+            policy: MetadataPolicy::PassThrough,
+        }]
+    );
+
+    // case 3: pass through leaves NFT of allowed collection untouched on ack
+    test.app
+        .execute_contract(
+            test.creator.clone(),
+            other_cw721.clone(),
+            &cw721_base::msg::ExecuteMsg::<
+                DefaultOptionalNftExtensionMsg,
+                DefaultOptionalCollectionExtensionMsg,
+                Empty,
+            >::Mint {
+                token_id: "1".to_string(),
+                owner: test.nft_owner.to_string(),
+                token_uri: Some("ipfs://other".to_string()),
+                extension: None,
+            },
+            &[],
+        )
+        .unwrap();
+    let res = test
+        .app
+        .execute_contract(
+            test.addr_ics721_contract.clone(),
+            test.addr_arkite_contract.clone(),
+            &ExecuteMsg::Ics721AckCallback(Ics721AckCallbackMsg {
+                status: Ics721Status::Success,
+                nft_contract: other_cw721.to_string(),
+                msg: to_json_binary(&CallbackData {
+                    sender: test.nft_owner.to_string(),
+                    token_id: "1".to_string(),
+                    default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+                    escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+                    transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                    royalty_info: None,
+                    collection_metadata: None,
+                    metadata_policy: MetadataPolicy::PassThrough,
+                })
+                .unwrap(),
+                original_packet: NonFungibleTokenPacketData {
+                    class_id: ClassId::new(other_cw721.to_string()),
+                    token_ids: vec![TokenId::new("1")],
+                    receiver: "receiver".to_string(),
+                    sender: test.addr_arkite_contract.to_string(),
+                    class_data: None,
+                    class_uri: None,
+                    memo: None,
+                    token_data: None,
+                    token_uris: None,
+                },
+            }),
+            &[],
+        )
+        .unwrap();
+    assert!(!res.events.iter().any(|event| event
+        .attributes
+        .iter()
+        .any(|attribute| attribute.key == "new_token_uri")));
+    let all_nft_info = test.query_cw721_all_nft_info(other_cw721.clone(), "1".to_string());
+    assert_eq!(
+        all_nft_info.info.token_uri,
+        Some("ipfs://other".to_string())
+    );
+
+    // case 4: removed collection is not allowed anymore
+    test.execute_set_collection_policy(test.creator.clone(), other_cw721.clone(), None)
+        .unwrap();
+    assert_eq!(test.query_collection_policy(other_cw721), None);
+    assert_eq!(test.query_collection_policies(), vec![]);
+}
@@ -0,0 +1,1271 @@
+use cosmwasm_std::{coin, coins, Decimal};
+use cw721_base::{msg::AllNftInfoResponse, DefaultOptionalNftExtension};
+
+use super::ibc_harness::{
+    InterchainTest, MultiHopTest, PacketAck, PacketOutcome, CHANNEL_B_TO_C, DEFAULT_TOKEN_URI,
+    ESCROWED_TOKEN_URI, TRANSFERRED_TOKEN_URI, TRANSFER_TIMEOUT_SECONDS,
+};
+use crate::{
+    error::ContractError,
+    events::{
+        parse_events, IncentiveRefunded, IncentiveReleased, MetadataUpdated, PassportEvent,
+        PassportMinted, PoapMinted, TransferAcked, TransferFailed, TransferInitiated,
+    },
+    execute::royalty_info_from_traits,
+    msg::{HomeLocationResponse, MigrateMsg, PacketIncentive},
+    state::{
+        CollectionMetadata, Counters, MetadataPolicy, OutgoingTransfer, RemoteLocation, RoyaltyInfo,
+    },
+};
+
+const NFT_OWNER_WALLET: &str = "nft_owner";
+const DENOM: &str = "uosmo";
+
+fn trait_value(
+    nft_info: &AllNftInfoResponse<DefaultOptionalNftExtension>,
+    trait_type: &str,
+) -> Option<String> {
+    nft_info
+        .info
+        .extension
+        .clone()
+        .and_then(|extension| extension.attributes)
+        .and_then(|attributes| {
+            attributes
+                .into_iter()
+                .find(|attribute| attribute.trait_type == trait_type)
+        })
+        .map(|attribute| attribute.value)
+}
+
+#[test]
+fn test_interchain_transfer_and_back_transfer() {
+    let mut test = InterchainTest::new();
+    let owner_a = test.chain_a.addr_make(NFT_OWNER_WALLET);
+    let owner_b = test.chain_b.addr_make(NFT_OWNER_WALLET);
+    let cw721_a = test.chain_a.addr_cw721_contract.clone();
+    let res = test.chain_a.execute_passport_mint(owner_a.clone()).unwrap();
+    let token_id = "0";
+    assert_eq!(
+        parse_events(&res.events).unwrap(),
+        vec![PassportEvent::PassportMinted(PassportMinted {
+            cw721: cw721_a.to_string(),
+            token_id: token_id.to_string(),
+            owner: owner_a.to_string(),
+        })]
+    );
+
+    // case 1: transfer from chain A to chain B
+    {
+        let res = test
+            .chain_a
+            .execute_transfer_nft(
+                owner_a.clone(),
+                cw721_a.clone(),
+                token_id,
+                owner_b.to_string(),
+            )
+            .unwrap();
+        assert_eq!(
+            parse_events(&res.events).unwrap(),
+            vec![PassportEvent::TransferInitiated(TransferInitiated {
+                cw721: cw721_a.to_string(),
+                token_id: token_id.to_string(),
+                sender: owner_a.to_string(),
+                receiver: owner_b.to_string(),
+                channel_id: test.chain_a.channel_id.clone(),
+            })]
+        );
+        let relayed_packets = test.relay_packets();
+        assert_eq!(relayed_packets.len(), 1);
+        assert_eq!(relayed_packets[0].src_chain, test.chain_a.chain_id);
+        assert!(matches!(
+            relayed_packets[0].outcome,
+            PacketOutcome::Ack(PacketAck::Result(_))
+        ));
+
+        // receive callback on chain B, followed by ack callback on chain A
+        let voucher_b = test
+            .chain_b
+            .query_nft_contract(test.class_id_on_chain_b(&cw721_a))
+            .unwrap();
+        assert_eq!(
+            parse_events(&relayed_packets[0].events).unwrap(),
+            vec![
+                PassportEvent::MetadataUpdated(MetadataUpdated {
+                    cw721: voucher_b.to_string(),
+                    token_id: token_id.to_string(),
+                    old_token_uri: DEFAULT_TOKEN_URI.to_string(),
+                    new_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                }),
+                PassportEvent::PoapMinted(PoapMinted {
+                    poap: test.chain_b.addr_poap_contract.to_string(),
+                    token_id: "0".to_string(),
+                    receiver: owner_b.to_string(),
+                }),
+                PassportEvent::TransferAcked(TransferAcked {
+                    cw721: cw721_a.to_string(),
+                    token_id: token_id.to_string(),
+                    sender: owner_a.to_string(),
+                }),
+                PassportEvent::MetadataUpdated(MetadataUpdated {
+                    cw721: cw721_a.to_string(),
+                    token_id: token_id.to_string(),
+                    old_token_uri: DEFAULT_TOKEN_URI.to_string(),
+                    new_token_uri: ESCROWED_TOKEN_URI.to_string(),
+                }),
+            ]
+        );
+
+        // chain A: nft is escrowed by ics721, and ack callback sets escrowed uri
+        let nft_info = test
+            .chain_a
+            .query_cw721_all_nft_info(cw721_a.clone(), token_id)
+            .unwrap();
+        assert_eq!(
+            nft_info.access.owner,
+            test.chain_a.addr_ics721_contract.to_string()
+        );
+        assert_eq!(
+            nft_info.info.token_uri,
+            Some(ESCROWED_TOKEN_URI.to_string())
+        );
+
+        // chain B: voucher owned by receiver, and receive callback sets transferred uri
+        let voucher_b = test
+            .chain_b
+            .query_nft_contract(test.class_id_on_chain_b(&cw721_a))
+            .unwrap();
+        let nft_info = test
+            .chain_b
+            .query_cw721_all_nft_info(voucher_b.clone(), token_id)
+            .unwrap();
+        assert_eq!(nft_info.access.owner, owner_b.to_string());
+        assert_eq!(
+            nft_info.info.token_uri,
+            Some(TRANSFERRED_TOKEN_URI.to_string())
+        );
+
+        // chain B: receiver got a poap
+        let poap_b = test.chain_b.addr_poap_contract.clone();
+        assert_eq!(test.chain_b.query_cw721_num_tokens(poap_b), 1);
+        assert_eq!(
+            test.chain_a.query_counters(),
+            Counters {
+                passports_minted: 1,
+                transfers_acked: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            test.chain_b.query_counters(),
+            Counters {
+                poaps_minted: 1,
+                ..Default::default()
+            }
+        );
+    }
+
+    // case 2: back transfer from chain B to chain A
+    {
+        let voucher_b = test
+            .chain_b
+            .query_nft_contract(test.class_id_on_chain_b(&cw721_a))
+            .unwrap();
+        test.chain_b
+            .execute_transfer_nft(
+                owner_b.clone(),
+                voucher_b.clone(),
+                token_id,
+                owner_a.to_string(),
+            )
+            .unwrap();
+        let relayed_packets = test.relay_packets();
+        assert_eq!(relayed_packets.len(), 1);
+        assert_eq!(relayed_packets[0].src_chain, test.chain_b.chain_id);
+        assert!(matches!(
+            relayed_packets[0].outcome,
+            PacketOutcome::Ack(PacketAck::Result(_))
+        ));
+
+        // chain A: nft is back at owner with default uri
+        let nft_info = test
+            .chain_a
+            .query_cw721_all_nft_info(cw721_a.clone(), token_id)
+            .unwrap();
+        assert_eq!(nft_info.access.owner, owner_a.to_string());
+        assert_eq!(nft_info.info.token_uri, Some(DEFAULT_TOKEN_URI.to_string()));
+
+        // chain A: owner got a poap
+        let poap_a = test.chain_a.addr_poap_contract.clone();
+        assert_eq!(test.chain_a.query_cw721_num_tokens(poap_a), 1);
+
+        // chain B: voucher is burned
+        assert_eq!(test.chain_b.query_cw721_num_tokens(voucher_b.clone()), 0);
+        test.chain_b
+            .query_cw721_all_nft_info(voucher_b, token_id)
+            .unwrap_err();
+    }
+}
+
+#[test]
+fn test_interchain_transfer_ack_fail() {
+    let mut test = InterchainTest::new();
+    let owner_a = test.chain_a.addr_make(NFT_OWNER_WALLET);
+    let cw721_a = test.chain_a.addr_cw721_contract.clone();
+    test.chain_a.execute_passport_mint(owner_a.clone()).unwrap();
+    let token_id = "0";
+
+    // invalid receiver on chain B, results in an error ack
+    test.chain_a
+        .execute_transfer_nft(
+            owner_a.clone(),
+            cw721_a.clone(),
+            token_id,
+            "invalid_receiver".to_string(),
+        )
+        .unwrap();
+    let relayed_packets = test.relay_packets();
+    assert_eq!(relayed_packets.len(), 1);
+    assert!(matches!(
+        relayed_packets[0].outcome,
+        PacketOutcome::Ack(PacketAck::Error(_))
+    ));
+    let events = parse_events(&relayed_packets[0].events).unwrap();
+    assert_eq!(events.len(), 1);
+    let PassportEvent::TransferFailed(TransferFailed {
+        cw721,
+        token_id: failed_token_id,
+        sender,
+        error,
+    }) = &events[0]
+    else {
+        panic!("expected transfer failed event, got {events:?}");
+    };
+    assert_eq!(cw721, cw721_a.as_str());
+    assert_eq!(failed_token_id, token_id);
+    assert_eq!(sender, owner_a.as_str());
+    assert!(!error.is_empty());
+
+    // chain A: nft is returned to owner, with default uri
+    let nft_info = test
+        .chain_a
+        .query_cw721_all_nft_info(cw721_a.clone(), token_id)
+        .unwrap();
+    assert_eq!(nft_info.access.owner, owner_a.to_string());
+    assert_eq!(nft_info.info.token_uri, Some(DEFAULT_TOKEN_URI.to_string()));
+    assert_eq!(test.chain_a.query_counters().transfers_failed, 1);
+
+    // chain B: no voucher and no poap
+    assert_eq!(
+        test.chain_b
+            .query_nft_contract(test.class_id_on_chain_b(&cw721_a)),
+        None
+    );
+    let poap_b = test.chain_b.addr_poap_contract.clone();
+    assert_eq!(test.chain_b.query_cw721_num_tokens(poap_b), 0);
+}
+
+#[test]
+fn test_interchain_transfer_timeout() {
+    let mut test = InterchainTest::new();
+    let owner_a = test.chain_a.addr_make(NFT_OWNER_WALLET);
+    let owner_b = test.chain_b.addr_make(NFT_OWNER_WALLET);
+    let cw721_a = test.chain_a.addr_cw721_contract.clone();
+    test.chain_a.execute_passport_mint(owner_a.clone()).unwrap();
+    let token_id = "0";
+
+    test.chain_a
+        .execute_transfer_nft(
+            owner_a.clone(),
+            cw721_a.clone(),
+            token_id,
+            owner_b.to_string(),
+        )
+        .unwrap();
+    // packet is relayed after timeout
+    test.chain_b.app.update_block(|block| {
+        block.time = block.time.plus_seconds(TRANSFER_TIMEOUT_SECONDS + 1);
+    });
+    let relayed_packets = test.relay_packets();
+    assert_eq!(relayed_packets.len(), 1);
+    assert_eq!(relayed_packets[0].outcome, PacketOutcome::Timeout);
+
+    // chain A: nft is returned to owner, with default uri
+    let nft_info = test
+        .chain_a
+        .query_cw721_all_nft_info(cw721_a.clone(), token_id)
+        .unwrap();
+    assert_eq!(nft_info.access.owner, owner_a.to_string());
+    assert_eq!(nft_info.info.token_uri, Some(DEFAULT_TOKEN_URI.to_string()));
+    assert_eq!(test.chain_a.query_counters().transfers_failed, 1);
+
+    // chain B: nothing received
+    assert_eq!(
+        test.chain_b
+            .query_nft_contract(test.class_id_on_chain_b(&cw721_a)),
+        None
+    );
+}
+
+#[test]
+fn test_interchain_relayer_pools() {
+    let setup = || {
+        let mut test = InterchainTest::new();
+        let relayer = test.chain_a.relayer.clone();
+        test.chain_a.execute_set_relayer_pool(relayer).unwrap();
+        let owner_a = test.chain_a.addr_make(NFT_OWNER_WALLET);
+        let other_payer = test.chain_a.addr_make("other_payer");
+        test.chain_a.fund(&owner_a, coins(1000, DENOM));
+        test.chain_a.fund(&other_payer, coins(1000, DENOM));
+        test.chain_a.execute_passport_mint(owner_a.clone()).unwrap();
+        let owner_b = test.chain_b.addr_make(NFT_OWNER_WALLET);
+        let cw721_a = test.chain_a.addr_cw721_contract.clone();
+        test.chain_a
+            .execute_transfer_nft(owner_a.clone(), cw721_a, "0", owner_b.to_string())
+            .unwrap();
+        (test, owner_a, owner_b, other_payer)
+    };
+
+    // case 1: incentives are released to relayer on successful ack
+    {
+        let (mut test, owner_a, owner_b, other_payer) = setup();
+        let cw721_a = test.chain_a.addr_cw721_contract.clone();
+        assert_eq!(
+            test.chain_a.query_outgoing_transfer(&cw721_a, "0"),
+            Some(OutgoingTransfer {
+                sender: owner_a.to_string(),
+                receiver: owner_b.to_string(),
+                channel_id: test.chain_a.channel_id.clone(),
+            })
+        );
+        for _ in 0..2 {
+            test.chain_a
+                .execute_pay_packet_incentive(
+                    owner_a.clone(),
+                    cw721_a.clone(),
+                    "0",
+                    &coins(50, DENOM),
+                )
+                .unwrap();
+        }
+        test.chain_a
+            .execute_pay_packet_incentive(
+                other_payer.clone(),
+                cw721_a.clone(),
+                "0",
+                &coins(30, DENOM),
+            )
+            .unwrap();
+        let mut incentives = vec![
+            PacketIncentive {
+                payer: owner_a.to_string(),
+                amount: coins(100, DENOM),
+            },
+            PacketIncentive {
+                payer: other_payer.to_string(),
+                amount: coins(30, DENOM),
+            },
+        ];
+        incentives.sort_by(|a, b| a.payer.cmp(&b.payer));
+        assert_eq!(
+            test.chain_a.query_packet_incentives(&cw721_a, "0"),
+            incentives
+        );
+
+        let relayed_packets = test.relay_packets();
+        assert!(matches!(
+            relayed_packets[0].outcome,
+            PacketOutcome::Ack(PacketAck::Result(_))
+        ));
+        let relayer = test.chain_a.relayer.clone();
+        assert_eq!(test.chain_a.query_balance(&relayer, DENOM), 130);
+        assert_eq!(test.chain_a.query_balance(&owner_a, DENOM), 900);
+        assert!(parse_events(&relayed_packets[0].events).unwrap().contains(
+            &PassportEvent::IncentiveReleased(IncentiveReleased {
+                cw721: cw721_a.to_string(),
+                token_id: "0".to_string(),
+                relayer_pool: relayer.to_string(),
+                amount: coin(130, DENOM).to_string(),
+            })
+        ));
+        assert_eq!(test.chain_a.query_outgoing_transfer(&cw721_a, "0"), None);
+        assert_eq!(test.chain_a.query_packet_incentives(&cw721_a, "0"), vec![]);
+
+        // nft is escrowed, no outgoing transfer to incentivize anymore
+        let err: ContractError = test
+            .chain_a
+            .execute_pay_packet_incentive(owner_a, cw721_a.clone(), "0", &coins(50, DENOM))
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::NoOutgoingTransfer {
+                cw721: cw721_a.to_string(),
+                token_id: "0".to_string(),
+            }
+        );
+    }
+
+    // case 2: incentives are refunded to payers on timeout
+    {
+        let (mut test, owner_a, _, other_payer) = setup();
+        let cw721_a = test.chain_a.addr_cw721_contract.clone();
+        test.chain_a
+            .execute_pay_packet_incentive(owner_a.clone(), cw721_a.clone(), "0", &coins(100, DENOM))
+            .unwrap();
+        test.chain_a
+            .execute_pay_packet_incentive(
+                other_payer.clone(),
+                cw721_a.clone(),
+                "0",
+                &coins(30, DENOM),
+            )
+            .unwrap();
+        test.chain_b.app.update_block(|block| {
+            block.time = block.time.plus_seconds(TRANSFER_TIMEOUT_SECONDS + 1);
+        });
+        let relayed_packets = test.relay_packets();
+        assert_eq!(relayed_packets[0].outcome, PacketOutcome::Timeout);
+
+        let relayer = test.chain_a.relayer.clone();
+        assert_eq!(test.chain_a.query_balance(&relayer, DENOM), 0);
+        assert_eq!(test.chain_a.query_balance(&owner_a, DENOM), 1000);
+        assert_eq!(test.chain_a.query_balance(&other_payer, DENOM), 1000);
+        let events = parse_events(&relayed_packets[0].events).unwrap();
+        assert!(
+            events.contains(&PassportEvent::IncentiveRefunded(IncentiveRefunded {
+                cw721: cw721_a.to_string(),
+                token_id: "0".to_string(),
+                payer: owner_a.to_string(),
+                amount: coin(100, DENOM).to_string(),
+            }))
+        );
+        assert_eq!(test.chain_a.query_outgoing_transfer(&cw721_a, "0"), None);
+    }
+
+    // case 3: incentives of back transfer are released, though voucher is burned before ack
+    {
+        let (mut test, owner_a, owner_b, _) = setup();
+        test.relay_packets();
+        let relayer_b = test.chain_b.relayer.clone();
+        test.chain_b
+            .execute_set_relayer_pool(relayer_b.clone())
+            .unwrap();
+        test.chain_b.fund(&owner_b, coins(1000, DENOM));
+        let cw721_a = test.chain_a.addr_cw721_contract.clone();
+        let voucher_b = test
+            .chain_b
+            .query_nft_contract(test.class_id_on_chain_b(&cw721_a))
+            .unwrap();
+        test.chain_b
+            .execute_transfer_nft(owner_b.clone(), voucher_b.clone(), "0", owner_a.to_string())
+            .unwrap();
+        test.chain_b
+            .execute_pay_packet_incentive(
+                owner_b.clone(),
+                voucher_b.clone(),
+                "0",
+                &coins(70, DENOM),
+            )
+            .unwrap();
+        assert_eq!(
+            test.chain_b.query_outgoing_transfer(&voucher_b, "0"),
+            Some(OutgoingTransfer {
+                sender: owner_b.to_string(),
+                receiver: owner_a.to_string(),
+                channel_id: test.chain_b.channel_id.clone(),
+            })
+        );
+
+        let relayed_packets = test.relay_packets();
+        assert!(matches!(
+            relayed_packets[0].outcome,
+            PacketOutcome::Ack(PacketAck::Result(_))
+        ));
+        // voucher is burned and passport is back home
+        assert!(test
+            .chain_b
+            .query_cw721_all_nft_info(voucher_b.clone(), "0")
+            .is_err());
+        let nft_info = test.chain_a.query_cw721_all_nft_info(cw721_a, "0").unwrap();
+        assert_eq!(nft_info.access.owner, owner_a.to_string());
+        // ack on chain B settled outgoing transfer and released incentives
+        assert_eq!(test.chain_b.query_outgoing_transfer(&voucher_b, "0"), None);
+        assert_eq!(
+            test.chain_b.query_packet_incentives(&voucher_b, "0"),
+            vec![]
+        );
+        assert_eq!(test.chain_b.query_balance(&relayer_b, DENOM), 70);
+        assert_eq!(test.chain_b.query_balance(&owner_b, DENOM), 930);
+        let arkite_b = test.chain_b.addr_arkite_contract.clone();
+        assert_eq!(test.chain_b.query_balance(&arkite_b, DENOM), 0);
+        assert!(parse_events(&relayed_packets[0].events).unwrap().contains(
+            &PassportEvent::IncentiveReleased(IncentiveReleased {
+                cw721: voucher_b.to_string(),
+                token_id: "0".to_string(),
+                relayer_pool: relayer_b.to_string(),
+                amount: coin(70, DENOM).to_string(),
+            })
+        ));
+        assert_eq!(test.chain_b.query_counters().transfers_acked, 1);
+    }
+}
+
+#[test]
+fn test_interchain_royalty_info() {
+    let mut test = InterchainTest::new();
+    let royalty_info = RoyaltyInfo {
+        payment_address: test.chain_a.addr_make("creator_royalty").to_string(),
+        share: Decimal::percent(5),
+    };
+    test.chain_a
+        .execute_set_royalty_info(Some(royalty_info.clone()))
+        .unwrap();
+    let owner_a = test.chain_a.addr_make(NFT_OWNER_WALLET);
+    let owner_b = test.chain_b.addr_make(NFT_OWNER_WALLET);
+    let cw721_a = test.chain_a.addr_cw721_contract.clone();
+    test.chain_a.execute_passport_mint(owner_a.clone()).unwrap();
+    let token_id = "0";
+
+    // case 1: voucher on chain B gets royalty of passport
+    {
+        test.chain_a
+            .execute_transfer_nft(
+                owner_a.clone(),
+                cw721_a.clone(),
+                token_id,
+                owner_b.to_string(),
+            )
+            .unwrap();
+        test.relay_packets();
+        let voucher_b = test
+            .chain_b
+            .query_nft_contract(test.class_id_on_chain_b(&cw721_a))
+            .unwrap();
+        for (chain, cw721, token_uri) in [
+            (&test.chain_a, cw721_a.clone(), ESCROWED_TOKEN_URI),
+            (&test.chain_b, voucher_b, TRANSFERRED_TOKEN_URI),
+        ] {
+            let nft_info = chain.query_cw721_all_nft_info(cw721, token_id).unwrap();
+            assert_eq!(nft_info.info.token_uri, Some(token_uri.to_string()));
+            let attributes = nft_info.info.extension.unwrap().attributes.unwrap();
+            assert_eq!(
+                royalty_info_from_traits(&attributes),
+                Some(royalty_info.clone())
+            );
+        }
+    }
+
+    // case 2: passport keeps royalty on back transfer, though chain B has none configured
+    {
+        let voucher_b = test
+            .chain_b
+            .query_nft_contract(test.class_id_on_chain_b(&cw721_a))
+            .unwrap();
+        test.chain_b
+            .execute_transfer_nft(owner_b, voucher_b, token_id, owner_a.to_string())
+            .unwrap();
+        test.relay_packets();
+        let nft_info = test
+            .chain_a
+            .query_cw721_all_nft_info(cw721_a, token_id)
+            .unwrap();
+        assert_eq!(nft_info.info.token_uri, Some(DEFAULT_TOKEN_URI.to_string()));
+        let attributes = nft_info.info.extension.unwrap().attributes.unwrap();
+        assert_eq!(royalty_info_from_traits(&attributes), Some(royalty_info));
+    }
+}
+
+#[test]
+fn test_interchain_collection_metadata() {
+    let mut test = InterchainTest::new();
+    let collection_metadata = CollectionMetadata {
+        description: Some("Arkite passports, bridged".to_string()),
+        image: Some("ipfs://interchain.collection".to_string()),
+        external_link: Some("https://arkite.example".to_string()),
+    };
+    test.chain_a
+        .execute_set_collection_metadata(Some(collection_metadata.clone()))
+        .unwrap();
+    let owner_a = test.chain_a.addr_make(NFT_OWNER_WALLET);
+    let owner_b = test.chain_b.addr_make(NFT_OWNER_WALLET);
+    let cw721_a = test.chain_a.addr_cw721_contract.clone();
+    let home_collection_info = test.chain_a.query_cw721_collection_info(cw721_a.clone());
+
+    // case 1: first receive sets collection info of voucher collection
+    {
+        test.chain_a.execute_passport_mint(owner_a.clone()).unwrap();
+        test.chain_a
+            .execute_transfer_nft(owner_a.clone(), cw721_a.clone(), "0", owner_b.to_string())
+            .unwrap();
+        test.relay_packets();
+        let voucher_b = test
+            .chain_b
+            .query_nft_contract(test.class_id_on_chain_b(&cw721_a))
+            .unwrap();
+        let extension = test
+            .chain_b
+            .query_cw721_collection_info(voucher_b)
+            .extension
+            .unwrap();
+        assert_eq!(Some(extension.description), collection_metadata.description);
+        assert_eq!(Some(extension.image), collection_metadata.image);
+        assert_eq!(extension.external_link, collection_metadata.external_link);
+    }
+
+    // case 2: voucher collection is synced only once
+    {
+        test.chain_a
+            .execute_set_collection_metadata(Some(CollectionMetadata {
+                description: Some("changed".to_string()),
+                ..Default::default()
+            }))
+            .unwrap();
+        test.chain_a.execute_passport_mint(owner_a.clone()).unwrap();
+        test.chain_a
+            .execute_transfer_nft(owner_a.clone(), cw721_a.clone(), "1", owner_b.to_string())
+            .unwrap();
+        test.relay_packets();
+        let voucher_b = test
+            .chain_b
+            .query_nft_contract(test.class_id_on_chain_b(&cw721_a))
+            .unwrap();
+        let extension = test
+            .chain_b
+            .query_cw721_collection_info(voucher_b)
+            .extension
+            .unwrap();
+        assert_eq!(Some(extension.description), collection_metadata.description);
+    }
+
+    // case 3: passport collection on home chain is never touched
+    {
+        let voucher_b = test
+            .chain_b
+            .query_nft_contract(test.class_id_on_chain_b(&cw721_a))
+            .unwrap();
+        test.chain_b
+            .execute_set_collection_metadata(Some(collection_metadata))
+            .unwrap();
+        test.chain_b
+            .execute_transfer_nft(owner_b, voucher_b, "0", owner_a.to_string())
+            .unwrap();
+        test.relay_packets();
+        let nft_info = test
+            .chain_a
+            .query_cw721_all_nft_info(cw721_a.clone(), "0")
+            .unwrap();
+        assert_eq!(nft_info.access.owner, owner_a.to_string());
+        assert_eq!(
+            test.chain_a.query_cw721_collection_info(cw721_a),
+            home_collection_info
+        );
+    }
+}
+
+#[test]
+fn test_interchain_collection_metadata_retry() {
+    let mut test = InterchainTest::new();
+    let owner_a = test.chain_a.addr_make(NFT_OWNER_WALLET);
+    let owner_b = test.chain_b.addr_make(NFT_OWNER_WALLET);
+    let cw721_a = test.chain_a.addr_cw721_contract.clone();
+
+    // case 1: collection info rejected by voucher collection, transfer succeeds and collection is not synced
+    {
+        test.chain_a
+            .execute_set_collection_metadata(Some(CollectionMetadata {
+                description: Some("a".repeat(1024)),
+                ..Default::default()
+            }))
+            .unwrap();
+        test.chain_a.execute_passport_mint(owner_a.clone()).unwrap();
+        test.chain_a
+            .execute_transfer_nft(owner_a.clone(), cw721_a.clone(), "0", owner_b.to_string())
+            .unwrap();
+        test.relay_packets();
+        let voucher_b = test
+            .chain_b
+            .query_nft_contract(test.class_id_on_chain_b(&cw721_a))
+            .unwrap();
+        let nft_info = test
+            .chain_b
+            .query_cw721_all_nft_info(voucher_b.clone(), "0")
+            .unwrap();
+        assert_eq!(nft_info.access.owner, owner_b.to_string());
+        let extension = test
+            .chain_b
+            .query_cw721_collection_info(voucher_b)
+            .extension;
+        assert_ne!(
+            extension.map(|extension| extension.description),
+            Some("a".repeat(1024))
+        );
+    }
+
+    // case 2: failed sync is retried on next receive
+    {
+        let collection_metadata = CollectionMetadata {
+            description: Some("Arkite passports, bridged".to_string()),
+            image: Some("ipfs://interchain.collection".to_string()),
+            external_link: None,
+        };
+        test.chain_a
+            .execute_set_collection_metadata(Some(collection_metadata.clone()))
+            .unwrap();
+        test.chain_a.execute_passport_mint(owner_a.clone()).unwrap();
+        test.chain_a
+            .execute_transfer_nft(owner_a, cw721_a.clone(), "1", owner_b.to_string())
+            .unwrap();
+        test.relay_packets();
+        let voucher_b = test
+            .chain_b
+            .query_nft_contract(test.class_id_on_chain_b(&cw721_a))
+            .unwrap();
+        let extension = test
+            .chain_b
+            .query_cw721_collection_info(voucher_b)
+            .extension
+            .unwrap();
+        assert_eq!(Some(extension.description), collection_metadata.description);
+        assert_eq!(Some(extension.image), collection_metadata.image);
+    }
+}
+
+#[test]
+fn test_interchain_voucher_creator() {
+    // case 1: passport on chain B is creator of voucher collection, and updates voucher
+    {
+        let mut test = InterchainTest::new();
+        let owner_a = test.chain_a.addr_make(NFT_OWNER_WALLET);
+        let owner_b = test.chain_b.addr_make(NFT_OWNER_WALLET);
+        let cw721_a = test.chain_a.addr_cw721_contract.clone();
+        test.chain_a.execute_passport_mint(owner_a.clone()).unwrap();
+        test.chain_a
+            .execute_transfer_nft(owner_a, cw721_a.clone(), "0", owner_b.to_string())
+            .unwrap();
+        test.relay_packets();
+        let voucher_b = test
+            .chain_b
+            .query_nft_contract(test.class_id_on_chain_b(&cw721_a))
+            .unwrap();
+        assert_eq!(
+            test.chain_b
+                .query_cw721_creator_ownership(voucher_b.clone())
+                .owner,
+            Some(test.chain_b.addr_arkite_contract.clone())
+        );
+        let nft_info = test
+            .chain_b
+            .query_cw721_all_nft_info(voucher_b, "0")
+            .unwrap();
+        assert_eq!(
+            nft_info.info.token_uri,
+            Some(TRANSFERRED_TOKEN_URI.to_string())
+        );
+    }
+
+    // case 2: receive callback fails, if passport is not creator of voucher collection
+    {
+        let mut test = InterchainTest::new();
+        let other_creator = test.chain_b.addr_make("other_creator");
+        test.chain_b
+            .migrate_ics721_cw721_creator(other_creator.to_string())
+            .unwrap();
+        let owner_a = test.chain_a.addr_make(NFT_OWNER_WALLET);
+        let owner_b = test.chain_b.addr_make(NFT_OWNER_WALLET);
+        let cw721_a = test.chain_a.addr_cw721_contract.clone();
+        test.chain_a.execute_passport_mint(owner_a.clone()).unwrap();
+        test.chain_a
+            .execute_transfer_nft(owner_a.clone(), cw721_a.clone(), "0", owner_b.to_string())
+            .unwrap();
+        let relayed_packets = test.relay_packets();
+        assert!(matches!(
+            relayed_packets[0].outcome,
+            PacketOutcome::Ack(PacketAck::Error(_))
+        ));
+
+        // chain A: nft is returned to owner
+        let nft_info = test
+            .chain_a
+            .query_cw721_all_nft_info(cw721_a.clone(), "0")
+            .unwrap();
+        assert_eq!(nft_info.access.owner, owner_a.to_string());
+        assert_eq!(test.chain_a.query_counters().transfers_failed, 1);
+
+        // chain B: no voucher
+        assert_eq!(
+            test.chain_b
+                .query_nft_contract(test.class_id_on_chain_b(&cw721_a)),
+            None
+        );
+    }
+}
+
+#[test]
+fn test_interchain_collection_policy() {
+    let mut test = InterchainTest::new();
+    let owner_a = test.chain_a.addr_make(NFT_OWNER_WALLET);
+    let owner_b = test.chain_b.addr_make(NFT_OWNER_WALLET);
+    let other_a = test.chain_a.instantiate_cw721("other");
+    let token_uri = "ipfs://other";
+    test.chain_a
+        .execute_cw721_mint(other_a.clone(), owner_a.clone(), "1", token_uri)
+        .unwrap();
+
+    // case 1: NFTs of collections not allowed are rejected, and stay with their owner
+    {
+        let err: ContractError = test
+            .chain_a
+            .execute_transfer_nft(owner_a.clone(), other_a.clone(), "1", owner_b.to_string())
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::CollectionNotAllowed {
+                cw721: other_a.to_string(),
+            }
+        );
+        let nft_info = test
+            .chain_a
+            .query_cw721_all_nft_info(other_a.clone(), "1")
+            .unwrap();
+        assert_eq!(nft_info.access.owner, owner_a.to_string());
+    }
+
+    // case 2: stamp policy keeps token uri on both chains, and stamps voucher
+    let voucher_b = {
+        test.chain_a
+            .execute_set_collection_policy(other_a.clone(), Some(MetadataPolicy::Stamp))
+            .unwrap();
+        test.chain_a
+            .execute_transfer_nft(owner_a.clone(), other_a.clone(), "1", owner_b.to_string())
+            .unwrap();
+        let relayed_packets = test.relay_packets();
+        assert!(matches!(
+            relayed_packets[0].outcome,
+            PacketOutcome::Ack(PacketAck::Result(_))
+        ));
+        // no metadata updated, neither on receive nor on ack
+        assert!(!parse_events(&relayed_packets[0].events)
+            .unwrap()
+            .iter()
+            .any(|event| matches!(event, PassportEvent::MetadataUpdated(_))));
+
+        let nft_info = test
+            .chain_a
+            .query_cw721_all_nft_info(other_a.clone(), "1")
+            .unwrap();
+        assert_eq!(
+            nft_info.access.owner,
+            test.chain_a.addr_ics721_contract.to_string()
+        );
+        assert_eq!(nft_info.info.token_uri, Some(token_uri.to_string()));
+
+        let voucher_b = test
+            .chain_b
+            .query_nft_contract(test.class_id_on_chain_b(&other_a))
+            .unwrap();
+        let nft_info = test
+            .chain_b
+            .query_cw721_all_nft_info(voucher_b.clone(), "1")
+            .unwrap();
+        assert_eq!(nft_info.access.owner, owner_b.to_string());
+        assert_eq!(nft_info.info.token_uri, Some(token_uri.to_string()));
+        let stamp = nft_info
+            .info
+            .extension
+            .and_then(|extension| extension.attributes)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|attribute| attribute.trait_type == "stamp")
+            .map(|attribute| attribute.value);
+        assert_eq!(stamp, Some(TRANSFERRED_TOKEN_URI.to_string()));
+        // voucher collection uses policy of its home collection
+        assert_eq!(
+            test.chain_b.query_collection_policy(&voucher_b),
+            Some(MetadataPolicy::Stamp)
+        );
+        voucher_b
+    };
+
+    // case 3: back transfer returns NFT untouched, home collection is never stamped
+    {
+        test.chain_b
+            .execute_transfer_nft(owner_b.clone(), voucher_b, "1", owner_a.to_string())
+            .unwrap();
+        let relayed_packets = test.relay_packets();
+        assert!(matches!(
+            relayed_packets[0].outcome,
+            PacketOutcome::Ack(PacketAck::Result(_))
+        ));
+        let nft_info = test
+            .chain_a
+            .query_cw721_all_nft_info(other_a.clone(), "1")
+            .unwrap();
+        assert_eq!(nft_info.access.owner, owner_a.to_string());
+        assert_eq!(nft_info.info.token_uri, Some(token_uri.to_string()));
+        assert_eq!(nft_info.info.extension, None);
+    }
+}
+
+#[test]
+fn test_interchain_remote_location() {
+    let mut test = InterchainTest::new();
+    let owner_a = test.chain_a.addr_make(NFT_OWNER_WALLET);
+    let owner_b = test.chain_b.addr_make(NFT_OWNER_WALLET);
+    let cw721_a = test.chain_a.addr_cw721_contract.clone();
+    test.chain_a.execute_passport_mint(owner_a.clone()).unwrap();
+    let token_id = "0";
+
+    // case 1: no location before transfer
+    assert_eq!(test.chain_a.query_remote_location_record(token_id), None);
+    assert_eq!(test.chain_a.query_home_location(&cw721_a, token_id), None);
+
+    // case 2: failed transfer records no location
+    test.chain_a
+        .execute_transfer_nft(
+            owner_a.clone(),
+            cw721_a.clone(),
+            token_id,
+            "invalid_receiver".to_string(),
+        )
+        .unwrap();
+    test.relay_packets();
+    assert_eq!(test.chain_a.query_remote_location_record(token_id), None);
+
+    // case 3: ack records location on chain A, voucher on chain B resolves to original
+    test.chain_a
+        .execute_transfer_nft(
+            owner_a.clone(),
+            cw721_a.clone(),
+            token_id,
+            owner_b.to_string(),
+        )
+        .unwrap();
+    test.relay_packets();
+    assert_eq!(
+        test.chain_a.query_remote_location_record(token_id),
+        Some(RemoteLocation {
+            channel_id: test.chain_a.channel_id.clone(),
+            class_id: cw721_a.to_string(),
+            receiver: owner_b.to_string(),
+        })
+    );
+    let voucher_b = test
+        .chain_b
+        .query_nft_contract(test.class_id_on_chain_b(&cw721_a))
+        .unwrap();
+    assert_eq!(
+        test.chain_b.query_home_location(&voucher_b, token_id),
+        Some(HomeLocationResponse {
+            channel_id: test.chain_b.channel_id.clone(),
+            class_id: cw721_a.to_string(),
+            token_id: token_id.to_string(),
+        })
+    );
+    // passport collection on chain B is not a voucher
+    let cw721_b = test.chain_b.addr_cw721_contract.clone();
+    assert_eq!(test.chain_b.query_home_location(&cw721_b, token_id), None);
+
+    // case 4: back transfer removes location
+    test.chain_b
+        .execute_transfer_nft(
+            owner_b.clone(),
+            voucher_b.clone(),
+            token_id,
+            owner_a.to_string(),
+        )
+        .unwrap();
+    test.relay_packets();
+    assert_eq!(test.chain_a.query_remote_location_record(token_id), None);
+}
+
+#[test]
+fn test_multi_hop_transfer_and_back_transfer() {
+    let mut test = MultiHopTest::new();
+    let owner_a = test.chain_a.addr_make(NFT_OWNER_WALLET);
+    let owner_b = test.chain_b.addr_make(NFT_OWNER_WALLET);
+    let owner_c = test.chain_c.addr_make(NFT_OWNER_WALLET);
+    let cw721_a = test.chain_a.addr_cw721_contract.clone();
+    let collection_metadata = CollectionMetadata {
+        description: Some("Arkite passports, bridged twice".to_string()),
+        image: Some("ipfs://interchain.collection".to_string()),
+        external_link: None,
+    };
+    test.chain_a
+        .execute_set_collection_metadata(Some(collection_metadata.clone()))
+        .unwrap();
+    // chain B has its own uris, which must not be used for passports of chain A
+    test.chain_b
+        .migrate_arkite_contract(&MigrateMsg::WithUpdate {
+            default_token_uri: Some("ipfs://chain_b.default".to_string()),
+            escrowed_token_uri: Some("ipfs://chain_b.escrowed".to_string()),
+            transferred_token_uri: Some("ipfs://chain_b.transferred".to_string()),
+        })
+        .unwrap();
+    test.chain_a.execute_passport_mint(owner_a.clone()).unwrap();
+    let token_id = "0";
+    // case 1: transfer from chain A to chain B
+    test.chain_a
+        .execute_transfer_nft(
+            owner_a.clone(),
+            cw721_a.clone(),
+            token_id,
+            owner_b.to_string(),
+        )
+        .unwrap();
+    test.relay_packets();
+    let voucher_b = test
+        .chain_b
+        .query_nft_contract(test.class_id_on_chain_b(&cw721_a))
+        .unwrap();
+
+    // case 2: chain B forwards voucher to chain C, callbacks run on chain C and chain B
+    {
+        let res = test
+            .chain_b
+            .execute_transfer_nft_on_channel(
+                owner_b.clone(),
+                voucher_b.clone(),
+                token_id,
+                owner_c.to_string(),
+                CHANNEL_B_TO_C,
+            )
+            .unwrap();
+        assert_eq!(
+            parse_events(&res.events).unwrap(),
+            vec![PassportEvent::TransferInitiated(TransferInitiated {
+                cw721: voucher_b.to_string(),
+                token_id: token_id.to_string(),
+                sender: owner_b.to_string(),
+                receiver: owner_c.to_string(),
+                channel_id: CHANNEL_B_TO_C.to_string(),
+            })]
+        );
+        let relayed_packets = test.relay_packets();
+        assert_eq!(relayed_packets.len(), 1);
+        assert_eq!(relayed_packets[0].src_chain, test.chain_b.chain_id);
+        let voucher_c = test
+            .chain_c
+            .query_nft_contract(test.class_id_on_chain_c(&cw721_a))
+            .unwrap();
+        assert_eq!(
+            parse_events(&relayed_packets[0].events).unwrap(),
+            vec![
+                PassportEvent::MetadataUpdated(MetadataUpdated {
+                    cw721: voucher_c.to_string(),
+                    token_id: token_id.to_string(),
+                    old_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                    new_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                }),
+                PassportEvent::PoapMinted(PoapMinted {
+                    poap: test.chain_c.addr_poap_contract.to_string(),
+                    token_id: "0".to_string(),
+                    receiver: owner_c.to_string(),
+                }),
+                PassportEvent::TransferAcked(TransferAcked {
+                    cw721: voucher_b.to_string(),
+                    token_id: token_id.to_string(),
+                    sender: owner_b.to_string(),
+                }),
+                PassportEvent::MetadataUpdated(MetadataUpdated {
+                    cw721: voucher_b.to_string(),
+                    token_id: token_id.to_string(),
+                    old_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+                    new_token_uri: ESCROWED_TOKEN_URI.to_string(),
+                }),
+            ]
+        );
+
+        // chain A: passport stays escrowed
+        let nft_info = test
+            .chain_a
+            .query_cw721_all_nft_info(cw721_a.clone(), token_id)
+            .unwrap();
+        assert_eq!(
+            nft_info.access.owner,
+            test.chain_a.addr_ics721_contract.to_string()
+        );
+        assert_eq!(
+            nft_info.info.token_uri,
+            Some(ESCROWED_TOKEN_URI.to_string())
+        );
+
+        // chain B: voucher escrowed by ics721
+        let nft_info = test
+            .chain_b
+            .query_cw721_all_nft_info(voucher_b.clone(), token_id)
+            .unwrap();
+        assert_eq!(
+            nft_info.access.owner,
+            test.chain_b.addr_ics721_contract.to_string()
+        );
+        assert_eq!(
+            nft_info.info.token_uri,
+            Some(ESCROWED_TOKEN_URI.to_string())
+        );
+
+        // chain C: voucher of voucher owned by receiver, with uris and collection info of chain A
+        let nft_info = test
+            .chain_c
+            .query_cw721_all_nft_info(voucher_c.clone(), token_id)
+            .unwrap();
+        assert_eq!(nft_info.access.owner, owner_c.to_string());
+        assert_eq!(
+            nft_info.info.token_uri,
+            Some(TRANSFERRED_TOKEN_URI.to_string())
+        );
+        assert_eq!(
+            trait_value(&nft_info, "default_uri"),
+            Some(DEFAULT_TOKEN_URI.to_string())
+        );
+        let extension = test
+            .chain_c
+            .query_cw721_collection_info(voucher_c.clone())
+            .extension
+            .unwrap();
+        assert_eq!(Some(extension.description), collection_metadata.description);
+
+        // chain C: home location is the voucher on chain B, which in turn resolves to chain A
+        assert_eq!(
+            test.chain_c.query_home_location(&voucher_c, token_id),
+            Some(HomeLocationResponse {
+                channel_id: test.chain_c.channel_id.clone(),
+                class_id: test.class_id_on_chain_b(&cw721_a),
+                token_id: token_id.to_string(),
+            })
+        );
+        assert_eq!(
+            test.chain_b
+                .query_home_location(&voucher_b, token_id)
+                .map(|home_location| home_location.class_id),
+            Some(cw721_a.to_string())
+        );
+    }
+
+    // case 3: back transfer from chain C to chain B, voucher on chain B is released as transferred
+    {
+        let voucher_c = test
+            .chain_c
+            .query_nft_contract(test.class_id_on_chain_c(&cw721_a))
+            .unwrap();
+        test.chain_c
+            .execute_transfer_nft(
+                owner_c.clone(),
+                voucher_c.clone(),
+                token_id,
+                owner_b.to_string(),
+            )
+            .unwrap();
+        let relayed_packets = test.relay_packets();
+        assert_eq!(relayed_packets.len(), 1);
+        assert!(matches!(
+            relayed_packets[0].outcome,
+            PacketOutcome::Ack(PacketAck::Result(_))
+        ));
+
+        let nft_info = test
+            .chain_b
+            .query_cw721_all_nft_info(voucher_b.clone(), token_id)
+            .unwrap();
+        assert_eq!(nft_info.access.owner, owner_b.to_string());
+        assert_eq!(
+            nft_info.info.token_uri,
+            Some(TRANSFERRED_TOKEN_URI.to_string())
+        );
+        assert_eq!(test.chain_c.query_cw721_num_tokens(voucher_c), 0);
+        let poap_b = test.chain_b.addr_poap_contract.clone();
+        assert_eq!(test.chain_b.query_cw721_num_tokens(poap_b), 2);
+    }
+
+    // case 4: back transfer from chain B to chain A, passport is home again
+    {
+        test.chain_b
+            .execute_transfer_nft(
+                owner_b.clone(),
+                voucher_b.clone(),
+                token_id,
+                owner_a.to_string(),
+            )
+            .unwrap();
+        test.relay_packets();
+
+        let nft_info = test
+            .chain_a
+            .query_cw721_all_nft_info(cw721_a.clone(), token_id)
+            .unwrap();
+        assert_eq!(nft_info.access.owner, owner_a.to_string());
+        assert_eq!(nft_info.info.token_uri, Some(DEFAULT_TOKEN_URI.to_string()));
+        assert_eq!(test.chain_b.query_cw721_num_tokens(voucher_b), 0);
+        assert_eq!(test.chain_a.query_remote_location_record(token_id), None);
+    }
+}
+
+#[test]
+fn test_multi_hop_counterparty_of_other_channel() {
+    let mut test = MultiHopTest::new();
+    let owner_b = test.chain_b.addr_make(NFT_OWNER_WALLET);
+    let owner_c = test.chain_c.addr_make(NFT_OWNER_WALLET);
+    let cw721_c = test.chain_c.addr_cw721_contract.clone();
+    test.chain_c.execute_passport_mint(owner_c.clone()).unwrap();
+    let token_id = "0";
+
+    // case 1: counterparty of channel to chain C sends over another channel, where chain A is counterparty
+    {
+        test.chain_c
+            .execute_transfer_nft_on_channel(
+                owner_c.clone(),
+                cw721_c.clone(),
+                token_id,
+                owner_b.to_string(),
+                "channel-5",
+            )
+            .unwrap();
+        let relayed_packets = test.relay_packets_on_channel("channel-5", "channel-4");
+        assert_eq!(relayed_packets.len(), 1);
+        assert!(matches!(
+            relayed_packets[0].outcome,
+            PacketOutcome::Ack(PacketAck::Error(_))
+        ));
+
+        // chain C: nft is returned to owner
+        let nft_info = test
+            .chain_c
+            .query_cw721_all_nft_info(cw721_c.clone(), token_id)
+            .unwrap();
+        assert_eq!(nft_info.access.owner, owner_c.to_string());
+
+        // chain B: no voucher
+        let class_id = format!("{}/channel-4/{}", test.chain_b.port_id(), cw721_c);
+        assert_eq!(test.chain_b.query_nft_contract(class_id), None);
+    }
+
+    // case 2: same counterparty sends over its own channel
+    {
+        test.chain_c
+            .execute_transfer_nft(
+                owner_c.clone(),
+                cw721_c.clone(),
+                token_id,
+                owner_b.to_string(),
+            )
+            .unwrap();
+        let relayed_packets = test.relay_packets();
+        assert_eq!(relayed_packets.len(), 1);
+        assert!(matches!(
+            relayed_packets[0].outcome,
+            PacketOutcome::Ack(PacketAck::Result(_))
+        ));
+
+        let class_id = format!("{}/{}/{}", test.chain_b.port_id(), CHANNEL_B_TO_C, cw721_c);
+        let voucher_b = test.chain_b.query_nft_contract(class_id).unwrap();
+        let nft_info = test
+            .chain_b
+            .query_cw721_all_nft_info(voucher_b, token_id)
+            .unwrap();
+        assert_eq!(nft_info.access.owner, owner_b.to_string());
+    }
+}
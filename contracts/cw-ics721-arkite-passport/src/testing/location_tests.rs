@@ -0,0 +1,165 @@
+use cosmwasm_std::{
+    from_json,
+    testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage},
+    to_json_binary, Addr, ContractResult, Empty, IbcChannel, IbcEndpoint, IbcOrder, OwnedDeps,
+    StdError, SystemResult, WasmQuery,
+};
+use ics721::ClassId;
+
+use crate::{
+    execute::query,
+    msg::{HomeLocationResponse, QueryMsg, RemoteLocationResponse},
+    state::{
+        Config, PoapPolicy, RateLimitPolicy, RemoteLocation, TimeoutPolicy, CONFIG,
+        REMOTE_LOCATIONS,
+    },
+};
+
+const ICS721: &str = "ics721";
+const CW721: &str = "cw721";
+const TOKEN_ID: &str = "1";
+
+type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>;
+
+/// Contract with ics721 on a channel to `channel-7` of counterparty ics721, and ics721 returning given class id.
+fn setup(class_id: Option<&'static str>) -> MockDeps {
+    let mut deps = mock_dependencies();
+    CONFIG
+        .save(
+            deps.as_mut().storage,
+            &Config {
+                default_token_uri: "ipfs://default".to_string(),
+                escrowed_token_uri: "ipfs://escrowed".to_string(),
+                transferred_token_uri: "ipfs://transferred".to_string(),
+                addr_cw721: Some(Addr::unchecked(CW721)),
+                addr_poap: Some(Addr::unchecked("poap")),
+                addr_ics721: Some(Addr::unchecked(ICS721)),
+                counterparty_contract: None,
+                poap_policy: PoapPolicy::Always,
+                timeout_policy: TimeoutPolicy::default(),
+                rate_limit_policy: RateLimitPolicy::default(),
+                royalty_info: None,
+                collection_metadata: None,
+            },
+        )
+        .unwrap();
+    deps.querier.update_ibc(
+        "wasm.ics721",
+        &[IbcChannel::new(
+            IbcEndpoint {
+                port_id: "wasm.ics721".to_string(),
+                channel_id: "channel-0".to_string(),
+            },
+            IbcEndpoint {
+                port_id: "wasm.counterparty_ics721".to_string(),
+                channel_id: "channel-7".to_string(),
+            },
+            IbcOrder::Unordered,
+            "ics721-1",
+            "connection-0",
+        )],
+    );
+    deps.querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { contract_addr, .. } if contract_addr == ICS721 => SystemResult::Ok(
+            ContractResult::Ok(to_json_binary(&class_id.map(ClassId::new)).unwrap()),
+        ),
+        _ => panic!("unexpected query {query:?}"),
+    });
+    deps
+}
+
+fn save_remote_location(deps: &mut MockDeps, channel_id: &str) {
+    REMOTE_LOCATIONS
+        .save(
+            deps.as_mut().storage,
+            TOKEN_ID,
+            &RemoteLocation {
+                channel_id: channel_id.to_string(),
+                class_id: CW721.to_string(),
+                receiver: "receiver".to_string(),
+            },
+        )
+        .unwrap();
+}
+
+fn query_remote_location(deps: &MockDeps) -> Result<Option<RemoteLocationResponse>, StdError> {
+    query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::RemoteLocation {
+            token_id: TOKEN_ID.to_string(),
+        },
+    )
+    .and_then(from_json)
+}
+
+fn query_home_location(deps: &MockDeps) -> Option<HomeLocationResponse> {
+    from_json(
+        query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::HomeLocation {
+                cw721: "voucher".to_string(),
+                token_id: TOKEN_ID.to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_query_remote_location() {
+    let mut deps = setup(None);
+
+    // case 1: passport on home chain
+    assert_eq!(query_remote_location(&deps).unwrap(), None);
+
+    // case 2: voucher class id is prefixed by counterparty port and channel
+    save_remote_location(&mut deps, "channel-0");
+    assert_eq!(
+        query_remote_location(&deps).unwrap(),
+        Some(RemoteLocationResponse {
+            channel_id: "channel-0".to_string(),
+            counterparty_channel_id: "channel-7".to_string(),
+            voucher_class_id: "wasm.counterparty_ics721/channel-7/cw721".to_string(),
+            token_id: TOKEN_ID.to_string(),
+            receiver: "receiver".to_string(),
+        })
+    );
+
+    // case 3: unknown channel
+    save_remote_location(&mut deps, "channel-1");
+    query_remote_location(&deps).unwrap_err();
+}
+
+#[test]
+fn test_query_home_location() {
+    // case 1: unknown to ics721
+    assert_eq!(query_home_location(&setup(None)), None);
+
+    // case 2: collection native to this chain
+    assert_eq!(query_home_location(&setup(Some("voucher"))), None);
+
+    // case 3: voucher of a collection from another chain
+    assert_eq!(
+        query_home_location(&setup(Some("wasm.ics721/channel-0/cw721"))),
+        Some(HomeLocationResponse {
+            channel_id: "channel-0".to_string(),
+            class_id: CW721.to_string(),
+            token_id: TOKEN_ID.to_string(),
+        })
+    );
+
+    // case 4: voucher of a voucher keeps full path of home chain
+    assert_eq!(
+        query_home_location(&setup(Some(
+            "wasm.ics721/channel-0/wasm.other_ics721/channel-3/cw721"
+        ))),
+        Some(HomeLocationResponse {
+            channel_id: "channel-0".to_string(),
+            class_id: "wasm.other_ics721/channel-3/cw721".to_string(),
+            token_id: TOKEN_ID.to_string(),
+        })
+    );
+}
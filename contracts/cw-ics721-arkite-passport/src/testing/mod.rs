@@ -1 +1,8 @@
+mod common;
+mod events_tests;
+mod fuzz_tests;
+mod ibc_harness;
 mod integration_tests;
+mod interchain_tests;
+mod location_tests;
+mod property_tests;
@@ -0,0 +1,334 @@
+use cosmwasm_std::Addr;
+use cw721_base::{msg::AllNftInfoResponse, DefaultOptionalNftExtension};
+use proptest::prelude::*;
+
+use super::ibc_harness::{
+    InterchainTest, MultiHopTest, CHANNEL_B_TO_C, DEFAULT_TOKEN_URI, ESCROWED_TOKEN_URI,
+    TRANSFERRED_TOKEN_URI, TRANSFER_TIMEOUT_SECONDS,
+};
+use crate::msg::MigrateMsg;
+
+const NFT_OWNER_WALLET: &str = "nft_owner";
+const TOKEN_ID: &str = "0";
+
+/// Step applied to a passport, which is either on its home chain A or a voucher on chain B.
+#[derive(Debug, Clone)]
+enum Step {
+    /// forward transfer if passport is on chain A, otherwise back transfer
+    Transfer,
+    /// transfer to an invalid receiver, resulting in an error ack
+    FailedTransfer,
+    /// transfer relayed after timeout
+    TimedOutTransfer,
+    /// migrate passport contract with new uris, on chain A (true) or chain B (false)
+    Migrate(bool, u8),
+}
+
+fn step_strategy() -> impl Strategy<Value = Step> {
+    prop_oneof![
+        3 => Just(Step::Transfer),
+        1 => Just(Step::FailedTransfer),
+        1 => Just(Step::TimedOutTransfer),
+        1 => (any::<bool>(), 1u8..5)
+            .prop_map(|(on_chain_a, generation)| Step::Migrate(on_chain_a, generation)),
+    ]
+}
+
+fn trait_value(
+    nft_info: &AllNftInfoResponse<DefaultOptionalNftExtension>,
+    trait_type: &str,
+) -> String {
+    nft_info
+        .info
+        .extension
+        .clone()
+        .and_then(|extension| extension.attributes)
+        .and_then(|attributes| {
+            attributes
+                .into_iter()
+                .find(|attribute| attribute.trait_type == trait_type)
+        })
+        .map(|attribute| attribute.value)
+        .unwrap_or_else(|| panic!("missing trait {trait_type}"))
+}
+
+struct UriStateMachine {
+    test: InterchainTest,
+    owner_a: Addr,
+    owner_b: Addr,
+    /// passport is a voucher on chain B
+    on_chain_b: bool,
+}
+
+impl UriStateMachine {
+    fn new() -> Self {
+        let mut test = InterchainTest::new();
+        let owner_a = test.chain_a.addr_make(NFT_OWNER_WALLET);
+        let owner_b = test.chain_b.addr_make(NFT_OWNER_WALLET);
+        test.chain_a.execute_passport_mint(owner_a.clone()).unwrap();
+        Self {
+            test,
+            owner_a,
+            owner_b,
+            on_chain_b: false,
+        }
+    }
+
+    fn voucher_b(&self) -> Option<Addr> {
+        let class_id = self
+            .test
+            .class_id_on_chain_b(&self.test.chain_a.addr_cw721_contract);
+        self.test.chain_b.query_nft_contract(class_id)
+    }
+
+    fn transfer(&mut self, valid_receiver: bool) {
+        if self.on_chain_b {
+            let receiver = match valid_receiver {
+                true => self.owner_a.to_string(),
+                false => "invalid_receiver".to_string(),
+            };
+            let voucher_b = self.voucher_b().unwrap();
+            self.test
+                .chain_b
+                .execute_transfer_nft(self.owner_b.clone(), voucher_b, TOKEN_ID, receiver)
+                .unwrap();
+        } else {
+            let receiver = match valid_receiver {
+                true => self.owner_b.to_string(),
+                false => "invalid_receiver".to_string(),
+            };
+            let cw721_a = self.test.chain_a.addr_cw721_contract.clone();
+            self.test
+                .chain_a
+                .execute_transfer_nft(self.owner_a.clone(), cw721_a, TOKEN_ID, receiver)
+                .unwrap();
+        }
+    }
+
+    fn apply(&mut self, step: &Step) {
+        match step {
+            Step::Transfer => {
+                self.transfer(true);
+                self.test.relay_packets();
+                self.on_chain_b = !self.on_chain_b;
+            }
+            Step::FailedTransfer => {
+                self.transfer(false);
+                self.test.relay_packets();
+            }
+            Step::TimedOutTransfer => {
+                self.transfer(true);
+                // both chains move forward, so later transfers are not timed out
+                for chain in [&mut self.test.chain_a, &mut self.test.chain_b] {
+                    chain.app.update_block(|block| {
+                        block.time = block.time.plus_seconds(TRANSFER_TIMEOUT_SECONDS + 1);
+                    });
+                }
+                self.test.relay_packets();
+            }
+            Step::Migrate(on_chain_a, generation) => {
+                let chain = match on_chain_a {
+                    true => &mut self.test.chain_a,
+                    false => &mut self.test.chain_b,
+                };
+                chain
+                    .migrate_arkite_contract(&MigrateMsg::WithUpdate {
+                        default_token_uri: Some(format!("ipfs://default.{generation}")),
+                        escrowed_token_uri: Some(format!("ipfs://escrowed.{generation}")),
+                        transferred_token_uri: Some(format!("ipfs://transferred.{generation}")),
+                    })
+                    .unwrap();
+            }
+        }
+    }
+
+    fn assert_invariants(&self) {
+        let chain_a = &self.test.chain_a;
+        let home = chain_a
+            .query_cw721_all_nft_info(chain_a.addr_cw721_contract.clone(), TOKEN_ID)
+            .unwrap();
+        let home_token_uri = home.info.token_uri.clone().unwrap();
+        let default_uri = trait_value(&home, "default_uri");
+        let escrowed_uri = trait_value(&home, "escrowed_uri");
+        // home-chain token is either default or escrowed
+        assert!(
+            home_token_uri == default_uri || home_token_uri == escrowed_uri,
+            "home token uri {home_token_uri} is neither default nor escrowed"
+        );
+        assert_eq!(trait_value(&home, "token_uri"), home_token_uri);
+
+        if self.on_chain_b {
+            assert_eq!(home.access.owner, chain_a.addr_ics721_contract.to_string());
+            assert_eq!(home_token_uri, escrowed_uri);
+            let voucher_b = self.voucher_b().unwrap();
+            let voucher = self
+                .test
+                .chain_b
+                .query_cw721_all_nft_info(voucher_b, TOKEN_ID)
+                .unwrap();
+            assert_eq!(voucher.access.owner, self.owner_b.to_string());
+            // voucher is always transferred
+            let voucher_token_uri = voucher.info.token_uri.clone().unwrap();
+            assert_eq!(
+                voucher_token_uri,
+                trait_value(&voucher, "transferred_uri"),
+                "voucher uri {voucher_token_uri} is not transferred"
+            );
+        } else {
+            assert_eq!(home.access.owner, self.owner_a.to_string());
+            assert_eq!(home_token_uri, default_uri);
+            // no voucher on chain B
+            if let Some(voucher_b) = self.voucher_b() {
+                assert_eq!(self.test.chain_b.query_cw721_num_tokens(voucher_b), 0);
+            }
+        }
+    }
+}
+
+/// Passport moving along chains A - B - C, where it is at `hops` from its home chain A.
+/// Chain B and C have their own uris, which must never be used for passports of chain A.
+struct MultiHopStateMachine {
+    test: MultiHopTest,
+    owners: [Addr; 3],
+    hops: usize,
+}
+
+impl MultiHopStateMachine {
+    fn new() -> Self {
+        let mut test = MultiHopTest::new();
+        for (chain, name) in [
+            (&mut test.chain_b, "chain_b"),
+            (&mut test.chain_c, "chain_c"),
+        ] {
+            chain
+                .migrate_arkite_contract(&MigrateMsg::WithUpdate {
+                    default_token_uri: Some(format!("ipfs://{name}.default")),
+                    escrowed_token_uri: Some(format!("ipfs://{name}.escrowed")),
+                    transferred_token_uri: Some(format!("ipfs://{name}.transferred")),
+                })
+                .unwrap();
+        }
+        let owners = [
+            test.chain_a.addr_make(NFT_OWNER_WALLET),
+            test.chain_b.addr_make(NFT_OWNER_WALLET),
+            test.chain_c.addr_make(NFT_OWNER_WALLET),
+        ];
+        test.chain_a
+            .execute_passport_mint(owners[0].clone())
+            .unwrap();
+        Self {
+            test,
+            owners,
+            hops: 0,
+        }
+    }
+
+    /// Passport collection on chain A, and voucher collections on chain B and C, once created.
+    fn collections(&self) -> [Option<Addr>; 3] {
+        let cw721_a = &self.test.chain_a.addr_cw721_contract;
+        [
+            Some(cw721_a.clone()),
+            self.test
+                .chain_b
+                .query_nft_contract(self.test.class_id_on_chain_b(cw721_a)),
+            self.test
+                .chain_c
+                .query_nft_contract(self.test.class_id_on_chain_c(cw721_a)),
+        ]
+    }
+
+    /// Moves passport one hop away from home chain, or one hop back.
+    fn hop(&mut self, away: bool) {
+        let cw721 = self.collections()[self.hops].clone().unwrap();
+        let owner = self.owners[self.hops].clone();
+        let (target, channel_id) = match (self.hops, away) {
+            (0, true) => (1, self.test.chain_a.channel_id.clone()),
+            (1, true) => (2, CHANNEL_B_TO_C.to_string()),
+            (1, false) => (0, self.test.chain_b.channel_id.clone()),
+            (2, false) => (1, self.test.chain_c.channel_id.clone()),
+            _ => unreachable!(),
+        };
+        let receiver = self.owners[target].to_string();
+        let chain = match self.hops {
+            0 => &mut self.test.chain_a,
+            1 => &mut self.test.chain_b,
+            _ => &mut self.test.chain_c,
+        };
+        chain
+            .execute_transfer_nft_on_channel(owner, cw721, TOKEN_ID, receiver, &channel_id)
+            .unwrap();
+        self.test.relay_packets();
+        self.hops = target;
+    }
+
+    /// Token uris only depend on where the passport is, not on the path taken:
+    /// escrowed on every chain it has left, transferred on the chain it is at, default once back home.
+    fn assert_invariants(&self) {
+        let chains = [&self.test.chain_a, &self.test.chain_b, &self.test.chain_c];
+        for (hops, collection) in self.collections().into_iter().enumerate() {
+            let nft_info = collection
+                .and_then(|cw721| chains[hops].query_cw721_all_nft_info(cw721, TOKEN_ID).ok());
+            // vouchers beyond current location are burned
+            if hops > self.hops {
+                assert!(nft_info.is_none(), "voucher at {hops} hops is not burned");
+                continue;
+            }
+            let nft_info = nft_info.unwrap();
+            let (owner, token_uri) = match (hops == self.hops, hops) {
+                (true, 0) => (self.owners[0].to_string(), DEFAULT_TOKEN_URI),
+                (true, _) => (self.owners[hops].to_string(), TRANSFERRED_TOKEN_URI),
+                (false, _) => (
+                    chains[hops].addr_ics721_contract.to_string(),
+                    ESCROWED_TOKEN_URI,
+                ),
+            };
+            assert_eq!(nft_info.access.owner, owner, "owner at {hops} hops");
+            assert_eq!(
+                nft_info.info.token_uri,
+                Some(token_uri.to_string()),
+                "token uri at {hops} hops"
+            );
+            // uris of home chain are carried along every hop
+            assert_eq!(trait_value(&nft_info, "default_uri"), DEFAULT_TOKEN_URI);
+            assert_eq!(trait_value(&nft_info, "escrowed_uri"), ESCROWED_TOKEN_URI);
+            assert_eq!(
+                trait_value(&nft_info, "transferred_uri"),
+                TRANSFERRED_TOKEN_URI
+            );
+        }
+    }
+}
+
+proptest! {
+    // each case sets up three chains, so keep number of cases low
+    #![proptest_config(ProptestConfig::with_cases(12))]
+    #[test]
+    fn test_multi_hop_uri_state_machine(steps in prop::collection::vec(any::<bool>(), 1..10)) {
+        let mut state_machine = MultiHopStateMachine::new();
+        state_machine.assert_invariants();
+        for away in steps {
+            // at home or at last chain, passport can only move in one direction
+            let away = match state_machine.hops {
+                0 => true,
+                2 => false,
+                _ => away,
+            };
+            state_machine.hop(away);
+            state_machine.assert_invariants();
+        }
+    }
+}
+
+proptest! {
+    // each case sets up two chains, so keep number of cases low
+    #![proptest_config(ProptestConfig::with_cases(24))]
+    #[test]
+    fn test_uri_state_machine(steps in prop::collection::vec(step_strategy(), 1..8)) {
+        let mut state_machine = UriStateMachine::new();
+        state_machine.assert_invariants();
+        for step in steps.iter() {
+            state_machine.apply(step);
+            state_machine.assert_invariants();
+        }
+    }
+}
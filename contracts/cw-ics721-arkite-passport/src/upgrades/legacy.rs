@@ -0,0 +1,46 @@
+//! Storage of previous versions, as it has been before an upgrade.
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+use cw_storage_plus::Item;
+
+use crate::state::{PoapPolicy, TimeoutPolicy};
+
+// prior to v0.3.0, before items have been consolidated into `Config`
+
+pub const DEFAULT_TOKEN_URI: Item<String> = Item::new("token_uri");
+pub const ESCROWED_TOKEN_URI: Item<String> = Item::new("escrowed_token_uri");
+pub const TRANSFERRED_TOKEN_URI: Item<String> = Item::new("transferred_token_uri");
+pub const ADDR_CW721: Item<Addr> = Item::new("addr_cw721");
+pub const ADDR_ICS721: Item<Addr> = Item::new("addr_ics721");
+pub const ADDR_POAP: Item<Addr> = Item::new("addr_poap");
+pub const COUNTERPARTY_CONTRACT: Item<String> = Item::new("counterpart_contract");
+
+// prior to v0.4.0, before poap and timeout policies
+pub const CONFIG_V0_3_0: Item<ConfigV0_3_0> = Item::new("config");
+
+#[cw_serde]
+pub struct ConfigV0_3_0 {
+    pub default_token_uri: String,
+    pub escrowed_token_uri: String,
+    pub transferred_token_uri: String,
+    pub addr_cw721: Option<Addr>,
+    pub addr_poap: Option<Addr>,
+    pub addr_ics721: Option<Addr>,
+    pub counterparty_contract: Option<String>,
+}
+
+// prior to v0.5.0, before rate limit policy, royalty info and collection metadata
+pub const CONFIG_V0_4_0: Item<ConfigV0_4_0> = Item::new("config");
+
+#[cw_serde]
+pub struct ConfigV0_4_0 {
+    pub default_token_uri: String,
+    pub escrowed_token_uri: String,
+    pub transferred_token_uri: String,
+    pub addr_cw721: Option<Addr>,
+    pub addr_poap: Option<Addr>,
+    pub addr_ics721: Option<Addr>,
+    pub counterparty_contract: Option<String>,
+    pub poap_policy: PoapPolicy,
+    pub timeout_policy: TimeoutPolicy,
+}
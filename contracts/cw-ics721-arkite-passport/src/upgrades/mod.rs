@@ -0,0 +1,65 @@
+use cosmwasm_std::{Attribute, DepsMut, Env};
+use cw2::{get_contract_version, set_contract_version};
+use semver::Version;
+
+use crate::{
+    error::ContractError,
+    execute::{CONTRACT_NAME, CONTRACT_VERSION},
+};
+
+pub(crate) mod legacy;
+mod v0_2_0;
+mod v0_3_0;
+mod v0_4_0;
+mod v0_5_0;
+
+/// A state upgrade, returning attributes describing what has been migrated.
+type Upgrade = fn(DepsMut, &Env) -> Result<Vec<Attribute>, ContractError>;
+
+/// State upgrades in ascending order. An upgrade runs on migration, in case stored version is older than the upgrade's version.
+const UPGRADES: &[(&str, Upgrade)] = &[
+    ("0.2.0", v0_2_0::upgrade),
+    ("0.3.0", v0_3_0::upgrade),
+    ("0.4.0", v0_4_0::upgrade),
+    ("0.5.0", v0_5_0::upgrade),
+];
+
+/// Reads stored cw2 version, runs all pending state upgrades and stores new contract version.
+/// Refuses migration from another contract or from a newer version.
+pub fn upgrade(mut deps: DepsMut, env: &Env) -> Result<Vec<Attribute>, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::InvalidContractName {
+            expected: CONTRACT_NAME.to_string(),
+            actual: stored.contract,
+        });
+    }
+    let stored_version = parse_version(&stored.version)?;
+    let new_version = parse_version(CONTRACT_VERSION)?;
+    if stored_version > new_version {
+        return Err(ContractError::CannotDowngrade {
+            stored: stored.version,
+            new: CONTRACT_VERSION.to_string(),
+        });
+    }
+
+    let mut attributes = vec![Attribute::new("from_version", stored.version)];
+    for (version, upgrade) in UPGRADES {
+        let version = parse_version(version)?;
+        if stored_version < version && version <= new_version {
+            attributes.push(Attribute::new("upgrade", version.to_string()));
+            attributes.extend(upgrade(deps.branch(), env)?);
+        }
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(attributes)
+}
+
+fn parse_version(version: &str) -> Result<Version, ContractError> {
+    version.parse().map_err(
+        |error: semver::Error| ContractError::InvalidContractVersion {
+            version: version.to_string(),
+            error: error.to_string(),
+        },
+    )
+}
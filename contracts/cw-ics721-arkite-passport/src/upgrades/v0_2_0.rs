@@ -0,0 +1,28 @@
+use cosmwasm_std::{Addr, Attribute, DepsMut, Env};
+
+use super::legacy::{ADDR_CW721, ADDR_POAP};
+use crate::{
+    error::ContractError,
+    execute::query_num_tokens,
+    state::{Counters, COUNTERS},
+};
+
+/// Introduces counters, seeded with number of minted passports and poaps.
+/// Transfers before this version are not tracked and start from zero.
+/// Collections not set (e.g. instantiate reply never stored them) have no tokens, so their counters start from zero.
+pub fn upgrade(deps: DepsMut, _env: &Env) -> Result<Vec<Attribute>, ContractError> {
+    let num_tokens = |cw721: Option<Addr>| match cw721 {
+        Some(cw721) => query_num_tokens(deps.as_ref(), cw721),
+        None => Ok(0),
+    };
+    let counters = Counters {
+        passports_minted: num_tokens(ADDR_CW721.may_load(deps.storage)?)?,
+        poaps_minted: num_tokens(ADDR_POAP.may_load(deps.storage)?)?,
+        ..Default::default()
+    };
+    COUNTERS.save(deps.storage, &counters)?;
+    Ok(vec![
+        Attribute::new("passports_minted", counters.passports_minted.to_string()),
+        Attribute::new("poaps_minted", counters.poaps_minted.to_string()),
+    ])
+}
@@ -0,0 +1,31 @@
+use cosmwasm_std::{Attribute, DepsMut, Env};
+
+use super::legacy::{
+    ConfigV0_3_0, ADDR_CW721, ADDR_ICS721, ADDR_POAP, CONFIG_V0_3_0, COUNTERPARTY_CONTRACT,
+    DEFAULT_TOKEN_URI, ESCROWED_TOKEN_URI, TRANSFERRED_TOKEN_URI,
+};
+use crate::error::ContractError;
+
+/// Folds legacy URI, address and counterparty items into a single `Config` item.
+pub fn upgrade(deps: DepsMut, _env: &Env) -> Result<Vec<Attribute>, ContractError> {
+    let config = ConfigV0_3_0 {
+        default_token_uri: DEFAULT_TOKEN_URI.load(deps.storage)?,
+        escrowed_token_uri: ESCROWED_TOKEN_URI.load(deps.storage)?,
+        transferred_token_uri: TRANSFERRED_TOKEN_URI.load(deps.storage)?,
+        addr_cw721: ADDR_CW721.may_load(deps.storage)?,
+        addr_poap: ADDR_POAP.may_load(deps.storage)?,
+        addr_ics721: ADDR_ICS721.may_load(deps.storage)?,
+        counterparty_contract: COUNTERPARTY_CONTRACT.may_load(deps.storage)?,
+    };
+    CONFIG_V0_3_0.save(deps.storage, &config)?;
+
+    DEFAULT_TOKEN_URI.remove(deps.storage);
+    ESCROWED_TOKEN_URI.remove(deps.storage);
+    TRANSFERRED_TOKEN_URI.remove(deps.storage);
+    ADDR_CW721.remove(deps.storage);
+    ADDR_POAP.remove(deps.storage);
+    ADDR_ICS721.remove(deps.storage);
+    COUNTERPARTY_CONTRACT.remove(deps.storage);
+
+    Ok(vec![Attribute::new("config", "consolidated")])
+}
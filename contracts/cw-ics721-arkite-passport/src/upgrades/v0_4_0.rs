@@ -0,0 +1,32 @@
+use cosmwasm_std::{Attribute, DepsMut, Env};
+
+use super::legacy::{ConfigV0_4_0, CONFIG_V0_3_0, CONFIG_V0_4_0};
+use crate::{
+    error::ContractError,
+    state::{PoapPolicy, TimeoutPolicy},
+};
+
+/// Adds poap and timeout policies to config, and makes contract admin owner of this contract.
+pub fn upgrade(deps: DepsMut, env: &Env) -> Result<Vec<Attribute>, ContractError> {
+    let legacy_config = CONFIG_V0_3_0.load(deps.storage)?;
+    let config = ConfigV0_4_0 {
+        default_token_uri: legacy_config.default_token_uri,
+        escrowed_token_uri: legacy_config.escrowed_token_uri,
+        transferred_token_uri: legacy_config.transferred_token_uri,
+        addr_cw721: legacy_config.addr_cw721,
+        addr_poap: legacy_config.addr_poap,
+        addr_ics721: legacy_config.addr_ics721,
+        counterparty_contract: legacy_config.counterparty_contract,
+        // keep previous behaviour: poap on each receive and no timeout bounds
+        poap_policy: PoapPolicy::Always,
+        timeout_policy: TimeoutPolicy::default(),
+    };
+    CONFIG_V0_4_0.save(deps.storage, &config)?;
+
+    let admin = deps
+        .querier
+        .query_wasm_contract_info(env.contract.address.clone())?
+        .admin;
+    let ownership = cw_ownable::initialize_owner(deps.storage, deps.api, admin.as_deref())?;
+    Ok(ownership.into_attributes())
+}
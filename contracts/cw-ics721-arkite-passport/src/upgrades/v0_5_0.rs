@@ -0,0 +1,38 @@
+use cosmwasm_std::{Attribute, DepsMut, Env};
+
+use super::legacy::CONFIG_V0_4_0;
+use crate::{
+    error::ContractError,
+    state::{Config, FeeConfig, PauseState, RateLimitPolicy, CONFIG, FEE_CONFIG, PAUSE},
+};
+
+/// Adds rate limit policy, royalty info and collection metadata to config, and stores default pause state and fee config.
+/// All other state introduced in this version are maps, which start empty.
+pub fn upgrade(deps: DepsMut, _env: &Env) -> Result<Vec<Attribute>, ContractError> {
+    let legacy_config = CONFIG_V0_4_0.load(deps.storage)?;
+    let config = Config {
+        default_token_uri: legacy_config.default_token_uri,
+        escrowed_token_uri: legacy_config.escrowed_token_uri,
+        transferred_token_uri: legacy_config.transferred_token_uri,
+        addr_cw721: legacy_config.addr_cw721,
+        addr_poap: legacy_config.addr_poap,
+        addr_ics721: legacy_config.addr_ics721,
+        counterparty_contract: legacy_config.counterparty_contract,
+        poap_policy: legacy_config.poap_policy,
+        timeout_policy: legacy_config.timeout_policy,
+        // keep previous behaviour: no rate limit, no royalty and collection info of vouchers untouched
+        rate_limit_policy: RateLimitPolicy::default(),
+        royalty_info: None,
+        collection_metadata: None,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    // nothing paused and no fees
+    PAUSE.save(deps.storage, &PauseState::default())?;
+    FEE_CONFIG.save(deps.storage, &FeeConfig::default())?;
+
+    Ok(vec![
+        Attribute::new("rate_limit_policy", "unlimited"),
+        Attribute::new("pause", "none"),
+        Attribute::new("fee_config", "none"),
+    ])
+}
@@ -0,0 +1,461 @@
+//! Gas benchmarks for the compiled contract, run under the cosmwasm-vm gas meter.
+//!
+//! Gas is measured on the optimized wasm, as deployed on chain. Build it first and run the benchmarks with:
+//!
+//! ```sh
+//! ../../build.sh && cargo gas-bench
+//! ```
+//!
+//! Benchmarks also run as part of `cargo test`, but are skipped with a notice on stderr in case wasm isn't built.
+//! A missing wasm fails instead, if `CI` or `ARKITE_PASSPORT_WASM` is set.
+//! Budgets in `gas_budgets.json` are provisional round upper bounds, which have not been measured yet. Baseline them
+//! with `GAS_BENCH_UPDATE=1 cargo gas-bench`, adding headroom to gas used, and commit the updated file.
+//! Unoptimized wasm of `cargo wasm` uses more gas, so budgets must never be baselined with it.
+//!
+//! Each path is checked against its budget in `tests/gas_budgets.json`. Gas is reported in VM gas
+//! and as SDK gas, using the default multiplier of wasmd. Cross-contract queries are answered by
+//! a mock querier, so only gas spent inside this contract is measured.
+use std::{collections::BTreeMap, io::Write};
+
+use cosmwasm_std::{
+    from_json, testing::MOCK_CONTRACT_ADDR, to_json_binary, Addr, Binary, ContractResult, Decimal,
+    Empty, Env, IbcTimeout, MessageInfo, Reply, Response, SubMsgResponse, SubMsgResult,
+    SystemError, SystemResult, WasmQuery,
+};
+use cosmwasm_vm::{
+    testing::{
+        execute, instantiate, mock_env, mock_info, mock_instance_with_gas_limit, reply, MockApi,
+        MockQuerier, MockStorage,
+    },
+    Instance,
+};
+use cw721_base::{
+    msg::{NftInfoResponse, NumTokensResponse, OwnerOfResponse},
+    receiver::Cw721ReceiveMsg,
+    state::{NftExtension, Trait},
+    DefaultOptionalCollectionExtension, DefaultOptionalNftExtension, Ownership,
+};
+use cw_cii::{Admin, ContractInstantiateInfo};
+use cw_ics721_arkite_passport::{
+    msg::{CallbackData, ExecuteMsg, InstantiateMsg},
+    state::{CollectionMetadata, MetadataPolicy, RoyaltyInfo},
+    INSTANTIATE_CW721_REPLY_ID, INSTANTIATE_ICS721_REPLY_ID, INSTANTIATE_POAP_REPLY_ID,
+};
+use ics721::{ClassId, NonFungibleTokenPacketData, TokenId};
+use ics721_types::{
+    ibc_types::IbcOutgoingMsg,
+    types::{Ics721AckCallbackMsg, Ics721ReceiveCallbackMsg, Ics721Status},
+};
+
+/// optimized wasm built by `build.sh`, can be overridden by env var
+const WASM_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../artifacts/cw_ics721_arkite_passport.wasm"
+);
+const WASM_PATH_ENV: &str = "ARKITE_PASSPORT_WASM";
+const BUDGETS: &str = include_str!("gas_budgets.json");
+const BUDGETS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/gas_budgets.json");
+/// set by CI providers, wasm must be built there
+const CI_ENV: &str = "CI";
+/// re-baselines budgets instead of checking them
+const UPDATE_ENV: &str = "GAS_BENCH_UPDATE";
+/// headroom added to gas used on re-baseline, in percent
+const BUDGET_HEADROOM_PERCENT: u64 = 20;
+/// gas limit of a single instance, high enough to never be hit by a single call
+const GAS_LIMIT: u64 = 10_000_000_000_000;
+/// default gas multiplier in wasmd: 1 SDK gas = 140_000_000 VM gas
+const SDK_GAS_MULTIPLIER: u64 = 140_000_000;
+
+const CREATOR: &str = "creator";
+const NFT_OWNER: &str = "nft_owner";
+const RECEIVER: &str = "receiver";
+const CW721: &str = "cw721";
+/// voucher collection created by ics721, with passport as creator
+const VOUCHER: &str = "voucher";
+const POAP: &str = "poap";
+const ICS721: &str = "ics721";
+const COUNTERPARTY: &str = "counterparty";
+const DEFAULT_TOKEN_URI: &str = "ipfs://interchain.passport";
+const ESCROWED_TOKEN_URI: &str = "ipfs://interchain.escrowed";
+const TRANSFERRED_TOKEN_URI: &str = "ipfs://interchain.transferred";
+
+type MockInstance = Instance<MockApi, MockStorage, MockQuerier>;
+
+/// Wasm of env var, or built by `build.sh`. `None` if there is no env var and wasm isn't built.
+fn load_wasm() -> Option<Vec<u8>> {
+    let Ok(path) = std::env::var(WASM_PATH_ENV) else {
+        return std::fs::read(WASM_PATH).ok();
+    };
+    let wasm = std::fs::read(&path).unwrap_or_else(|err| panic!("failed reading {path}: {err}"));
+    Some(wasm)
+}
+
+/// Protobuf encoded `MsgInstantiateContractResponse`, holding contract address only.
+fn instantiate_reply(id: u64, contract_address: &str) -> Reply {
+    let mut data = vec![0x0a, contract_address.len() as u8];
+    data.extend_from_slice(contract_address.as_bytes());
+    Reply {
+        id,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(Binary::from(data)),
+        }),
+    }
+}
+
+/// Passport as returned by cw721, with uris set on mint.
+fn nft_info(token_uri: &str) -> NftInfoResponse<DefaultOptionalNftExtension> {
+    let trait_of = |trait_type: &str, value: &str| Trait {
+        display_type: None,
+        trait_type: trait_type.to_string(),
+        value: value.to_string(),
+    };
+    NftInfoResponse {
+        token_uri: Some(token_uri.to_string()),
+        extension: Some(NftExtension {
+            image: Some(token_uri.to_string()),
+            attributes: Some(vec![
+                trait_of("token_uri", token_uri),
+                trait_of("default_uri", DEFAULT_TOKEN_URI),
+                trait_of("escrowed_uri", ESCROWED_TOKEN_URI),
+                trait_of("transferred_uri", TRANSFERRED_TOKEN_URI),
+            ]),
+            ..Default::default()
+        }),
+    }
+}
+
+/// Answers queries to cw721, poap and ics721, as made by the passport contract.
+fn mock_wasm_queries(instance: &mut MockInstance) {
+    instance
+        .with_querier(|querier| {
+            querier.update_wasm(|query| {
+                let res = match query {
+                    WasmQuery::Smart { contract_addr, msg } if contract_addr == ICS721 => {
+                        match from_json::<ics721::msg::QueryMsg>(msg) {
+                            // no proxies
+                            Ok(ics721::msg::QueryMsg::OutgoingProxy {})
+                            | Ok(ics721::msg::QueryMsg::IncomingProxy {}) => {
+                                to_json_binary(&None::<String>)
+                            }
+                            // voucher is received on channel-0, from collection of counterparty
+                            Ok(ics721::msg::QueryMsg::ClassId { contract }) => {
+                                to_json_binary(&(contract == VOUCHER).then(|| {
+                                    ClassId::new(format!("wasm.{ICS721}/channel-0/{CW721}"))
+                                }))
+                            }
+                            _ => return unsupported(),
+                        }
+                    }
+                    WasmQuery::Smart { msg, .. } => {
+                        match from_json::<
+                            cw721_base::msg::QueryMsg<
+                                DefaultOptionalNftExtension,
+                                DefaultOptionalCollectionExtension,
+                                Empty,
+                            >,
+                        >(msg)
+                        {
+                            Ok(cw721_base::msg::QueryMsg::NftInfo { .. }) => {
+                                to_json_binary(&nft_info(DEFAULT_TOKEN_URI))
+                            }
+                            // ack success skips update of burned vouchers
+                            Ok(cw721_base::msg::QueryMsg::OwnerOf { .. }) => {
+                                to_json_binary(&OwnerOfResponse {
+                                    owner: ICS721.to_string(),
+                                    approvals: vec![],
+                                })
+                            }
+                            Ok(cw721_base::msg::QueryMsg::NumTokens {}) => {
+                                to_json_binary(&NumTokensResponse { count: 1 })
+                            }
+                            // passport is creator of voucher collections
+                            Ok(cw721_base::msg::QueryMsg::GetCreatorOwnership {}) => {
+                                to_json_binary(&Ownership::<Addr> {
+                                    owner: Some(Addr::unchecked(MOCK_CONTRACT_ADDR)),
+                                    pending_owner: None,
+                                    pending_expiry: None,
+                                })
+                            }
+                            _ => return unsupported(),
+                        }
+                    }
+                    _ => return unsupported(),
+                };
+                SystemResult::Ok(ContractResult::Ok(res.unwrap()))
+            });
+            Ok(())
+        })
+        .unwrap();
+}
+
+fn unsupported() -> SystemResult<ContractResult<Binary>> {
+    SystemResult::Err(SystemError::UnsupportedRequest {
+        kind: "query".to_string(),
+    })
+}
+
+fn cw721_instantiate_info(name: &str) -> ContractInstantiateInfo {
+    ContractInstantiateInfo {
+        admin: Some(Admin::Instantiator {}),
+        msg: to_json_binary(&cw721_base::msg::InstantiateMsg::<
+            cw721_base::DefaultOptionalCollectionExtensionMsg,
+        > {
+            name: name.to_string(),
+            symbol: name.to_string(),
+            collection_info_extension: None,
+            minter: None,
+            creator: None,
+            withdraw_address: None,
+        })
+        .unwrap(),
+        code_id: 1,
+        label: name.to_string(),
+    }
+}
+
+fn instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+        escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+        transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+        cw721_base: cw721_instantiate_info("passport"),
+        cw721_poap: cw721_instantiate_info("poap"),
+        ics721_base: ContractInstantiateInfo {
+            admin: Some(Admin::Instantiator {}),
+            msg: to_json_binary(&ics721::msg::InstantiateMsg {
+                cw721_base_code_id: 1,
+                incoming_proxy: None,
+                outgoing_proxy: None,
+                pauser: None,
+                cw721_admin: None,
+                cw721_creator: None,
+                contract_addr_length: None,
+            })
+            .unwrap(),
+            code_id: 2,
+            label: "ics721".to_string(),
+        },
+    }
+}
+
+fn callback_data() -> CallbackData {
+    CallbackData {
+        token_id: "1".to_string(),
+        sender: NFT_OWNER.to_string(),
+        default_token_uri: DEFAULT_TOKEN_URI.to_string(),
+        escrowed_token_uri: ESCROWED_TOKEN_URI.to_string(),
+        transferred_token_uri: TRANSFERRED_TOKEN_URI.to_string(),
+        royalty_info: None,
+        collection_metadata: None,
+        metadata_policy: MetadataPolicy::Passport,
+    }
+}
+
+/// Callback data of a forward transfer, with royalty and collection metadata of home chain.
+fn voucher_callback_data() -> CallbackData {
+    CallbackData {
+        royalty_info: Some(RoyaltyInfo {
+            payment_address: CREATOR.to_string(),
+            share: Decimal::percent(5),
+        }),
+        collection_metadata: Some(CollectionMetadata {
+            description: Some("Arkite InterChain Passport collection".to_string()),
+            image: Some(DEFAULT_TOKEN_URI.to_string()),
+            external_link: None,
+        }),
+        ..callback_data()
+    }
+}
+
+fn packet() -> NonFungibleTokenPacketData {
+    NonFungibleTokenPacketData {
+        class_id: ClassId::new(CW721),
+        class_uri: None,
+        class_data: None,
+        token_ids: vec![TokenId::new("1")],
+        token_uris: Some(vec![DEFAULT_TOKEN_URI.to_string()]),
+        token_data: None,
+        // receive callback data is only trusted from counterparty
+        sender: COUNTERPARTY.to_string(),
+        receiver: RECEIVER.to_string(),
+        memo: None,
+    }
+}
+
+struct Benchmark {
+    instance: MockInstance,
+    env: Env,
+}
+
+impl Benchmark {
+    /// Instantiated contract, with all linked contracts set by replies and counterparty set.
+    fn new(wasm: &[u8]) -> Self {
+        let mut instance = mock_instance_with_gas_limit(wasm, GAS_LIMIT);
+        mock_wasm_queries(&mut instance);
+        let env = mock_env();
+        let mut benchmark = Self { instance, env };
+        benchmark.instantiate().unwrap();
+        for (id, addr) in [
+            (INSTANTIATE_CW721_REPLY_ID, CW721),
+            (INSTANTIATE_POAP_REPLY_ID, POAP),
+            (INSTANTIATE_ICS721_REPLY_ID, ICS721),
+        ] {
+            let res: ContractResult<Response> = reply(
+                &mut benchmark.instance,
+                benchmark.env.clone(),
+                instantiate_reply(id, addr),
+            );
+            res.unwrap();
+        }
+        benchmark
+            .execute(
+                mock_info(CREATOR, &[]),
+                ExecuteMsg::CounterPartyContract {
+                    addr: COUNTERPARTY.to_string(),
+                },
+            )
+            .unwrap();
+        benchmark
+    }
+
+    fn instantiate(&mut self) -> Result<u64, String> {
+        let gas_before = self.instance.get_gas_left();
+        let res: ContractResult<Response> = instantiate(
+            &mut self.instance,
+            self.env.clone(),
+            mock_info(CREATOR, &[]),
+            instantiate_msg(),
+        );
+        res.into_result()?;
+        Ok(gas_before - self.instance.get_gas_left())
+    }
+
+    fn execute(&mut self, info: MessageInfo, msg: ExecuteMsg) -> Result<u64, String> {
+        let gas_before = self.instance.get_gas_left();
+        let res: ContractResult<Response> =
+            execute(&mut self.instance, self.env.clone(), info, msg);
+        res.into_result()?;
+        Ok(gas_before - self.instance.get_gas_left())
+    }
+}
+
+/// Runs a single path on a fresh instance and returns its gas used.
+fn run_path(wasm: &[u8], path: &str) -> Result<u64, String> {
+    if path == "instantiate" {
+        let mut instance = mock_instance_with_gas_limit(wasm, GAS_LIMIT);
+        mock_wasm_queries(&mut instance);
+        return Benchmark {
+            instance,
+            env: mock_env(),
+        }
+        .instantiate();
+    }
+    let mut benchmark = Benchmark::new(wasm);
+    match path {
+        "mint" => benchmark.execute(mock_info(NFT_OWNER, &[]), ExecuteMsg::Mint {}),
+        "receive_nft" => {
+            let ibc_outgoing_msg = IbcOutgoingMsg {
+                receiver: RECEIVER.to_string(),
+                channel_id: "channel-0".to_string(),
+                timeout: IbcTimeout::with_timestamp(benchmark.env.block.time.plus_seconds(600)),
+                memo: None,
+            };
+            benchmark.execute(
+                mock_info(CW721, &[]),
+                ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                    sender: NFT_OWNER.to_string(),
+                    token_id: "1".to_string(),
+                    msg: to_json_binary(&ibc_outgoing_msg).unwrap(),
+                }),
+            )
+        }
+        "receive_callback" => benchmark.execute(
+            mock_info(ICS721, &[]),
+            ExecuteMsg::Ics721ReceiveCallback(Ics721ReceiveCallbackMsg {
+                msg: to_json_binary(&callback_data()).unwrap(),
+                nft_contract: CW721.to_string(),
+                original_packet: packet(),
+            }),
+        ),
+        // forward transfer: creator check, voucher policy and metadata saved, and collection info synced
+        "receive_callback_voucher" => benchmark.execute(
+            mock_info(ICS721, &[]),
+            ExecuteMsg::Ics721ReceiveCallback(Ics721ReceiveCallbackMsg {
+                msg: to_json_binary(&voucher_callback_data()).unwrap(),
+                nft_contract: VOUCHER.to_string(),
+                original_packet: packet(),
+            }),
+        ),
+        "ack_callback_success" | "ack_callback_fail" => {
+            let status = match path {
+                "ack_callback_success" => Ics721Status::Success,
+                _ => Ics721Status::Failed("timeout".to_string()),
+            };
+            // acks are only handled for packets sent by passport
+            let original_packet = NonFungibleTokenPacketData {
+                sender: benchmark.env.contract.address.to_string(),
+                ..packet()
+            };
+            benchmark.execute(
+                mock_info(ICS721, &[]),
+                ExecuteMsg::Ics721AckCallback(Ics721AckCallbackMsg {
+                    status,
+                    msg: to_json_binary(&callback_data()).unwrap(),
+                    nft_contract: CW721.to_string(),
+                    original_packet,
+                }),
+            )
+        }
+        _ => Err(format!("unknown path {path}")),
+    }
+}
+
+#[test]
+fn gas_benchmarks() {
+    let Some(wasm) = load_wasm() else {
+        if std::env::var(CI_ENV).is_ok() {
+            panic!("no wasm at {WASM_PATH}, build it with `build.sh` before running in CI");
+        }
+        // written to stderr directly, since test output is captured
+        writeln!(
+            std::io::stderr(),
+            "skipping gas benchmarks, no wasm at {WASM_PATH}. Build it with `build.sh`"
+        )
+        .unwrap();
+        return;
+    };
+    let mut budgets: BTreeMap<String, u64> = from_json(BUDGETS).unwrap();
+    let update = std::env::var(UPDATE_ENV).is_ok();
+    let mut over_budget = vec![];
+    println!(
+        "{:<24} {:>18} {:>10} {:>18}",
+        "path", "vm gas", "sdk gas", "budget"
+    );
+    for (path, budget) in budgets.iter_mut() {
+        let gas_used = run_path(&wasm, path).unwrap_or_else(|err| panic!("{path} failed: {err}"));
+        println!(
+            "{:<24} {:>18} {:>10} {:>18}",
+            path,
+            gas_used,
+            gas_used / SDK_GAS_MULTIPLIER,
+            budget
+        );
+        if update {
+            *budget = gas_used + gas_used * BUDGET_HEADROOM_PERCENT / 100;
+        } else if gas_used > *budget {
+            over_budget.push(format!("{path}: {gas_used} > {budget}"));
+        }
+    }
+    if update {
+        let json = serde_json::to_string_pretty(&budgets).unwrap();
+        std::fs::write(BUDGETS_PATH, json + "\n").unwrap();
+        println!("budgets written to {BUDGETS_PATH}");
+        return;
+    }
+    assert!(
+        over_budget.is_empty(),
+        "paths over gas budget:\n{}",
+        over_budget.join("\n")
+    );
+}
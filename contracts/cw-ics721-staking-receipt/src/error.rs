@@ -0,0 +1,39 @@
+use cosmwasm_std::StdError;
+use cw_ics721_callback::UnauthorizedCallback;
+use cw_ownable::OwnershipError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error(transparent)]
+    Std(#[from] StdError),
+
+    #[error(transparent)]
+    Ownership(#[from] OwnershipError),
+
+    #[error("Unauthorized callback. Only ICS721 can call back.")]
+    UnauthorizedCallback {},
+
+    #[error("Ack of packet sent by {sender}, only packets sent by this contract are acked")]
+    UnauthorizedAckPacket { sender: String },
+
+    #[error("Packet sender {sender} is not the counterparty contract")]
+    UnauthorizedPacketSender { sender: String },
+
+    #[error("Counterparty contract not set, it is required for staking NFTs on the other chain")]
+    MissingCounterpartyContract {},
+
+    #[error("NFT {token_id} in {cw721} is not staked by sender")]
+    NotStaker { cw721: String, token_id: String },
+}
+
+impl From<UnauthorizedCallback> for ContractError {
+    fn from(err: UnauthorizedCallback) -> Self {
+        match err {
+            UnauthorizedCallback::Sender => ContractError::UnauthorizedCallback {},
+            UnauthorizedCallback::PacketSender { sender } => {
+                ContractError::UnauthorizedAckPacket { sender }
+            }
+        }
+    }
+}
@@ -0,0 +1,422 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdResult, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw721_base::{
+    receiver::Cw721ReceiveMsg, DefaultOptionalCollectionExtensionMsg,
+    DefaultOptionalNftExtensionMsg,
+};
+use cw_ics721_callback::{
+    handle_ack_callback, handle_receive_callback, outgoing_proxy_or_ics721, set_memo,
+    Ics721CallbackHandler,
+};
+use ics721::NonFungibleTokenPacketData;
+use ics721_types::{
+    ibc_types::IbcOutgoingMsg,
+    types::{Ics721AckCallbackMsg, Ics721ReceiveCallbackMsg},
+};
+
+use crate::{
+    error::ContractError,
+    msg::{CallbackData, ExecuteMsg, InstantiateMsg, QueryMsg, StakeMsg, StakingExecuteMsg},
+    state::{Config, CONFIG, STAKERS},
+};
+
+pub(crate) const CONTRACT_NAME: &str = "crates.io:ics721-staking-receipt";
+pub(crate) const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(info.sender.as_str()))?;
+    let config = Config {
+        ics721: deps.api.addr_validate(&msg.ics721)?,
+        staking: deps.api.addr_validate(&msg.staking)?,
+        counterparty_contract: None,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    Ok(Response::default()
+        .add_attribute("method", "instantiate")
+        .add_attribute("ics721", config.ics721)
+        .add_attribute("staking", config.staking))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::ReceiveNft(msg) => execute_receive_nft(deps, env, info, msg),
+        ExecuteMsg::Unstake {
+            cw721,
+            token_id,
+            msg,
+        } => execute_unstake(deps, env, info, cw721, token_id, msg),
+        ExecuteMsg::CounterPartyContract { addr } => {
+            execute_counterparty_contract(deps, info, addr)
+        }
+        ExecuteMsg::UpdateOwnership(action) => execute_update_ownership(deps, env, info, action),
+        ExecuteMsg::Ics721AckCallback(msg) => {
+            handle_ack_callback(&StakingReceiptCallbacks, deps, env, info, msg)
+        }
+        ExecuteMsg::Ics721ReceiveCallback(msg) => {
+            handle_receive_callback(&StakingReceiptCallbacks, deps, env, info, msg)
+        }
+    }
+}
+
+fn execute_update_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: cw_ownable::Action,
+) -> Result<Response, ContractError> {
+    let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
+    Ok(Response::default()
+        .add_attribute("method", "execute_update_ownership")
+        .add_attributes(ownership.into_attributes()))
+}
+
+fn execute_counterparty_contract(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    CONFIG.update(deps.storage, |mut config| -> StdResult<_> {
+        config.counterparty_contract = Some(addr.clone());
+        Ok(config)
+    })?;
+    Ok(Response::default()
+        .add_attribute("method", "execute_counterparty_contract")
+        .add_attribute("counterparty_contract", addr))
+}
+
+/// Transfers NFT to counterparty contract, which stakes it on behalf of the receiver on target chain.
+fn execute_receive_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let ibc_msg: IbcOutgoingMsg = from_json(&msg.msg)?;
+    let callback_data = CallbackData::Stake {
+        sender: msg.sender.clone(),
+        staker: ibc_msg.receiver.clone(),
+        token_id: msg.token_id.clone(),
+    };
+    let send_msg = create_send_msg(
+        deps.as_ref(),
+        env,
+        info.sender.clone(),
+        msg.token_id.clone(),
+        ibc_msg.clone(),
+        callback_data,
+    )?;
+    Ok(Response::default()
+        .add_message(send_msg)
+        .add_attribute("method", "execute_receive_nft")
+        .add_attribute("cw721", info.sender)
+        .add_attribute("token_id", msg.token_id)
+        .add_attribute("sender", msg.sender)
+        .add_attribute("staker", ibc_msg.receiver)
+        .add_attribute("channel_id", ibc_msg.channel_id))
+}
+
+/// Unstakes voucher, paying out rewards to staker, and transfers NFT back to receiver on home chain.
+fn execute_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw721: String,
+    token_id: String,
+    ibc_msg: IbcOutgoingMsg,
+) -> Result<Response, ContractError> {
+    let cw721 = deps.api.addr_validate(&cw721)?;
+    let staker = STAKERS.may_load(deps.storage, (cw721.as_str(), &token_id))?;
+    if staker.as_ref() != Some(&info.sender) {
+        return Err(ContractError::NotStaker {
+            cw721: cw721.to_string(),
+            token_id,
+        });
+    }
+    STAKERS.remove(deps.storage, (cw721.as_str(), &token_id));
+    let config = CONFIG.load(deps.storage)?;
+    // staking contract returns NFT to this contract, before it is sent to ics721
+    let unstake_msg = WasmMsg::Execute {
+        contract_addr: config.staking.to_string(),
+        msg: to_json_binary(&StakingExecuteMsg::Unstake {
+            cw721: cw721.to_string(),
+            token_id: token_id.clone(),
+            rewards_recipient: info.sender.to_string(),
+        })?,
+        funds: vec![],
+    };
+    let callback_data = CallbackData::Unstake {
+        sender: info.sender.to_string(),
+        receiver: ibc_msg.receiver.clone(),
+        token_id: token_id.clone(),
+    };
+    let send_msg = create_send_msg(
+        deps.as_ref(),
+        env,
+        cw721.clone(),
+        token_id.clone(),
+        ibc_msg.clone(),
+        callback_data,
+    )?;
+    Ok(Response::default()
+        .add_message(unstake_msg)
+        .add_message(send_msg)
+        .add_attribute("method", "execute_unstake")
+        .add_attribute("cw721", cw721)
+        .add_attribute("token_id", token_id)
+        .add_attribute("staker", info.sender)
+        .add_attribute("receiver", ibc_msg.receiver)
+        .add_attribute("channel_id", ibc_msg.channel_id))
+}
+
+/// Sends NFT to ics721 or its outgoing proxy. Counterparty contract is set as NFT receiver, since it handles the NFT in
+/// its receive callback.
+fn create_send_msg(
+    deps: Deps,
+    env: Env,
+    cw721: Addr,
+    token_id: String,
+    mut ibc_msg: IbcOutgoingMsg,
+    callback_data: CallbackData,
+) -> Result<WasmMsg, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let counterparty_contract = config
+        .counterparty_contract
+        .ok_or(ContractError::MissingCounterpartyContract {})?;
+    let outgoing_proxy_or_ics721 = outgoing_proxy_or_ics721(&deps.querier, config.ics721)?;
+    let memo = callback_data.to_memo(
+        env.contract.address.to_string(),
+        counterparty_contract.clone(),
+    )?;
+    ibc_msg.receiver = counterparty_contract;
+    set_memo(&mut ibc_msg, &memo)?;
+    Ok(WasmMsg::Execute {
+        contract_addr: cw721.to_string(),
+        msg: to_json_binary(&cw721_base::msg::ExecuteMsg::<
+            DefaultOptionalNftExtensionMsg,
+            DefaultOptionalCollectionExtensionMsg,
+            Empty,
+        >::SendNft {
+            contract: outgoing_proxy_or_ics721.to_string(),
+            token_id,
+            msg: to_json_binary(&ibc_msg)?,
+        })?,
+        funds: vec![],
+    })
+}
+
+/// Stakes NFT owned by this contract on behalf of staker.
+fn create_stake_msg(
+    staking: &Addr,
+    cw721: &str,
+    token_id: String,
+    staker: &Addr,
+) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: cw721.to_string(),
+        msg: to_json_binary(&cw721_base::msg::ExecuteMsg::<
+            DefaultOptionalNftExtensionMsg,
+            DefaultOptionalCollectionExtensionMsg,
+            Empty,
+        >::SendNft {
+            contract: staking.to_string(),
+            token_id,
+            msg: to_json_binary(&StakeMsg {
+                staker: staker.to_string(),
+            })?,
+        })?,
+        funds: vec![],
+    })
+}
+
+fn create_transfer_msg(cw721: &str, token_id: String, recipient: String) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: cw721.to_string(),
+        msg: to_json_binary(&cw721_base::msg::ExecuteMsg::<
+            DefaultOptionalNftExtensionMsg,
+            DefaultOptionalCollectionExtensionMsg,
+            Empty,
+        >::TransferNft {
+            recipient,
+            token_id,
+        })?,
+        funds: vec![],
+    })
+}
+
+/// Tokens of a packet. Callback data is shared by all tokens, so its token id is not used by callbacks.
+fn packet_token_ids(packet: &NonFungibleTokenPacketData) -> Vec<String> {
+    packet
+        .token_ids
+        .iter()
+        .map(|token_id| token_id.to_string())
+        .collect()
+}
+
+/// Staking receipt implementation of ics721 callbacks. Sender checks and decoding of callback data is done by
+/// `cw-ics721-callback`, counterparty check on receive is done here.
+pub struct StakingReceiptCallbacks;
+
+impl Ics721CallbackHandler for StakingReceiptCallbacks {
+    type CallbackData = CallbackData;
+    type Error = ContractError;
+
+    fn ics721(&self, deps: Deps) -> StdResult<Addr> {
+        Ok(CONFIG.load(deps.storage)?.ics721)
+    }
+
+    /// Stakes received vouchers on target chain, or transfers NFTs to their receiver on back transfer to home chain.
+    fn on_receive(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        msg: Ics721ReceiveCallbackMsg,
+        callback_data: CallbackData,
+    ) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        // memo is set by packet sender, so callback data is only trusted when sent by counterparty contract
+        if config.counterparty_contract.as_deref() != Some(msg.original_packet.sender.as_str()) {
+            return Err(ContractError::UnauthorizedPacketSender {
+                sender: msg.original_packet.sender,
+            });
+        }
+        let token_ids = packet_token_ids(&msg.original_packet);
+        let res = Response::default()
+            .add_attribute("method", "execute_receive_callback")
+            .add_attribute("cw721", msg.nft_contract.clone());
+        match callback_data {
+            CallbackData::Stake { staker, .. } => {
+                // invalid staker reverts transfer, and NFT is returned on source chain
+                let staker = deps.api.addr_validate(&staker)?;
+                let mut stake_msgs = vec![];
+                for token_id in &token_ids {
+                    STAKERS.save(deps.storage, (&msg.nft_contract, token_id), &staker)?;
+                    stake_msgs.push(create_stake_msg(
+                        &config.staking,
+                        &msg.nft_contract,
+                        token_id.clone(),
+                        &staker,
+                    )?);
+                }
+                Ok(res
+                    .add_messages(stake_msgs)
+                    .add_attribute("token_ids", token_ids.join(","))
+                    .add_attribute("staker", staker))
+            }
+            CallbackData::Unstake { receiver, .. } => {
+                let receiver = deps.api.addr_validate(&receiver)?;
+                let transfer_msgs = token_ids
+                    .iter()
+                    .map(|token_id| {
+                        create_transfer_msg(
+                            &msg.nft_contract,
+                            token_id.clone(),
+                            receiver.to_string(),
+                        )
+                    })
+                    .collect::<StdResult<Vec<_>>>()?;
+                Ok(res
+                    .add_messages(transfer_msgs)
+                    .add_attribute("token_ids", token_ids.join(","))
+                    .add_attribute("receiver", receiver))
+            }
+        }
+    }
+
+    fn on_ack_success(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        msg: Ics721AckCallbackMsg,
+        callback_data: CallbackData,
+    ) -> Result<Response, ContractError> {
+        let sender = match callback_data {
+            CallbackData::Stake { sender, .. } | CallbackData::Unstake { sender, .. } => sender,
+        };
+        Ok(Response::default()
+            .add_attribute("method", "execute_ack_callback")
+            .add_attribute("ics721_status", "ack_success")
+            .add_attribute("cw721", msg.nft_contract)
+            .add_attribute(
+                "token_ids",
+                packet_token_ids(&msg.original_packet).join(","),
+            )
+            .add_attribute("sender", sender))
+    }
+
+    /// Also called on timeout. NFTs are returned to this contract, they are transferred back to their owner on stake,
+    /// and staked again on unstake.
+    fn on_ack_failure(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        msg: Ics721AckCallbackMsg,
+        callback_data: CallbackData,
+        error: String,
+    ) -> Result<Response, ContractError> {
+        let token_ids = packet_token_ids(&msg.original_packet);
+        let res = Response::default()
+            .add_attribute("method", "execute_ack_callback")
+            .add_attribute("ics721_status", "ack_fail")
+            .add_attribute("ack_error", error)
+            .add_attribute("cw721", msg.nft_contract.clone())
+            .add_attribute("token_ids", token_ids.join(","));
+        match callback_data {
+            CallbackData::Stake { sender, .. } => {
+                let transfer_msgs = token_ids
+                    .iter()
+                    .map(|token_id| {
+                        create_transfer_msg(&msg.nft_contract, token_id.clone(), sender.clone())
+                    })
+                    .collect::<StdResult<Vec<_>>>()?;
+                Ok(res
+                    .add_messages(transfer_msgs)
+                    .add_attribute("owner", sender))
+            }
+            CallbackData::Unstake { sender, .. } => {
+                let staker = deps.api.addr_validate(&sender)?;
+                let config = CONFIG.load(deps.storage)?;
+                let mut stake_msgs = vec![];
+                for token_id in &token_ids {
+                    STAKERS.save(deps.storage, (&msg.nft_contract, token_id), &staker)?;
+                    stake_msgs.push(create_stake_msg(
+                        &config.staking,
+                        &msg.nft_contract,
+                        token_id.clone(),
+                        &staker,
+                    )?);
+                }
+                Ok(res.add_messages(stake_msgs).add_attribute("staker", staker))
+            }
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Staker { cw721, token_id } => {
+            to_json_binary(&STAKERS.may_load(deps.storage, (&cw721, &token_id))?)
+        }
+        QueryMsg::Ownership {} => to_json_binary(&cw_ownable::get_ownership(deps.storage)?),
+    }
+}
@@ -0,0 +1,7 @@
+pub mod error;
+pub mod execute;
+pub mod msg;
+pub mod state;
+
+#[cfg(test)]
+pub mod testing;
@@ -0,0 +1,95 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, StdResult};
+use cw721_base::receiver::Cw721ReceiveMsg;
+use cw_ics721_callback::callback_memo;
+use cw_ownable::{cw_ownable_execute, cw_ownable_query};
+use ics721_types::{
+    ibc_types::IbcOutgoingMsg,
+    types::{Ics721AckCallbackMsg, Ics721Memo, Ics721ReceiveCallbackMsg},
+};
+
+use crate::state::Config;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub ics721: String,
+    /// local staking contract, see `StakingExecuteMsg` for its interface
+    pub staking: String,
+}
+
+#[cw_ownable_execute]
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Outgoing transfer via `send_nft`, with `IbcOutgoingMsg` as msg. NFT is sent to counterparty contract, and staked
+    /// on behalf of `receiver` on target chain.
+    ReceiveNft(Cw721ReceiveMsg),
+    /// Staker only, unstakes voucher and pays out rewards to sender. NFT is transferred back to `receiver` of `msg` on
+    /// home chain.
+    Unstake {
+        cw721: String,
+        token_id: String,
+        msg: IbcOutgoingMsg,
+    },
+    /// Owner only
+    CounterPartyContract { addr: String },
+    /// Ack callback on source chain
+    Ics721AckCallback(Ics721AckCallbackMsg),
+    /// Receive callback on target chain, NOTE: if this fails, the transfer will fail and NFT is reverted back to the sender
+    Ics721ReceiveCallback(Ics721ReceiveCallbackMsg),
+}
+
+#[cw_ownable_query]
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(Config)]
+    Config {},
+    /// Staker of a voucher, None if not staked
+    #[returns(Option<Addr>)]
+    Staker { cw721: String, token_id: String },
+}
+
+/// Sent along outgoing transfers, passed to ack callback on source chain and to receive callback on target chain.
+#[cw_serde]
+pub enum CallbackData {
+    /// NFT is staked on target chain on behalf of `staker`.
+    Stake {
+        sender: String,
+        staker: String,
+        token_id: String,
+    },
+    /// Voucher is unstaked, and NFT is transferred to `receiver` on home chain.
+    Unstake {
+        sender: String,
+        receiver: String,
+        token_id: String,
+    },
+}
+
+impl CallbackData {
+    /// Memo with ack callback to source contract, and receive callback to counterparty contract on target chain.
+    pub fn to_memo(
+        &self,
+        ack_callback_addr: String,
+        counterparty_contract: String,
+    ) -> StdResult<Ics721Memo> {
+        callback_memo(self, ack_callback_addr, Some(counterparty_contract))
+    }
+}
+
+/// Interface of the local staking contract. Vouchers are staked via `send_nft` with `StakeMsg` as msg.
+#[cw_serde]
+pub enum StakingExecuteMsg {
+    ReceiveNft(Cw721ReceiveMsg),
+    /// Returns NFT to the contract which staked it, and pays out rewards to `rewards_recipient`.
+    Unstake {
+        cw721: String,
+        token_id: String,
+        rewards_recipient: String,
+    },
+}
+
+#[cw_serde]
+pub struct StakeMsg {
+    pub staker: String,
+}
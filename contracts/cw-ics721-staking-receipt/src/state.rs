@@ -0,0 +1,16 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+
+pub const CONFIG: Item<Config> = Item::new("config");
+/// stakers of vouchers staked on receive, by voucher cw721 and token id
+pub const STAKERS: Map<(&str, &str), Addr> = Map::new("stakers");
+
+#[cw_serde]
+pub struct Config {
+    pub ics721: Addr,
+    /// local staking contract, vouchers are staked into it on receive
+    pub staking: Addr,
+    /// staking receipt contract on the other chain, used as NFT receiver and receive callback address
+    pub counterparty_contract: Option<String>,
+}
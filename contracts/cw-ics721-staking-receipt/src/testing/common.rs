@@ -0,0 +1,110 @@
+use cosmwasm_std::{
+    coins, from_json, to_json_binary, Addr, BankMsg, Binary, Deps, DepsMut, Empty, Env,
+    MessageInfo, Reply, Response, StdError, StdResult, WasmMsg,
+};
+use cw721_base::{DefaultOptionalCollectionExtensionMsg, DefaultOptionalNftExtensionMsg};
+use cw_multi_test::{Contract, ContractWrapper};
+use cw_storage_plus::Map;
+use ics721::ContractError as Ics721ContractError;
+
+use crate::{
+    execute,
+    msg::{StakeMsg, StakingExecuteMsg},
+};
+
+pub fn staking_receipt_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(execute::execute, execute::instantiate, execute::query);
+    Box::new(contract)
+}
+
+pub fn cw721_base_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        cw721_base::entry::execute,
+        cw721_base::entry::instantiate,
+        cw721_base::entry::query,
+    );
+    Box::new(contract)
+}
+
+pub fn ics721_contract() -> Box<dyn Contract<Empty>> {
+    // need to wrap method in function for testing
+    fn ibc_reply(deps: DepsMut, env: Env, reply: Reply) -> Result<Response, Ics721ContractError> {
+        ics721_base::reply(deps, env, reply)
+    }
+
+    let contract = ContractWrapper::new(
+        ics721_base::execute,
+        ics721_base::instantiate,
+        ics721_base::query,
+    )
+    .with_reply(ibc_reply);
+    Box::new(contract)
+}
+
+/// Rewards paid out by mock staking contract on each unstake.
+pub const STAKING_REWARD: u128 = 100;
+pub const STAKING_DENOM: &str = "ustake";
+/// contract which staked an NFT, by cw721 and token id
+const STAKED: Map<(&str, &str), Addr> = Map::new("staked");
+
+/// Mock of a local staking contract, implementing `StakingExecuteMsg`. Rewards are paid from its own balance.
+pub fn staking_contract() -> Box<dyn Contract<Empty>> {
+    fn instantiate(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        Ok(Response::default())
+    }
+
+    fn execute(
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        msg: StakingExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            StakingExecuteMsg::ReceiveNft(msg) => {
+                let _: StakeMsg = from_json(&msg.msg)?;
+                let sender = deps.api.addr_validate(&msg.sender)?;
+                STAKED.save(deps.storage, (info.sender.as_str(), &msg.token_id), &sender)?;
+                Ok(Response::default())
+            }
+            StakingExecuteMsg::Unstake {
+                cw721,
+                token_id,
+                rewards_recipient,
+            } => {
+                let sender = STAKED.load(deps.storage, (&cw721, &token_id))?;
+                if sender != info.sender {
+                    return Err(StdError::generic_err("unauthorized"));
+                }
+                STAKED.remove(deps.storage, (&cw721, &token_id));
+                Ok(Response::default()
+                    .add_message(WasmMsg::Execute {
+                        contract_addr: cw721,
+                        msg: to_json_binary(&cw721_base::msg::ExecuteMsg::<
+                            DefaultOptionalNftExtensionMsg,
+                            DefaultOptionalCollectionExtensionMsg,
+                            Empty,
+                        >::TransferNft {
+                            recipient: sender.to_string(),
+                            token_id,
+                        })?,
+                        funds: vec![],
+                    })
+                    .add_message(BankMsg::Send {
+                        to_address: rewards_recipient,
+                        amount: coins(STAKING_REWARD, STAKING_DENOM),
+                    }))
+            }
+        }
+    }
+
+    fn query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        Err(StdError::generic_err("no queries"))
+    }
+
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
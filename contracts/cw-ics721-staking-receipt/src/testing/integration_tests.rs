@@ -0,0 +1,803 @@
+use super::common::{
+    cw721_base_contract, ics721_contract, staking_contract, staking_receipt_contract,
+    STAKING_DENOM, STAKING_REWARD,
+};
+use crate::{
+    error::ContractError,
+    msg::{CallbackData, ExecuteMsg, InstantiateMsg, QueryMsg},
+    state::Config,
+};
+use cosmwasm_std::{coins, to_json_binary, Addr, Empty, IbcTimeout, Timestamp, Uint128};
+use cw721_base::{
+    msg::{InstantiateMsg as Cw721InstantiateMsg, OwnerOfResponse},
+    DefaultOptionalCollectionExtension, DefaultOptionalCollectionExtensionMsg,
+    DefaultOptionalNftExtension, DefaultOptionalNftExtensionMsg,
+};
+use cw_ics721_callback::{
+    multi_test::{mock_app, MockApp},
+    TIMEOUT_ERROR,
+};
+use cw_multi_test::{AppResponse, Executor};
+use cw_ownable::OwnershipError;
+use ics721::{
+    msg::InstantiateMsg as Ics721InstantiateMsg, ClassId, NonFungibleTokenPacketData, TokenId,
+};
+use ics721_types::{
+    ibc_types::IbcOutgoingMsg,
+    types::{Ics721AckCallbackMsg, Ics721ReceiveCallbackMsg, Ics721Status},
+};
+
+const CREATOR_WALLET: &str = "creator";
+const NFT_OWNER_WALLET: &str = "nft_owner";
+const STAKER_WALLET: &str = "staker";
+const OTHER_CHAIN_WALLET: &str = "other_chain";
+const BECH32_PREFIX_HRP: &str = "ark";
+const CHANNEL: &str = "channel";
+const COUNTERPARTY_CONTRACT: &str = "counterparty_contract";
+
+/// Single chain setup, with real ics721 for outgoing transfers. Incoming transfers are simulated by calling callbacks
+/// from ics721 address, with NFTs pretended to be received by this contract.
+struct Test {
+    app: MockApp,
+    creator: Addr,
+    nft_owner: Addr,
+    staker: Addr,
+    other_chain_wallet: Addr,
+    code_id_receipt: u64,
+    addr_receipt_contract: Addr,
+    addr_cw721_contract: Addr,
+    addr_ics721_contract: Addr,
+    addr_staking_contract: Addr,
+}
+
+impl Test {
+    fn new() -> Self {
+        let mut app = mock_app(BECH32_PREFIX_HRP);
+        let code_id_receipt = app.store_code(staking_receipt_contract());
+        let code_id_cw721 = app.store_code(cw721_base_contract());
+        let code_id_ics721 = app.store_code(ics721_contract());
+        let code_id_staking = app.store_code(staking_contract());
+
+        let creator = app.api().addr_make(CREATOR_WALLET);
+        let addr_cw721_contract = app
+            .instantiate_contract(
+                code_id_cw721,
+                creator.clone(),
+                &Cw721InstantiateMsg::<DefaultOptionalCollectionExtensionMsg> {
+                    name: "name".to_string(),
+                    symbol: "symbol".to_string(),
+                    collection_info_extension: None,
+                    minter: None,  // none = sender/creator is minter
+                    creator: None, // none = sender/creator is creator
+                    withdraw_address: None,
+                },
+                &[],
+                "cw721-base",
+                None,
+            )
+            .unwrap();
+        let addr_ics721_contract = app
+            .instantiate_contract(
+                code_id_ics721,
+                creator.clone(),
+                &Ics721InstantiateMsg {
+                    cw721_base_code_id: code_id_cw721,
+                    incoming_proxy: None,
+                    outgoing_proxy: None,
+                    pauser: None,
+                    cw721_admin: None,
+                    cw721_creator: None,
+                    contract_addr_length: None,
+                },
+                &[],
+                "ics721-base",
+                None,
+            )
+            .unwrap();
+        let addr_staking_contract = app
+            .instantiate_contract(
+                code_id_staking,
+                creator.clone(),
+                &Empty {},
+                &[],
+                "staking",
+                None,
+            )
+            .unwrap();
+        let addr_receipt_contract = app
+            .instantiate_contract(
+                code_id_receipt,
+                creator.clone(),
+                &InstantiateMsg {
+                    ics721: addr_ics721_contract.to_string(),
+                    staking: addr_staking_contract.to_string(),
+                },
+                &[],
+                "staking-receipt",
+                None,
+            )
+            .unwrap();
+        // rewards are paid from staking contract balance
+        let staking = addr_staking_contract.clone();
+        app.init_modules(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &staking, coins(1000, STAKING_DENOM))
+                .unwrap()
+        });
+
+        let nft_owner = app.api().addr_make(NFT_OWNER_WALLET);
+        let staker = app.api().addr_make(STAKER_WALLET);
+        let other_chain_wallet = app.api().addr_make(OTHER_CHAIN_WALLET);
+
+        let mut test = Self {
+            app,
+            creator,
+            nft_owner,
+            staker,
+            other_chain_wallet,
+            code_id_receipt,
+            addr_receipt_contract,
+            addr_cw721_contract,
+            addr_ics721_contract,
+            addr_staking_contract,
+        };
+        test.execute_counter_party_contract(test.creator.clone(), COUNTERPARTY_CONTRACT)
+            .unwrap();
+        test
+    }
+
+    fn query_config(&mut self) -> Config {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.addr_receipt_contract.clone(), &QueryMsg::Config {})
+            .unwrap()
+    }
+
+    fn query_staker(&mut self, token_id: &str) -> Option<Addr> {
+        self.app
+            .wrap()
+            .query_wasm_smart(
+                self.addr_receipt_contract.clone(),
+                &QueryMsg::Staker {
+                    cw721: self.addr_cw721_contract.to_string(),
+                    token_id: token_id.to_string(),
+                },
+            )
+            .unwrap()
+    }
+
+    fn query_cw721_owner(&mut self, token_id: &str) -> Addr {
+        let res: OwnerOfResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                self.addr_cw721_contract.clone(),
+                &cw721_base::msg::QueryMsg::<
+                    DefaultOptionalNftExtension,
+                    DefaultOptionalCollectionExtension,
+                    Empty,
+                >::OwnerOf {
+                    token_id: token_id.to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        Addr::unchecked(res.owner)
+    }
+
+    fn query_balance(&self, addr: &Addr) -> Uint128 {
+        self.app
+            .wrap()
+            .query_balance(addr, STAKING_DENOM)
+            .unwrap()
+            .amount
+    }
+
+    fn execute_counter_party_contract(
+        &mut self,
+        sender: Addr,
+        addr: &str,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            sender,
+            self.addr_receipt_contract.clone(),
+            &ExecuteMsg::CounterPartyContract {
+                addr: addr.to_string(),
+            },
+            &[],
+        )
+    }
+
+    fn execute_cw721_mint(&mut self, owner: Addr, token_id: &str) {
+        self.app
+            .execute_contract(
+                self.creator.clone(),
+                self.addr_cw721_contract.clone(),
+                &cw721_base::msg::ExecuteMsg::<
+                    DefaultOptionalNftExtensionMsg,
+                    DefaultOptionalCollectionExtensionMsg,
+                    Empty,
+                >::Mint {
+                    token_id: token_id.to_string(),
+                    owner: owner.to_string(),
+                    token_uri: None,
+                    extension: None,
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    /// Transfers NFT to other chain, to be staked on behalf of staker.
+    fn execute_cw721_send_nft(
+        &mut self,
+        token_id: &str,
+        staker: &str,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            self.nft_owner.clone(),
+            self.addr_cw721_contract.clone(),
+            &cw721_base::msg::ExecuteMsg::<
+                DefaultOptionalNftExtensionMsg,
+                DefaultOptionalCollectionExtensionMsg,
+                Empty,
+            >::SendNft {
+                contract: self.addr_receipt_contract.to_string(),
+                token_id: token_id.to_string(),
+                msg: to_json_binary(&ibc_outgoing_msg(staker)).unwrap(),
+            },
+            &[],
+        )
+    }
+
+    /// Pretend ics721 returned escrowed NFT to this contract, like on failed ack or timeout.
+    fn return_escrowed_nft(&mut self, token_id: &str) {
+        self.app
+            .execute_contract(
+                self.addr_ics721_contract.clone(),
+                self.addr_cw721_contract.clone(),
+                &cw721_base::msg::ExecuteMsg::<
+                    DefaultOptionalNftExtensionMsg,
+                    DefaultOptionalCollectionExtensionMsg,
+                    Empty,
+                >::TransferNft {
+                    recipient: self.addr_receipt_contract.to_string(),
+                    token_id: token_id.to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    fn execute_unstake(
+        &mut self,
+        sender: Addr,
+        token_id: &str,
+        receiver: &str,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            sender,
+            self.addr_receipt_contract.clone(),
+            &ExecuteMsg::Unstake {
+                cw721: self.addr_cw721_contract.to_string(),
+                token_id: token_id.to_string(),
+                msg: ibc_outgoing_msg(receiver),
+            },
+            &[],
+        )
+    }
+
+    fn execute_receive_callback(
+        &mut self,
+        ics721: Addr,
+        msg: CallbackData,
+        token_id: &str,
+        packet_sender: String,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            ics721,
+            self.addr_receipt_contract.clone(),
+            &ExecuteMsg::Ics721ReceiveCallback(Ics721ReceiveCallbackMsg {
+                msg: to_json_binary(&msg).unwrap(),
+                nft_contract: self.addr_cw721_contract.to_string(), // pretend this is the voucher contract
+                original_packet: packet(token_id, packet_sender),
+            }),
+            &[],
+        )
+    }
+
+    fn execute_ack_callback(
+        &mut self,
+        ics721: Addr,
+        status: Ics721Status,
+        msg: CallbackData,
+        token_id: &str,
+        packet_sender: String,
+    ) -> Result<AppResponse, anyhow::Error> {
+        self.app.execute_contract(
+            ics721,
+            self.addr_receipt_contract.clone(),
+            &ExecuteMsg::Ics721AckCallback(Ics721AckCallbackMsg {
+                status,
+                nft_contract: self.addr_cw721_contract.to_string(),
+                msg: to_json_binary(&msg).unwrap(),
+                original_packet: packet(token_id, packet_sender),
+            }),
+            &[],
+        )
+    }
+
+    /// Pretend voucher has been received by this contract, and staked via receive callback.
+    fn stake(&mut self, token_id: &str) {
+        self.execute_cw721_mint(self.addr_receipt_contract.clone(), token_id);
+        self.execute_receive_callback(
+            self.addr_ics721_contract.clone(),
+            CallbackData::Stake {
+                sender: self.other_chain_wallet.to_string(),
+                staker: self.staker.to_string(),
+                token_id: token_id.to_string(),
+            },
+            token_id,
+            COUNTERPARTY_CONTRACT.to_string(),
+        )
+        .unwrap();
+    }
+}
+
+fn ibc_outgoing_msg(receiver: &str) -> IbcOutgoingMsg {
+    IbcOutgoingMsg {
+        receiver: receiver.to_string(),
+        channel_id: CHANNEL.to_string(),
+        timeout: IbcTimeout::with_timestamp(Timestamp::from_seconds(0)),
+        memo: None,
+    }
+}
+
+/// Packet sent by counterparty contract on receive, and by this contract on ack.
+fn packet(token_id: &str, sender: String) -> NonFungibleTokenPacketData {
+    NonFungibleTokenPacketData {
+        class_id: ClassId::new("some/class/id"),
+        token_ids: vec![TokenId::new(token_id)],
+        receiver: COUNTERPARTY_CONTRACT.to_string(),
+        sender,
+        class_data: None,
+        class_uri: None,
+        memo: None,
+        token_data: None,
+        token_uris: None,
+    }
+}
+
+#[test]
+fn test_instantiate() {
+    let mut test = Test::new();
+    assert_eq!(
+        test.query_config(),
+        Config {
+            ics721: test.addr_ics721_contract.clone(),
+            staking: test.addr_staking_contract.clone(),
+            counterparty_contract: Some(COUNTERPARTY_CONTRACT.to_string()),
+        }
+    );
+}
+
+#[test]
+fn test_execute_counter_party_contract() {
+    let mut test = Test::new();
+
+    // case 1: only owner may set counterparty contract
+    let err: ContractError = test
+        .execute_counter_party_contract(test.nft_owner.clone(), "other")
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+
+    // case 2: owner sets counterparty contract
+    test.execute_counter_party_contract(test.creator.clone(), "other")
+        .unwrap();
+    assert_eq!(
+        test.query_config().counterparty_contract,
+        Some("other".to_string())
+    );
+}
+
+#[test]
+fn test_receive_nft() {
+    // case 1: transfer fails without counterparty contract, NFT stays with owner
+    {
+        let mut test = Test::new();
+        test.addr_receipt_contract = test
+            .app
+            .instantiate_contract(
+                test.code_id_receipt,
+                test.creator.clone(),
+                &InstantiateMsg {
+                    ics721: test.addr_ics721_contract.to_string(),
+                    staking: test.addr_staking_contract.to_string(),
+                },
+                &[],
+                "staking-receipt",
+                None,
+            )
+            .unwrap();
+        test.execute_cw721_mint(test.nft_owner.clone(), "1");
+        let err: ContractError = test
+            .execute_cw721_send_nft("1", "staker")
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, ContractError::MissingCounterpartyContract {});
+        assert_eq!(test.query_cw721_owner("1"), test.nft_owner);
+    }
+
+    // case 2: NFT is forwarded to ics721, with counterparty contract as receiver and staker in callback data
+    {
+        let mut test = Test::new();
+        test.execute_cw721_mint(test.nft_owner.clone(), "1");
+        let res = test.execute_cw721_send_nft("1", "staker").unwrap();
+        assert_eq!(test.query_cw721_owner("1"), test.addr_ics721_contract);
+        let event = res
+            .events
+            .iter()
+            .find(|event| {
+                event.ty == "wasm"
+                    && event
+                        .attributes
+                        .iter()
+                        .any(|attr| attr.key == "method" && attr.value == "execute_receive_nft")
+            })
+            .unwrap();
+        for (key, value) in [
+            ("sender", test.nft_owner.as_str()),
+            ("staker", "staker"),
+            ("channel_id", CHANNEL),
+        ] {
+            assert!(event
+                .attributes
+                .iter()
+                .any(|attr| attr.key == key && attr.value == value));
+        }
+    }
+}
+
+#[test]
+fn test_receive_callback() {
+    // case 1: only ics721 can call back
+    {
+        let mut test = Test::new();
+        let err: ContractError = test
+            .execute_receive_callback(
+                test.addr_cw721_contract.clone(), // unauthorized
+                CallbackData::Stake {
+                    sender: test.other_chain_wallet.to_string(),
+                    staker: test.staker.to_string(),
+                    token_id: "1".to_string(),
+                },
+                "1",
+                COUNTERPARTY_CONTRACT.to_string(),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, ContractError::UnauthorizedCallback {});
+    }
+
+    // case 2: invalid staker reverts transfer
+    {
+        let mut test = Test::new();
+        test.execute_cw721_mint(test.addr_receipt_contract.clone(), "1");
+        test.execute_receive_callback(
+            test.addr_ics721_contract.clone(),
+            CallbackData::Stake {
+                sender: test.other_chain_wallet.to_string(),
+                staker: "invalid".to_string(),
+                token_id: "1".to_string(),
+            },
+            "1",
+            COUNTERPARTY_CONTRACT.to_string(),
+        )
+        .unwrap_err();
+        assert_eq!(test.query_staker("1"), None);
+    }
+
+    // case 3: received voucher is staked on behalf of staker
+    {
+        let mut test = Test::new();
+        test.stake("1");
+        assert_eq!(test.query_cw721_owner("1"), test.addr_staking_contract);
+        assert_eq!(test.query_staker("1"), Some(test.staker.clone()));
+    }
+
+    // case 4: on back transfer to home chain, NFT is transferred to receiver
+    {
+        let mut test = Test::new();
+        test.execute_cw721_mint(test.addr_receipt_contract.clone(), "1");
+        test.execute_receive_callback(
+            test.addr_ics721_contract.clone(),
+            CallbackData::Unstake {
+                sender: test.other_chain_wallet.to_string(),
+                receiver: test.nft_owner.to_string(),
+                token_id: "1".to_string(),
+            },
+            "1",
+            COUNTERPARTY_CONTRACT.to_string(),
+        )
+        .unwrap();
+        assert_eq!(test.query_cw721_owner("1"), test.nft_owner);
+        assert_eq!(test.query_staker("1"), None);
+    }
+
+    // case 5: only counterparty contract sends packets with trusted callback data
+    {
+        let mut test = Test::new();
+        test.execute_cw721_mint(test.addr_receipt_contract.clone(), "1");
+        let err: ContractError = test
+            .execute_receive_callback(
+                test.addr_ics721_contract.clone(),
+                CallbackData::Unstake {
+                    sender: test.other_chain_wallet.to_string(),
+                    receiver: test.other_chain_wallet.to_string(),
+                    token_id: "1".to_string(),
+                },
+                "1",
+                test.other_chain_wallet.to_string(),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::UnauthorizedPacketSender {
+                sender: test.other_chain_wallet.to_string(),
+            }
+        );
+        assert_eq!(test.query_cw721_owner("1"), test.addr_receipt_contract);
+    }
+
+    // case 6: tokens are taken from packet, token id of callback data is ignored
+    {
+        let mut test = Test::new();
+        test.execute_cw721_mint(test.addr_receipt_contract.clone(), "1");
+        test.execute_cw721_mint(test.addr_receipt_contract.clone(), "2");
+        test.execute_receive_callback(
+            test.addr_ics721_contract.clone(),
+            CallbackData::Stake {
+                sender: test.other_chain_wallet.to_string(),
+                staker: test.staker.to_string(),
+                token_id: "2".to_string(),
+            },
+            "1",
+            COUNTERPARTY_CONTRACT.to_string(),
+        )
+        .unwrap();
+        assert_eq!(test.query_staker("1"), Some(test.staker.clone()));
+        assert_eq!(test.query_staker("2"), None);
+        assert_eq!(test.query_cw721_owner("2"), test.addr_receipt_contract);
+    }
+}
+
+#[test]
+fn test_unstake() {
+    let mut test = Test::new();
+    test.stake("1");
+
+    // case 1: only staker can unstake
+    let err: ContractError = test
+        .execute_unstake(test.nft_owner.clone(), "1", "receiver")
+        .unwrap_err()
+        .downcast()
+        .unwrap();
+    assert_eq!(
+        err,
+        ContractError::NotStaker {
+            cw721: test.addr_cw721_contract.to_string(),
+            token_id: "1".to_string(),
+        }
+    );
+
+    // case 2: voucher is unstaked, rewards are paid out, and voucher is forwarded to ics721
+    test.execute_unstake(test.staker.clone(), "1", "receiver")
+        .unwrap();
+    assert_eq!(test.query_cw721_owner("1"), test.addr_ics721_contract);
+    assert_eq!(test.query_staker("1"), None);
+    assert_eq!(
+        test.query_balance(&test.staker),
+        Uint128::new(STAKING_REWARD)
+    );
+
+    // case 3: voucher can't be unstaked twice
+    test.execute_unstake(test.staker.clone(), "1", "receiver")
+        .unwrap_err();
+}
+
+#[test]
+fn test_ack_callback() {
+    // case 1: only ics721 can call back
+    {
+        let mut test = Test::new();
+        let err: ContractError = test
+            .execute_ack_callback(
+                test.addr_cw721_contract.clone(), // unauthorized
+                Ics721Status::Success,
+                CallbackData::Stake {
+                    sender: test.nft_owner.to_string(),
+                    staker: "staker".to_string(),
+                    token_id: "1".to_string(),
+                },
+                "1",
+                test.addr_receipt_contract.to_string(),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(err, ContractError::UnauthorizedCallback {});
+    }
+
+    // case 2: on success, NFT stays escrowed by ics721
+    {
+        let mut test = Test::new();
+        test.execute_cw721_mint(test.nft_owner.clone(), "1");
+        test.execute_cw721_send_nft("1", "staker").unwrap();
+        test.execute_ack_callback(
+            test.addr_ics721_contract.clone(),
+            Ics721Status::Success,
+            CallbackData::Stake {
+                sender: test.nft_owner.to_string(),
+                staker: "staker".to_string(),
+                token_id: "1".to_string(),
+            },
+            "1",
+            test.addr_receipt_contract.to_string(),
+        )
+        .unwrap();
+        assert_eq!(test.query_cw721_owner("1"), test.addr_ics721_contract);
+    }
+
+    // case 3: failed stake transfer, returned NFT is transferred back to its owner
+    {
+        let mut test = Test::new();
+        // pretend NFT has been returned to this contract by ics721
+        test.execute_cw721_mint(test.addr_receipt_contract.clone(), "1");
+        test.execute_ack_callback(
+            test.addr_ics721_contract.clone(),
+            Ics721Status::Failed("error".to_string()),
+            CallbackData::Stake {
+                sender: test.nft_owner.to_string(),
+                staker: "staker".to_string(),
+                token_id: "1".to_string(),
+            },
+            "1",
+            test.addr_receipt_contract.to_string(),
+        )
+        .unwrap();
+        assert_eq!(test.query_cw721_owner("1"), test.nft_owner);
+    }
+
+    // case 4: timed out unstake transfer, returned voucher is staked again
+    {
+        let mut test = Test::new();
+        test.execute_cw721_mint(test.addr_receipt_contract.clone(), "1");
+        test.execute_ack_callback(
+            test.addr_ics721_contract.clone(),
+            Ics721Status::Failed(TIMEOUT_ERROR.to_string()),
+            CallbackData::Unstake {
+                sender: test.staker.to_string(),
+                receiver: "receiver".to_string(),
+                token_id: "1".to_string(),
+            },
+            "1",
+            test.addr_receipt_contract.to_string(),
+        )
+        .unwrap();
+        assert_eq!(test.query_cw721_owner("1"), test.addr_staking_contract);
+        assert_eq!(test.query_staker("1"), Some(test.staker.clone()));
+    }
+
+    // case 5: forged memo, packet sent by another wallet calling back this contract
+    {
+        let mut test = Test::new();
+        test.execute_cw721_mint(test.addr_receipt_contract.clone(), "1");
+        let err: ContractError = test
+            .execute_ack_callback(
+                test.addr_ics721_contract.clone(),
+                Ics721Status::Failed("error".to_string()),
+                CallbackData::Stake {
+                    sender: test.other_chain_wallet.to_string(),
+                    staker: "staker".to_string(),
+                    token_id: "1".to_string(),
+                },
+                "1",
+                test.other_chain_wallet.to_string(),
+            )
+            .unwrap_err()
+            .downcast()
+            .unwrap();
+        assert_eq!(
+            err,
+            ContractError::UnauthorizedAckPacket {
+                sender: test.other_chain_wallet.to_string(),
+            }
+        );
+        assert_eq!(test.query_cw721_owner("1"), test.addr_receipt_contract);
+    }
+
+    // case 6: tokens are taken from packet, token id of callback data is ignored
+    {
+        let mut test = Test::new();
+        test.execute_cw721_mint(test.addr_receipt_contract.clone(), "1");
+        test.execute_cw721_mint(test.addr_receipt_contract.clone(), "2");
+        test.execute_ack_callback(
+            test.addr_ics721_contract.clone(),
+            Ics721Status::Failed("error".to_string()),
+            CallbackData::Stake {
+                sender: test.nft_owner.to_string(),
+                staker: "staker".to_string(),
+                token_id: "2".to_string(),
+            },
+            "1",
+            test.addr_receipt_contract.to_string(),
+        )
+        .unwrap();
+        assert_eq!(test.query_cw721_owner("1"), test.nft_owner);
+        assert_eq!(test.query_cw721_owner("2"), test.addr_receipt_contract);
+    }
+}
+
+#[test]
+fn test_unstake_ack_callback() {
+    // case 1: on success, unstaked voucher stays escrowed by ics721
+    {
+        let mut test = Test::new();
+        test.stake("1");
+        test.execute_unstake(test.staker.clone(), "1", "receiver")
+            .unwrap();
+        test.execute_ack_callback(
+            test.addr_ics721_contract.clone(),
+            Ics721Status::Success,
+            CallbackData::Unstake {
+                sender: test.staker.to_string(),
+                receiver: "receiver".to_string(),
+                token_id: "1".to_string(),
+            },
+            "1",
+            test.addr_receipt_contract.to_string(),
+        )
+        .unwrap();
+        assert_eq!(test.query_cw721_owner("1"), test.addr_ics721_contract);
+        assert_eq!(test.query_staker("1"), None);
+        assert_eq!(
+            test.query_balance(&test.staker),
+            Uint128::new(STAKING_REWARD)
+        );
+    }
+
+    // case 2: on timeout, voucher returned by ics721 is staked again for staker
+    {
+        let mut test = Test::new();
+        test.stake("1");
+        test.execute_unstake(test.staker.clone(), "1", "receiver")
+            .unwrap();
+        test.return_escrowed_nft("1");
+        test.execute_ack_callback(
+            test.addr_ics721_contract.clone(),
+            Ics721Status::Failed(TIMEOUT_ERROR.to_string()),
+            CallbackData::Unstake {
+                sender: test.staker.to_string(),
+                receiver: "receiver".to_string(),
+                token_id: "1".to_string(),
+            },
+            "1",
+            test.addr_receipt_contract.to_string(),
+        )
+        .unwrap();
+        assert_eq!(test.query_cw721_owner("1"), test.addr_staking_contract);
+        assert_eq!(test.query_staker("1"), Some(test.staker.clone()));
+
+        // staked voucher can be unstaked again
+        test.execute_unstake(test.staker.clone(), "1", "receiver")
+            .unwrap();
+        assert_eq!(test.query_cw721_owner("1"), test.addr_ics721_contract);
+    }
+}
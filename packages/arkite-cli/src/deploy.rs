@@ -0,0 +1,216 @@
+//! Deployment actions on a chain. Addresses of instantiated contracts are written to its chain profile.
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::{
+    manifest::Manifest,
+    msgs,
+    planner::{Action, Step},
+    profile::ChainProfile,
+    tx::{find_attribute, ChainCli, Mode, WasmTx},
+};
+
+/// rate limit of outgoing proxy, in case it is not defined by a manifest
+pub const DEFAULT_RATE_LIMIT_PER_BLOCK: u64 = 1;
+
+pub fn setup_contracts(profile: &mut ChainProfile, mode: Mode) -> Result<()> {
+    let cli = ChainCli::new(profile, mode);
+    let tx = WasmTx::instantiate(
+        profile.get_u64("CODE_ID_ARKITE_PASSPORT")?,
+        &msgs::passport_instantiate_msg(profile)?,
+        msgs::PASSPORT_LABEL,
+    )?;
+    let Some(tx_hash) = cli.tx(&tx)? else {
+        return Ok(());
+    };
+    let tx = cli.wait_for_tx(&tx_hash)?;
+    // first instantiated contract is arkite passport, other addresses are set in replies
+    let addresses = [
+        ("ADDR_ARKITE_PASSPORT", "instantiate", "_contract_address"),
+        ("ADDR_CW721", "wasm", "addr_cw721"),
+        ("ADDR_POAP", "wasm", "addr_poap"),
+        ("ADDR_ICS721", "wasm", "addr_ics721"),
+    ]
+    .into_iter()
+    .map(|(key, event_type, attribute)| {
+        find_attribute(&tx, event_type, attribute)
+            .map(|addr| (key, addr))
+            .ok_or_else(|| anyhow!("no {attribute} in tx {tx_hash}"))
+    })
+    .collect::<Result<Vec<_>>>()?;
+    for (key, addr) in addresses {
+        eprintln!("{key}: {addr}");
+        profile.set(key, &addr)?;
+    }
+    Ok(())
+}
+
+/// Instantiates incoming and outgoing proxies, and migrates ics721 with proxies.
+pub fn setup_proxies(profile: &mut ChainProfile, mode: Mode) -> Result<()> {
+    let channels = vec![profile.get("CHANNEL_ID")?.to_string()];
+    instantiate_incoming_proxy(profile, mode, channels)?;
+    instantiate_outgoing_proxy(profile, mode, DEFAULT_RATE_LIMIT_PER_BLOCK)?;
+    migrate_ics721(profile, mode)
+}
+
+pub fn instantiate_incoming_proxy(
+    profile: &mut ChainProfile,
+    mode: Mode,
+    channels: Vec<String>,
+) -> Result<()> {
+    let tx = WasmTx::instantiate(
+        profile.get_u64("CODE_ID_INCOMING_PROXY")?,
+        &msgs::incoming_proxy_instantiate_msg(profile, channels)?,
+        msgs::INCOMING_PROXY_LABEL,
+    )?;
+    instantiate(profile, mode, "ADDR_INCOMING_PROXY", &tx)
+}
+
+pub fn instantiate_outgoing_proxy(
+    profile: &mut ChainProfile,
+    mode: Mode,
+    rate_limit_per_block: u64,
+) -> Result<()> {
+    let tx = WasmTx::instantiate(
+        profile.get_u64("CODE_ID_OUTGOING_PROXY")?,
+        &msgs::outgoing_proxy_instantiate_msg(profile, rate_limit_per_block)?,
+        msgs::OUTGOING_PROXY_LABEL,
+    )?;
+    instantiate(profile, mode, "ADDR_OUTGOING_PROXY", &tx)
+}
+
+/// Instantiates contract and sets its address in chain profile.
+fn instantiate(profile: &mut ChainProfile, mode: Mode, key: &str, tx: &WasmTx) -> Result<()> {
+    let cli = ChainCli::new(profile, mode);
+    let Some(tx_hash) = cli.tx(tx)? else {
+        return Ok(());
+    };
+    let tx = cli.wait_for_tx(&tx_hash)?;
+    let addr = find_attribute(&tx, "instantiate", "_contract_address")
+        .ok_or_else(|| anyhow!("no contract address in tx {tx_hash}"))?;
+    eprintln!("{key}: {addr}");
+    profile.set(key, &addr)
+}
+
+pub fn migrate_ics721(profile: &ChainProfile, mode: Mode) -> Result<()> {
+    let tx = WasmTx::migrate(
+        profile.get("ADDR_ICS721")?,
+        profile.get_u64("CODE_ID_ICS721")?,
+        &msgs::ics721_migrate_msg(profile)?,
+    )?;
+    ChainCli::new(profile, mode).tx(&tx)?;
+    Ok(())
+}
+
+pub fn migrate_passport(profile: &ChainProfile, mode: Mode) -> Result<()> {
+    let tx = WasmTx::migrate(
+        profile.get("ADDR_ARKITE_PASSPORT")?,
+        profile.get_u64("CODE_ID_ARKITE_PASSPORT")?,
+        &msgs::passport_migrate_msg(profile)?,
+    )?;
+    ChainCli::new(profile, mode).tx(&tx)?;
+    Ok(())
+}
+
+/// Migrates passport or poap collection to cw721 code id of chain profile, passport stays creator.
+pub fn migrate_cw721(profile: &ChainProfile, cw721: &str, mode: Mode) -> Result<()> {
+    let tx = WasmTx::migrate(
+        cw721,
+        profile.get_u64("CODE_ID_CW721")?,
+        &msgs::cw721_creator_migrate_msg(profile)?,
+    )?;
+    ChainCli::new(profile, mode).tx(&tx)?;
+    Ok(())
+}
+
+pub fn update_passport_config(profile: &ChainProfile, mode: Mode) -> Result<()> {
+    let tx = WasmTx::execute(
+        profile.get("ADDR_ARKITE_PASSPORT")?,
+        &msgs::passport_update_config_msg(profile)?,
+    )?;
+    ChainCli::new(profile, mode).tx(&tx)?;
+    Ok(())
+}
+
+/// Sets passport of counterparty as receive callback contract.
+pub fn register_counterparty(
+    profile: &ChainProfile,
+    counterparty: &ChainProfile,
+    mode: Mode,
+) -> Result<()> {
+    let tx = WasmTx::execute(
+        profile.get("ADDR_ARKITE_PASSPORT")?,
+        &msgs::counterparty_contract_msg(counterparty)?,
+    )?;
+    ChainCli::new(profile, mode).tx(&tx)?;
+    Ok(())
+}
+
+/// Loads chain profiles of all chains in manifest, with manifest values applied.
+/// Values are written to env files on broadcast only, printing commands and generating txs have no side effects.
+pub fn load_profiles(
+    scripts_dir: &Path,
+    manifest: &Manifest,
+    mode: Mode,
+) -> Result<BTreeMap<String, ChainProfile>> {
+    let mut profiles = BTreeMap::new();
+    for (chain, desired) in &manifest.chains {
+        let mut profile = ChainProfile::load(scripts_dir, chain)?;
+        for (key, value) in desired.profile_vars() {
+            if profile.get(key).ok() == Some(value.as_str()) {
+                continue;
+            }
+            match mode {
+                Mode::Print | Mode::GenerateOnly => profile.override_var(key, &value),
+                Mode::Broadcast => profile.set(key, &value)?,
+            }
+        }
+        profiles.insert(chain.clone(), profile);
+    }
+    Ok(profiles)
+}
+
+/// Executes steps in order, profiles are updated with addresses of instantiated contracts.
+pub fn apply(
+    manifest: &Manifest,
+    profiles: &mut BTreeMap<String, ChainProfile>,
+    steps: &[Step],
+    mode: Mode,
+) -> Result<()> {
+    for step in steps {
+        eprintln!("============ {step}");
+        run_step(manifest, profiles, step, mode).with_context(|| format!("failed: {step}"))?;
+    }
+    Ok(())
+}
+
+fn run_step(
+    manifest: &Manifest,
+    profiles: &mut BTreeMap<String, ChainProfile>,
+    step: &Step,
+    mode: Mode,
+) -> Result<()> {
+    let desired = &manifest.chains[&step.chain];
+    let mut profile = || {
+        profiles
+            .get_mut(&step.chain)
+            .ok_or_else(|| anyhow!("no chain profile for {}", step.chain))
+    };
+    match &step.action {
+        Action::InstantiatePassport => setup_contracts(profile()?, mode),
+        Action::MigratePassport => migrate_passport(profile()?, mode),
+        Action::UpdatePassportConfig => update_passport_config(profile()?, mode),
+        Action::MigrateCw721 { cw721 } => migrate_cw721(profile()?, cw721, mode),
+        Action::InstantiateIncomingProxy => {
+            instantiate_incoming_proxy(profile()?, mode, desired.incoming_channels())
+        }
+        Action::InstantiateOutgoingProxy => {
+            instantiate_outgoing_proxy(profile()?, mode, desired.proxies.outgoing_rate_limit)
+        }
+        Action::MigrateIcs721 => migrate_ics721(profile()?, mode),
+        Action::RegisterCounterparty { counterparty } => {
+            register_counterparty(&profiles[&step.chain], &profiles[counterparty], mode)
+        }
+    }
+}
@@ -0,0 +1,559 @@
+//! Deploys and operates arkite passport contracts. Messages are built from contract types, and txs are
+//! signed and broadcasted by the chain binary defined in chain profiles (`scripts/<chain>.env`).
+mod deploy;
+mod manifest;
+mod msgs;
+mod payload;
+mod planner;
+mod profile;
+mod tx;
+
+#[cfg(test)]
+mod testing;
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Parser, Subcommand};
+use cosmwasm_std::{Decimal, Timestamp};
+use cw_ics721_arkite_passport::state::{CollectionMetadata, MetadataPolicy, RoyaltyInfo};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    manifest::Manifest,
+    profile::ChainProfile,
+    tx::{find_attribute, ChainCli, Mode, WasmTx},
+};
+
+#[derive(Parser)]
+#[command(name = "arkite-cli", version, about)]
+struct Args {
+    /// folder with chain profiles, e.g. `osmosis.env` and `stargaze.env`
+    #[arg(long, global = true, default_value = "scripts")]
+    scripts_dir: PathBuf,
+    /// print commands, without executing them
+    #[arg(long, global = true, conflicts_with = "generate_only")]
+    print: bool,
+    /// print unsigned txs for offline signing, instead of broadcasting them
+    #[arg(long, global = true)]
+    generate_only: bool,
+    #[command(subcommand)]
+    command: Cmd,
+}
+
+#[derive(Subcommand)]
+enum Cmd {
+    /// Uploads wasm binaries and updates code ids in chain profile
+    Upload {
+        #[arg(long)]
+        chain: String,
+        /// wasm binaries, defaults to all binaries in artifacts and scripts folder
+        wasm: Vec<PathBuf>,
+    },
+    /// Instantiates arkite passport, including passport and poap collection, and ics721
+    SetupContracts {
+        #[arg(long)]
+        chain: String,
+    },
+    /// Instantiates incoming and outgoing proxies, and migrates ics721 with proxies
+    SetupProxies {
+        #[arg(long)]
+        chain: String,
+    },
+    /// Sets passport on target chain as counterparty contract
+    SetupCounterparty {
+        #[arg(long)]
+        chain: String,
+        #[arg(long)]
+        target_chain: String,
+    },
+    /// Mints a passport NFT
+    Mint {
+        #[arg(long)]
+        chain: String,
+    },
+    /// Transfers a passport NFT to target chain
+    Transfer {
+        #[arg(long)]
+        chain: String,
+        #[arg(long)]
+        target_chain: String,
+        #[arg(long)]
+        token_id: String,
+        /// collection, defaults to passport collection, for back transfers use voucher collection
+        #[arg(long)]
+        cw721: Option<String>,
+        /// defaults to whitelisted channel of chain profile
+        #[arg(long)]
+        channel_id: Option<String>,
+        #[arg(long, default_value_t = 5)]
+        timeout_minutes: u64,
+        /// relay packets using hermes, once tx is included in a block
+        #[arg(long)]
+        relay: bool,
+    },
+    /// Migrates arkite passport with token uris of chain profile
+    MigratePassport {
+        #[arg(long)]
+        chain: String,
+    },
+    /// Migrates ics721 with proxies of chain profile
+    MigrateIcs721 {
+        #[arg(long)]
+        chain: String,
+    },
+    /// Migrates a cw721 collection with arkite passport as creator
+    MigrateCw721Creator {
+        #[arg(long)]
+        chain: String,
+        #[arg(long)]
+        cw721: String,
+    },
+    /// Prints steps for reaching state described by manifest, diffing it against deployed contracts
+    Plan {
+        #[arg(long, default_value = "scripts/deployment.toml")]
+        manifest: PathBuf,
+    },
+    /// Executes steps for reaching state described by manifest, manifest values are written to chain profiles
+    Apply {
+        #[arg(long, default_value = "scripts/deployment.toml")]
+        manifest: PathBuf,
+    },
+    /// Prints JSON of a message to send, with nested payloads built offline
+    Build {
+        #[command(subcommand)]
+        payload: BuildCmd,
+    },
+    /// Decodes nested base64 payloads of a message or tx into readable JSON, and validates them
+    Decode {
+        /// JSON of a message, a tx, or a base64 payload; `@<file>` reads from file, defaults to stdin
+        input: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum BuildCmd {
+    /// `SendNft` msg for cw721, transferring NFT via passport
+    SendNft {
+        #[arg(long)]
+        chain: String,
+        #[arg(long)]
+        target_chain: String,
+        #[arg(long)]
+        token_id: String,
+        /// defaults to wallet of target chain profile
+        #[arg(long)]
+        receiver: Option<String>,
+        /// defaults to whitelisted channel of chain profile
+        #[arg(long)]
+        channel_id: Option<String>,
+        #[arg(long, default_value_t = 5)]
+        timeout_minutes: u64,
+    },
+    /// Base64 `Ics721Memo`, with callbacks as created by passport
+    Memo {
+        #[arg(long)]
+        chain: String,
+        /// sets counterparty passport of target chain as receive callback
+        #[arg(long)]
+        target_chain: Option<String>,
+        #[arg(long)]
+        token_id: String,
+        /// NFT owner on source chain, defaults to wallet of chain profile
+        #[arg(long)]
+        sender: Option<String>,
+        /// metadata policy of collection: passport, pass_through or stamp
+        #[arg(long, default_value = "passport", value_parser = payload::parse_metadata_policy)]
+        metadata_policy: MetadataPolicy,
+        /// royalty info of passport config, together with royalty share
+        #[arg(long, requires = "royalty_share")]
+        royalty_payment_address: Option<String>,
+        /// e.g. 0.05 for 5%
+        #[arg(long, requires = "royalty_payment_address")]
+        royalty_share: Option<Decimal>,
+        /// collection metadata of passport config
+        #[arg(long)]
+        collection_description: Option<String>,
+        #[arg(long)]
+        collection_image: Option<String>,
+        #[arg(long)]
+        collection_external_link: Option<String>,
+    },
+    /// Passport `InstantiateMsg`, with embedded cw721 and ics721 instantiate infos
+    Instantiate {
+        #[arg(long)]
+        chain: String,
+    },
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let mode = match (args.print, args.generate_only) {
+        (true, _) => Mode::Print,
+        (_, true) => Mode::GenerateOnly,
+        _ => Mode::Broadcast,
+    };
+    let scripts_dir = args.scripts_dir.as_path();
+    match args.command {
+        Cmd::Upload { chain, wasm } => {
+            let mut profile = ChainProfile::load(scripts_dir, &chain)?;
+            upload(&mut profile, mode, wasm_files(scripts_dir, wasm)?)
+        }
+        Cmd::SetupContracts { chain } => {
+            let mut profile = ChainProfile::load(scripts_dir, &chain)?;
+            deploy::setup_contracts(&mut profile, mode)
+        }
+        Cmd::SetupProxies { chain } => {
+            let mut profile = ChainProfile::load(scripts_dir, &chain)?;
+            deploy::setup_proxies(&mut profile, mode)
+        }
+        Cmd::SetupCounterparty {
+            chain,
+            target_chain,
+        } => {
+            let profile = ChainProfile::load(scripts_dir, &chain)?;
+            let target = ChainProfile::load(scripts_dir, &target_chain)?;
+            deploy::register_counterparty(&profile, &target, mode)
+        }
+        Cmd::Mint { chain } => {
+            let profile = ChainProfile::load(scripts_dir, &chain)?;
+            mint(&profile, mode)
+        }
+        Cmd::Transfer {
+            chain,
+            target_chain,
+            token_id,
+            cw721,
+            channel_id,
+            timeout_minutes,
+            relay,
+        } => {
+            let profile = ChainProfile::load(scripts_dir, &chain)?;
+            let target = ChainProfile::load(scripts_dir, &target_chain)?;
+            let cw721 = match cw721 {
+                Some(cw721) => cw721,
+                None => profile.get("ADDR_CW721")?.to_string(),
+            };
+            let channel_id = match channel_id {
+                Some(channel_id) => channel_id,
+                None => profile.get("CHANNEL_ID")?.to_string(),
+            };
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+            let timeout = Timestamp::from_seconds(now.as_secs() + timeout_minutes * 60);
+            let msg = msgs::transfer_msg(
+                &profile,
+                &token_id,
+                target.get("WALLET_ARKITE_PASSPORT")?,
+                &channel_id,
+                timeout,
+            )?;
+            let cli = ChainCli::new(&profile, mode);
+            let Some(tx_hash) = cli.tx(&WasmTx::execute(&cw721, &msg)?)? else {
+                return Ok(());
+            };
+            cli.wait_for_tx(&tx_hash)?;
+            if relay {
+                relay_packets(&profile, &channel_id)?;
+                report_transfer(&profile, &target, mode, &cw721, &token_id)?;
+            }
+            Ok(())
+        }
+        Cmd::MigratePassport { chain } => {
+            let profile = ChainProfile::load(scripts_dir, &chain)?;
+            deploy::migrate_passport(&profile, mode)
+        }
+        Cmd::MigrateIcs721 { chain } => {
+            let profile = ChainProfile::load(scripts_dir, &chain)?;
+            deploy::migrate_ics721(&profile, mode)
+        }
+        Cmd::MigrateCw721Creator { chain, cw721 } => {
+            let profile = ChainProfile::load(scripts_dir, &chain)?;
+            deploy::migrate_cw721(&profile, &cw721, mode)
+        }
+        Cmd::Plan { manifest } => {
+            let manifest = Manifest::load(&manifest)?;
+            // profiles are not changed for planning
+            let profiles = deploy::load_profiles(scripts_dir, &manifest, Mode::Print)?;
+            let steps = plan(&manifest, &profiles)?;
+            if steps.is_empty() {
+                eprintln!("deployed contracts are up to date");
+            }
+            for step in steps {
+                println!("{step}");
+            }
+            Ok(())
+        }
+        Cmd::Apply { manifest } => {
+            let manifest = Manifest::load(&manifest)?;
+            let mut profiles = deploy::load_profiles(scripts_dir, &manifest, mode)?;
+            let steps = plan(&manifest, &profiles)?;
+            deploy::apply(&manifest, &mut profiles, &steps, mode)
+        }
+        Cmd::Build { payload } => build(scripts_dir, payload),
+        Cmd::Decode { input } => {
+            let input = match input {
+                Some(input) => match input.strip_prefix('@') {
+                    Some(file) => fs::read_to_string(file)?,
+                    None => input,
+                },
+                None => {
+                    let mut input = String::new();
+                    io::stdin().read_to_string(&mut input)?;
+                    input
+                }
+            };
+            let input = input.trim();
+            // base64 payloads are passed as plain string
+            let value = serde_json::from_str(input).unwrap_or_else(|_| Value::from(input));
+            let validated = payload::validate(&value)?;
+            println!("{}", serde_json::to_string_pretty(&payload::decode(value))?);
+            for payload in validated {
+                eprintln!("- {payload}");
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Queries deployed contracts on all chains and plans steps for reaching desired state.
+fn plan(
+    manifest: &Manifest,
+    profiles: &BTreeMap<String, ChainProfile>,
+) -> Result<Vec<planner::Step>> {
+    let mut states = BTreeMap::new();
+    for (chain, profile) in profiles {
+        // queries are executed in all modes
+        let cli = ChainCli::new(profile, Mode::Broadcast);
+        let state = planner::query_state(profile, &cli)
+            .with_context(|| format!("failed querying contracts on {chain}"))?;
+        states.insert(chain.clone(), state);
+    }
+    Ok(planner::plan(manifest, &states))
+}
+
+/// Prints exact JSON to send on stdout, and its decoded form on stderr.
+fn build(scripts_dir: &Path, payload: BuildCmd) -> Result<()> {
+    match payload {
+        BuildCmd::SendNft {
+            chain,
+            target_chain,
+            token_id,
+            receiver,
+            channel_id,
+            timeout_minutes,
+        } => {
+            let profile = ChainProfile::load(scripts_dir, &chain)?;
+            let target = ChainProfile::load(scripts_dir, &target_chain)?;
+            let receiver = match receiver {
+                Some(receiver) => receiver,
+                None => target.get("WALLET_ARKITE_PASSPORT")?.to_string(),
+            };
+            let channel_id = match channel_id {
+                Some(channel_id) => channel_id,
+                None => profile.get("CHANNEL_ID")?.to_string(),
+            };
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+            let timeout = Timestamp::from_seconds(now.as_secs() + timeout_minutes * 60);
+            let msg = msgs::transfer_msg(&profile, &token_id, &receiver, &channel_id, timeout)?;
+            print_payload(&msg)
+        }
+        BuildCmd::Memo {
+            chain,
+            target_chain,
+            token_id,
+            sender,
+            metadata_policy,
+            royalty_payment_address,
+            royalty_share,
+            collection_description,
+            collection_image,
+            collection_external_link,
+        } => {
+            let profile = ChainProfile::load(scripts_dir, &chain)?;
+            let target = match target_chain {
+                Some(target_chain) => Some(ChainProfile::load(scripts_dir, &target_chain)?),
+                None => None,
+            };
+            let sender = match sender {
+                Some(sender) => sender,
+                None => profile.get("WALLET_ARKITE_PASSPORT")?.to_string(),
+            };
+            let royalty_info =
+                royalty_payment_address
+                    .zip(royalty_share)
+                    .map(|(payment_address, share)| RoyaltyInfo {
+                        payment_address,
+                        share,
+                    });
+            let collection_metadata = CollectionMetadata {
+                description: collection_description,
+                image: collection_image,
+                external_link: collection_external_link,
+            };
+            let collection_metadata = (collection_metadata != CollectionMetadata::default())
+                .then_some(collection_metadata);
+            let memo = payload::memo(
+                &profile,
+                target.as_ref(),
+                &token_id,
+                &sender,
+                metadata_policy,
+                royalty_info,
+                collection_metadata,
+            )?;
+            print_payload(&payload::encode_memo(&memo)?)
+        }
+        BuildCmd::Instantiate { chain } => {
+            let profile = ChainProfile::load(scripts_dir, &chain)?;
+            print_payload(&msgs::passport_instantiate_msg(&profile)?)
+        }
+    }
+}
+
+fn print_payload(payload: &impl Serialize) -> Result<()> {
+    let value = serde_json::to_value(payload)?;
+    // fail early, in case a builder creates an invalid payload
+    payload::validate(&value)?;
+    println!("{}", serde_json::to_string(&value)?);
+    eprintln!("{}", serde_json::to_string_pretty(&payload::decode(value))?);
+    Ok(())
+}
+
+/// Given wasm files, or all wasm files in artifacts and scripts folder.
+fn wasm_files(scripts_dir: &Path, wasm: Vec<PathBuf>) -> Result<Vec<PathBuf>> {
+    if !wasm.is_empty() {
+        return Ok(wasm);
+    }
+    let mut files = vec![];
+    for dir in [scripts_dir.join("../artifacts"), scripts_dir.to_path_buf()] {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "wasm")
+            {
+                files.push(path);
+            }
+        }
+    }
+    if files.is_empty() {
+        bail!("no wasm files found");
+    }
+    Ok(files)
+}
+
+fn upload(profile: &mut ChainProfile, mode: Mode, wasm_files: Vec<PathBuf>) -> Result<()> {
+    for wasm in wasm_files {
+        let contract = wasm
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| anyhow!("invalid wasm file {}", wasm.display()))?
+            .to_string();
+        eprintln!("============ Uploading {contract} to {}", profile.name);
+        let cli = ChainCli::new(profile, mode);
+        let Some(tx_hash) = cli.tx(&WasmTx::Store { wasm })? else {
+            continue;
+        };
+        let tx = cli.wait_for_tx(&tx_hash)?;
+        let code_id = find_attribute(&tx, "store_code", "code_id")
+            .or_else(|| find_attribute(&tx, "cosmwasm.wasm.v1.EventCodeStored", "code_id"))
+            .ok_or_else(|| anyhow!("no code id in tx {tx_hash}"))?;
+        let key = profile.code_id_key(&contract)?;
+        eprintln!("============ Updating {key} to {code_id}");
+        profile.set(&key, &code_id)?;
+    }
+    Ok(())
+}
+
+fn mint(profile: &ChainProfile, mode: Mode) -> Result<()> {
+    let cli = ChainCli::new(profile, mode);
+    let tx = WasmTx::execute(profile.get("ADDR_ARKITE_PASSPORT")?, &msgs::mint_msg())?;
+    let Some(tx_hash) = cli.tx(&tx)? else {
+        return Ok(());
+    };
+    let tx = cli.wait_for_tx(&tx_hash)?;
+    let token_id = find_attribute(&tx, "wasm", "token_id")
+        .ok_or_else(|| anyhow!("no token id in tx {tx_hash}"))?;
+    eprintln!("Minted NFT #{token_id}");
+    let nft = cli.query_smart(
+        profile.get("ADDR_CW721")?,
+        &msgs::all_nft_info_query(&token_id),
+    )?;
+    println!("{}", serde_json::to_string_pretty(&nft)?);
+    Ok(())
+}
+
+/// Prints NFT on source chain, its voucher on target chain, and latest POAP on target chain.
+fn report_transfer(
+    source: &ChainProfile,
+    target: &ChainProfile,
+    mode: Mode,
+    cw721: &str,
+    token_id: &str,
+) -> Result<()> {
+    let nft_info = msgs::all_nft_info_query(token_id);
+    // NFT is burned on back transfers
+    let source_nft = ChainCli::new(source, mode)
+        .query_smart(cw721, &nft_info)
+        .unwrap_or_default();
+    eprintln!("============ {}: {cw721}", source.name);
+    println!("{}", serde_json::to_string_pretty(&source_nft)?);
+
+    let target_cli = ChainCli::new(target, mode);
+    let target_cw721 = if source_nft.is_null() {
+        target.get("ADDR_CW721")?.to_string()
+    } else {
+        let class_id = format!(
+            "wasm.{}/{}/{cw721}",
+            target.get("ADDR_ICS721")?,
+            target.get("CHANNEL_ID")?
+        );
+        let nft_contract = target_cli.query_smart(
+            target.get("ADDR_ICS721")?,
+            &msgs::nft_contract_query(class_id),
+        )?;
+        nft_contract
+            .as_str()
+            .ok_or_else(|| anyhow!("no voucher collection on {}", target.name))?
+            .to_string()
+    };
+    let target_nft = target_cli.query_smart(&target_cw721, &nft_info)?;
+    eprintln!("============ {}: {target_cw721}", target.name);
+    println!("{}", serde_json::to_string_pretty(&target_nft)?);
+
+    let num_tokens = target_cli.query_smart(target.get("ADDR_POAP")?, &msgs::num_tokens_query())?;
+    if let Some(count) = num_tokens["count"].as_u64().filter(|count| *count > 0) {
+        eprintln!("- POAP NFT #{}", count - 1);
+    }
+    Ok(())
+}
+
+/// Clears pending packets on channel, using hermes config in relayer folder.
+fn relay_packets(profile: &ChainProfile, channel_id: &str) -> Result<()> {
+    let port = format!("wasm.{}", profile.get("ADDR_ICS721")?);
+    let status = Command::new("hermes")
+        .args([
+            "--config",
+            "./relayer/hermes/config.toml",
+            "clear",
+            "packets",
+        ])
+        .args(["--chain", profile.get("CHAIN_ID")?])
+        .args(["--channel", channel_id, "--port", &port])
+        .status()?;
+    if !status.success() {
+        bail!("hermes failed relaying packets on {channel_id}");
+    }
+    Ok(())
+}
@@ -0,0 +1,116 @@
+//! Deployment manifest, describing desired state of passport deployments on all chains (e.g. `scripts/deployment.toml`).
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// chain name, as used by chain profile (e.g. `osmosis` for `osmosis.env`)
+    pub chains: BTreeMap<String, ChainManifest>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ChainManifest {
+    /// ics721 channel to counterparty chain
+    pub channel_id: String,
+    /// chain name of counterparty, its passport is set as counterparty contract
+    pub counterparty: Option<String>,
+    pub code_ids: CodeIds,
+    pub passport: PassportManifest,
+    pub proxies: ProxiesManifest,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CodeIds {
+    pub arkite_passport: u64,
+    pub cw721: u64,
+    pub ics721: u64,
+    pub incoming_proxy: u64,
+    pub outgoing_proxy: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PassportManifest {
+    pub default_token_uri: String,
+    pub escrowed_token_uri: String,
+    pub transferred_token_uri: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ProxiesManifest {
+    /// whitelisted channels of incoming proxy, defaults to channel of chain
+    #[serde(default)]
+    pub incoming_channels: Vec<String>,
+    /// max NFTs transferred per block by outgoing proxy
+    pub outgoing_rate_limit: u64,
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("failed reading manifest {}", path.display()))?;
+        let manifest = Self::parse(&content)
+            .with_context(|| format!("invalid manifest {}", path.display()))?;
+        Ok(manifest)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let manifest: Manifest = toml::from_str(content)?;
+        for (name, chain) in &manifest.chains {
+            if let Some(counterparty) = &chain.counterparty {
+                if counterparty == name {
+                    bail!("{name} can't be its own counterparty");
+                }
+                if !manifest.chains.contains_key(counterparty) {
+                    bail!("counterparty {counterparty} of {name} is not defined");
+                }
+            }
+        }
+        Ok(manifest)
+    }
+}
+
+impl ChainManifest {
+    pub fn incoming_channels(&self) -> Vec<String> {
+        if self.proxies.incoming_channels.is_empty() {
+            return vec![self.channel_id.clone()];
+        }
+        self.proxies.incoming_channels.clone()
+    }
+
+    /// Chain profile variables, which are used for building messages.
+    pub fn profile_vars(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("CHANNEL_ID", self.channel_id.clone()),
+            (
+                "CODE_ID_ARKITE_PASSPORT",
+                self.code_ids.arkite_passport.to_string(),
+            ),
+            ("CODE_ID_CW721", self.code_ids.cw721.to_string()),
+            ("CODE_ID_ICS721", self.code_ids.ics721.to_string()),
+            (
+                "CODE_ID_INCOMING_PROXY",
+                self.code_ids.incoming_proxy.to_string(),
+            ),
+            (
+                "CODE_ID_OUTGOING_PROXY",
+                self.code_ids.outgoing_proxy.to_string(),
+            ),
+            ("DEFAULT_TOKEN_URI", self.passport.default_token_uri.clone()),
+            (
+                "ESCROWED_TOKEN_URI",
+                self.passport.escrowed_token_uri.clone(),
+            ),
+            (
+                "TRANSFERRED_TOKEN_URI",
+                self.passport.transferred_token_uri.clone(),
+            ),
+        ]
+    }
+}
@@ -0,0 +1,209 @@
+//! Messages built from contract types, using values of a chain profile.
+use anyhow::Result;
+use cosmwasm_std::{to_json_binary, Empty, IbcTimeout, Timestamp};
+use cw721_base::{
+    msg::{
+        ExecuteMsg as Cw721ExecuteMsg, InstantiateMsg as Cw721InstantiateMsg,
+        QueryMsg as Cw721QueryMsg,
+    },
+    DefaultOptionalCollectionExtension, DefaultOptionalCollectionExtensionMsg,
+    DefaultOptionalNftExtension, DefaultOptionalNftExtensionMsg,
+};
+use cw_cii::{Admin, ContractInstantiateInfo};
+use cw_ics721_arkite_passport::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg};
+use ics721::msg::{
+    InstantiateMsg as Ics721InstantiateMsg, MigrateMsg as Ics721MigrateMsg,
+    QueryMsg as Ics721QueryMsg,
+};
+use ics721_types::ibc_types::IbcOutgoingMsg;
+
+use crate::profile::ChainProfile;
+
+pub type Cw721Execute =
+    Cw721ExecuteMsg<DefaultOptionalNftExtensionMsg, DefaultOptionalCollectionExtensionMsg, Empty>;
+pub type Cw721Query =
+    Cw721QueryMsg<DefaultOptionalNftExtension, DefaultOptionalCollectionExtension, Empty>;
+
+pub const PASSPORT_LABEL: &str = "Arkite InterChain Passport";
+pub const INCOMING_PROXY_LABEL: &str = "ICS721 Incoming Proxy (powered by Ark)";
+pub const OUTGOING_PROXY_LABEL: &str = "ICS721 Outgoing Proxy (powered by Ark)";
+
+fn cw721_instantiate_info(
+    profile: &ChainProfile,
+    name: &str,
+    symbol: &str,
+) -> Result<ContractInstantiateInfo> {
+    Ok(ContractInstantiateInfo {
+        code_id: profile.get_u64("CODE_ID_CW721")?,
+        admin: Some(Admin::Address {
+            addr: profile.get("WALLET_ARKITE_PASSPORT")?.to_string(),
+        }),
+        label: name.to_string(),
+        msg: to_json_binary(
+            &Cw721InstantiateMsg::<DefaultOptionalCollectionExtensionMsg> {
+                name: name.to_string(),
+                symbol: symbol.to_string(),
+                collection_info_extension: None,
+                minter: None,  // none = arkite passport is minter
+                creator: None, // none = arkite passport is creator
+                withdraw_address: None,
+            },
+        )?,
+    })
+}
+
+/// Arkite passport, instantiating passport and poap collection, and ics721.
+pub fn passport_instantiate_msg(profile: &ChainProfile) -> Result<InstantiateMsg> {
+    let wallet = profile.get("WALLET_ARKITE_PASSPORT")?.to_string();
+    Ok(InstantiateMsg {
+        default_token_uri: profile.get("DEFAULT_TOKEN_URI")?.to_string(),
+        escrowed_token_uri: profile.get("ESCROWED_TOKEN_URI")?.to_string(),
+        transferred_token_uri: profile.get("TRANSFERRED_TOKEN_URI")?.to_string(),
+        cw721_base: cw721_instantiate_info(
+            profile,
+            "Arkite InterChain Passport collection",
+            "passport",
+        )?,
+        cw721_poap: cw721_instantiate_info(profile, "Arkite POAP collection", "poap")?,
+        ics721_base: ContractInstantiateInfo {
+            code_id: profile.get_u64("CODE_ID_ICS721")?,
+            admin: Some(Admin::Address {
+                addr: wallet.clone(),
+            }),
+            label: "Arkite ICS721".to_string(),
+            // cw721_admin needs to be set, this way creator can be transitioned from ics721 to any wallet
+            msg: to_json_binary(&Ics721InstantiateMsg {
+                cw721_base_code_id: profile.get_u64("CODE_ID_CW721")?,
+                incoming_proxy: None,
+                outgoing_proxy: None,
+                pauser: Some(wallet.clone()),
+                cw721_admin: Some(wallet),
+                cw721_creator: None, // passport contract is set as creator on instantiation
+                contract_addr_length: None,
+            })?,
+        },
+    })
+}
+
+/// Incoming proxy, whitelisting given channels for inbound NFTs.
+pub fn incoming_proxy_instantiate_msg(
+    profile: &ChainProfile,
+    channels: Vec<String>,
+) -> Result<cw_ics721_incoming_proxy_base::msg::InstantiateMsg> {
+    Ok(cw_ics721_incoming_proxy_base::msg::InstantiateMsg {
+        origin: Some(profile.get("ADDR_ICS721")?.to_string()),
+        channels: Some(channels),
+    })
+}
+
+/// Outgoing proxy, limiting number of outbound NFTs per block.
+pub fn outgoing_proxy_instantiate_msg(
+    profile: &ChainProfile,
+    rate_limit_per_block: u64,
+) -> Result<cw_ics721_outgoing_proxy_rate_limit::msg::InstantiateMsg> {
+    Ok(cw_ics721_outgoing_proxy_rate_limit::msg::InstantiateMsg {
+        origin: Some(profile.get("ADDR_ICS721")?.to_string()),
+        rate_limit: cw_ics721_outgoing_proxy_rate_limit::Rate::PerBlock(rate_limit_per_block),
+    })
+}
+
+/// Migrates ics721 with proxies, wallet as pauser and cw721 admin, and passport as cw721 creator.
+pub fn ics721_migrate_msg(profile: &ChainProfile) -> Result<Ics721MigrateMsg> {
+    let wallet = profile.get("WALLET_ARKITE_PASSPORT")?.to_string();
+    Ok(Ics721MigrateMsg::WithUpdate {
+        incoming_proxy: Some(profile.get("ADDR_INCOMING_PROXY")?.to_string()),
+        outgoing_proxy: Some(profile.get("ADDR_OUTGOING_PROXY")?.to_string()),
+        cw721_base_code_id: Some(profile.get_u64("CODE_ID_CW721")?),
+        pauser: Some(wallet.clone()),
+        cw721_admin: Some(wallet),
+        cw721_creator: Some(profile.get("ADDR_ARKITE_PASSPORT")?.to_string()),
+        contract_addr_length: None,
+    })
+}
+
+pub fn passport_migrate_msg(profile: &ChainProfile) -> Result<MigrateMsg> {
+    Ok(MigrateMsg::WithUpdate {
+        default_token_uri: Some(profile.get("DEFAULT_TOKEN_URI")?.to_string()),
+        escrowed_token_uri: Some(profile.get("ESCROWED_TOKEN_URI")?.to_string()),
+        transferred_token_uri: Some(profile.get("TRANSFERRED_TOKEN_URI")?.to_string()),
+    })
+}
+
+/// Updates token uris only, other config fields are kept.
+pub fn passport_update_config_msg(profile: &ChainProfile) -> Result<ExecuteMsg> {
+    Ok(ExecuteMsg::UpdateConfig {
+        default_token_uri: Some(profile.get("DEFAULT_TOKEN_URI")?.to_string()),
+        escrowed_token_uri: Some(profile.get("ESCROWED_TOKEN_URI")?.to_string()),
+        transferred_token_uri: Some(profile.get("TRANSFERRED_TOKEN_URI")?.to_string()),
+        poap_policy: None,
+        timeout_policy: None,
+        rate_limit_policy: None,
+    })
+}
+
+/// Migrates a cw721 collection with passport as new creator.
+pub fn cw721_creator_migrate_msg(profile: &ChainProfile) -> Result<cw721_base::msg::MigrateMsg> {
+    Ok(cw721_base::msg::MigrateMsg::WithUpdate {
+        minter: None,
+        creator: Some(profile.get("ADDR_ARKITE_PASSPORT")?.to_string()),
+    })
+}
+
+pub fn mint_msg() -> ExecuteMsg {
+    ExecuteMsg::Mint {}
+}
+
+pub fn counterparty_contract_msg(counterparty: &ChainProfile) -> Result<ExecuteMsg> {
+    Ok(ExecuteMsg::CounterPartyContract {
+        addr: counterparty.get("ADDR_ARKITE_PASSPORT")?.to_string(),
+    })
+}
+
+/// Sends NFT to passport contract, which forwards it to ics721 with callbacks.
+pub fn transfer_msg(
+    profile: &ChainProfile,
+    token_id: &str,
+    receiver: &str,
+    channel_id: &str,
+    timeout: Timestamp,
+) -> Result<Cw721Execute> {
+    let ibc_outgoing_msg = IbcOutgoingMsg {
+        receiver: receiver.to_string(),
+        channel_id: channel_id.to_string(),
+        timeout: IbcTimeout::with_timestamp(timeout),
+        memo: None,
+    };
+    Ok(Cw721Execute::SendNft {
+        contract: profile.get("ADDR_ARKITE_PASSPORT")?.to_string(),
+        token_id: token_id.to_string(),
+        msg: to_json_binary(&ibc_outgoing_msg)?,
+    })
+}
+
+pub fn all_nft_info_query(token_id: &str) -> Cw721Query {
+    Cw721Query::AllNftInfo {
+        token_id: token_id.to_string(),
+        include_expired: None,
+    }
+}
+
+pub fn num_tokens_query() -> Cw721Query {
+    Cw721Query::NumTokens {}
+}
+
+pub fn incoming_channels_query(
+    start_after: Option<String>,
+) -> cw_ics721_incoming_proxy_base::msg::QueryMsg {
+    cw_ics721_incoming_proxy_base::msg::QueryMsg::GetChannels {
+        start_after,
+        limit: None,
+    }
+}
+
+pub fn rate_limit_query() -> cw_ics721_outgoing_proxy_rate_limit::msg::QueryMsg {
+    cw_ics721_outgoing_proxy_rate_limit::msg::QueryMsg::RateLimit {}
+}
+
+pub fn nft_contract_query(class_id: String) -> Ics721QueryMsg {
+    Ics721QueryMsg::NftContract { class_id }
+}
@@ -0,0 +1,238 @@
+//! Offline building, decoding and validation of nested payloads, like the base64 `IbcOutgoingMsg` in `SendNft`
+//! and the `Ics721Memo` with callbacks inside it.
+use anyhow::{bail, Context, Result};
+use cosmwasm_std::{from_json, to_json_binary, Binary};
+use cw721_base::{
+    msg::InstantiateMsg as Cw721InstantiateMsg, DefaultOptionalCollectionExtensionMsg,
+};
+use cw_ics721_arkite_passport::{
+    msg::{CallbackData, ExecuteMsg, InstantiateMsg},
+    state::{CollectionMetadata, MetadataPolicy, RoyaltyInfo},
+};
+use ics721::msg::InstantiateMsg as Ics721InstantiateMsg;
+use ics721_types::{ibc_types::IbcOutgoingMsg, types::Ics721Memo};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{msgs::Cw721Execute, profile::ChainProfile};
+
+/// Memo as created by passport on source chain, with receive callback in case target chain is given.
+/// Uris are taken from chain profile, metadata policy, royalty info and collection metadata as set on passport.
+pub fn memo(
+    profile: &ChainProfile,
+    target: Option<&ChainProfile>,
+    token_id: &str,
+    sender: &str,
+    metadata_policy: MetadataPolicy,
+    royalty_info: Option<RoyaltyInfo>,
+    collection_metadata: Option<CollectionMetadata>,
+) -> Result<Ics721Memo> {
+    let callback_data = CallbackData {
+        token_id: token_id.to_string(),
+        sender: sender.to_string(),
+        default_token_uri: profile.get("DEFAULT_TOKEN_URI")?.to_string(),
+        escrowed_token_uri: profile.get("ESCROWED_TOKEN_URI")?.to_string(),
+        transferred_token_uri: profile.get("TRANSFERRED_TOKEN_URI")?.to_string(),
+        royalty_info,
+        collection_metadata,
+        metadata_policy,
+    };
+    let counterparty_contract = match target {
+        Some(target) => Some(target.get("ADDR_ARKITE_PASSPORT")?.to_string()),
+        None => None,
+    };
+    Ok(callback_data.to_memo(
+        profile.get("ADDR_ARKITE_PASSPORT")?.to_string(),
+        counterparty_contract,
+    )?)
+}
+
+/// Metadata policy by its JSON name, e.g. `pass_through`.
+pub fn parse_metadata_policy(value: &str) -> Result<MetadataPolicy> {
+    from_json(to_json_binary(value)?).with_context(|| {
+        format!("unknown metadata policy {value}, expected passport, pass_through or stamp")
+    })
+}
+
+/// Memo as expected by ics721: base64 encoded JSON.
+pub fn encode_memo(memo: &Ics721Memo) -> Result<String> {
+    Ok(to_json_binary(memo)?.to_base64())
+}
+
+/// Recursively replaces base64 encoded JSON strings with their decoded JSON.
+pub fn decode(value: Value) -> Value {
+    match value {
+        Value::String(string) => match decode_base64_json(&string) {
+            Some(decoded) => decode(decoded),
+            None => Value::String(string),
+        },
+        Value::Array(values) => Value::Array(values.into_iter().map(decode).collect()),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (key, decode(value)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+fn decode_base64_json(string: &str) -> Option<Value> {
+    let bytes = Binary::from_base64(string).ok()?;
+    match serde_json::from_slice(&bytes).ok()? {
+        value @ (Value::Object(_) | Value::Array(_)) => Some(value),
+        _ => None,
+    }
+}
+
+/// Validates message and its nested payloads against contract types. Accepts a tx (e.g. output of
+/// `--generate-only`), a message of a tx, or a base64 encoded payload.
+/// Returns type of each validated payload, prefixed by its path.
+pub fn validate(value: &Value) -> Result<Vec<String>> {
+    let mut validated = vec![];
+    let messages = value["body"]["messages"]
+        .as_array()
+        .or_else(|| value["tx"]["body"]["messages"].as_array());
+    match messages {
+        Some(messages) => {
+            for (index, message) in messages.iter().enumerate() {
+                let path = format!("messages[{index}].msg");
+                validate_msg(&path, &message["msg"], &mut validated)?;
+            }
+        }
+        None => validate_msg("msg", value, &mut validated)?,
+    }
+    Ok(validated)
+}
+
+fn validate_msg(path: &str, value: &Value, validated: &mut Vec<String>) -> Result<()> {
+    // payloads may also be passed base64 encoded
+    if let Some(decoded) = value.as_str().and_then(decode_base64_json) {
+        return validate_msg(path, &decoded, validated);
+    }
+    if let Ok(msg) = serde_json::from_value::<InstantiateMsg>(value.clone()) {
+        validated.push(format!("{path}: passport InstantiateMsg"));
+        for (field, info) in [
+            ("cw721_base", &msg.cw721_base),
+            ("cw721_poap", &msg.cw721_poap),
+        ] {
+            parse::<Cw721InstantiateMsg<DefaultOptionalCollectionExtensionMsg>>(
+                &format!("{path}.{field}.msg"),
+                "cw721 InstantiateMsg",
+                &info.msg,
+                validated,
+            )?;
+        }
+        parse::<Ics721InstantiateMsg>(
+            &format!("{path}.ics721_base.msg"),
+            "ics721 InstantiateMsg",
+            &msg.ics721_base.msg,
+            validated,
+        )?;
+        return Ok(());
+    }
+    if let Ok(msg) = serde_json::from_value::<ExecuteMsg>(value.clone()) {
+        validated.push(format!("{path}: passport ExecuteMsg"));
+        match msg {
+            ExecuteMsg::ReceiveNft(receive_msg) => {
+                validate_ibc_outgoing_msg(
+                    &format!("{path}.receive_nft.msg"),
+                    &receive_msg.msg,
+                    validated,
+                )?;
+            }
+            ExecuteMsg::Ics721AckCallback(callback_msg) => {
+                parse::<CallbackData>(
+                    &format!("{path}.ics721_ack_callback.msg"),
+                    "CallbackData",
+                    &callback_msg.msg,
+                    validated,
+                )?;
+            }
+            ExecuteMsg::Ics721ReceiveCallback(callback_msg) => {
+                parse::<CallbackData>(
+                    &format!("{path}.ics721_receive_callback.msg"),
+                    "CallbackData",
+                    &callback_msg.msg,
+                    validated,
+                )?;
+            }
+            _ => {}
+        }
+        return Ok(());
+    }
+    if let Ok(msg) = serde_json::from_value::<Cw721Execute>(value.clone()) {
+        validated.push(format!("{path}: cw721 ExecuteMsg"));
+        if let Cw721Execute::SendNft { msg, .. } = msg {
+            validate_ibc_outgoing_msg(&format!("{path}.send_nft.msg"), &msg, validated)?;
+        }
+        return Ok(());
+    }
+    if let Ok(msg) = serde_json::from_value::<IbcOutgoingMsg>(value.clone()) {
+        validated.push(format!("{path}: IbcOutgoingMsg"));
+        if let Some(memo) = msg.memo {
+            validate_memo(&format!("{path}.memo"), &memo, validated)?;
+        }
+        return Ok(());
+    }
+    if let Ok(memo) = serde_json::from_value::<Ics721Memo>(value.clone()) {
+        validated.push(format!("{path}: Ics721Memo"));
+        return validate_callbacks(path, &memo, validated);
+    }
+    bail!("{path}: unknown message {value}")
+}
+
+fn validate_ibc_outgoing_msg(path: &str, msg: &Binary, validated: &mut Vec<String>) -> Result<()> {
+    let msg: IbcOutgoingMsg = parse(path, "IbcOutgoingMsg", msg, validated)?;
+    if msg.timeout.block().is_none() && msg.timeout.timestamp().is_none() {
+        bail!("{path}: IbcOutgoingMsg has no timeout");
+    }
+    if let Some(memo) = msg.memo {
+        validate_memo(&format!("{path}.memo"), &memo, validated)?;
+    }
+    Ok(())
+}
+
+fn validate_memo(path: &str, memo: &str, validated: &mut Vec<String>) -> Result<()> {
+    let memo = Binary::from_base64(memo).with_context(|| format!("{path}: memo is not base64"))?;
+    let memo: Ics721Memo = parse(path, "Ics721Memo", &memo, validated)?;
+    validate_callbacks(path, &memo, validated)
+}
+
+fn validate_callbacks(path: &str, memo: &Ics721Memo, validated: &mut Vec<String>) -> Result<()> {
+    let Some(callbacks) = &memo.callbacks else {
+        return Ok(());
+    };
+    for (field, data, addr) in [
+        (
+            "ack_callback_data",
+            &callbacks.ack_callback_data,
+            &callbacks.ack_callback_addr,
+        ),
+        (
+            "receive_callback_data",
+            &callbacks.receive_callback_data,
+            &callbacks.receive_callback_addr,
+        ),
+    ] {
+        let path = format!("{path}.callbacks.{field}");
+        match (data, addr) {
+            (Some(data), Some(_)) => {
+                parse::<CallbackData>(&path, "CallbackData", data, validated)?;
+            }
+            (Some(_), None) => bail!("{path}: callback data without callback address"),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn parse<T: DeserializeOwned>(
+    path: &str,
+    type_name: &str,
+    data: &Binary,
+    validated: &mut Vec<String>,
+) -> Result<T> {
+    let parsed = from_json(data).with_context(|| format!("{path}: invalid {type_name}"))?;
+    validated.push(format!("{path}: {type_name}"));
+    Ok(parsed)
+}
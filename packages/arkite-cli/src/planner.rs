@@ -0,0 +1,371 @@
+//! Turns a deployment manifest into ordered actions, by diffing desired state against deployed state.
+use std::{collections::BTreeMap, fmt};
+
+use anyhow::Result;
+use cw_ics721_arkite_passport::msg::{ContractInfoResponse, QueryMsg};
+use cw_ics721_outgoing_proxy_rate_limit::Rate;
+use ics721::msg::QueryMsg as Ics721QueryMsg;
+use serde_json::Value;
+
+use crate::{
+    manifest::{ChainManifest, Manifest},
+    msgs,
+    profile::ChainProfile,
+    tx::ChainCli,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractState {
+    pub addr: String,
+    pub code_id: u64,
+}
+
+/// Passport settings diffed by planner. Queried by `ContractInfo`, or by legacy queries for passports without it
+/// (e.g. 0.1.0).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PassportConfig {
+    pub default_token_uri: String,
+    pub escrowed_token_uri: String,
+    pub transferred_token_uri: String,
+    pub cw721: Option<String>,
+    pub poap: Option<String>,
+    pub ics721: Option<String>,
+    pub counterparty_contract: Option<String>,
+}
+
+/// Deployed contracts of a chain. Passport is `None` if it is not instantiated yet.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChainState {
+    pub passport: Option<ContractState>,
+    pub passport_config: Option<PassportConfig>,
+    /// passport and poap collection, instantiated by passport
+    pub cw721: Option<ContractState>,
+    pub poap: Option<ContractState>,
+    pub ics721: Option<ContractState>,
+    /// code id used by ics721 for instantiating voucher collections
+    pub ics721_cw721_code_id: Option<u64>,
+    /// proxies used by ics721
+    pub incoming_proxy: Option<ContractState>,
+    pub outgoing_proxy: Option<ContractState>,
+    /// whitelisted channels of incoming proxy
+    pub incoming_channels: Vec<String>,
+    /// max NFTs per block of outgoing proxy, `None` if it is not limited per block
+    pub outgoing_rate_limit: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    /// instantiates passport, including passport and poap collection, and ics721
+    InstantiatePassport,
+    MigratePassport,
+    /// updates token uris of passport, in case code id is unchanged
+    UpdatePassportConfig,
+    /// migrates passport or poap collection to cw721 code id of chain profile
+    MigrateCw721 {
+        cw721: String,
+    },
+    InstantiateIncomingProxy,
+    InstantiateOutgoingProxy,
+    /// migrates ics721 with code id and proxies of chain profile
+    MigrateIcs721,
+    RegisterCounterparty {
+        counterparty: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+    pub chain: String,
+    pub action: Action,
+    /// difference between deployed and desired state
+    pub reason: String,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match &self.action {
+            Action::InstantiatePassport => "instantiate passport".to_string(),
+            Action::MigratePassport => "migrate passport".to_string(),
+            Action::UpdatePassportConfig => "update passport config".to_string(),
+            Action::MigrateCw721 { cw721 } => format!("migrate cw721 {cw721}"),
+            Action::InstantiateIncomingProxy => "instantiate incoming proxy".to_string(),
+            Action::InstantiateOutgoingProxy => "instantiate outgoing proxy".to_string(),
+            Action::MigrateIcs721 => "migrate ics721".to_string(),
+            Action::RegisterCounterparty { counterparty } => {
+                format!("register counterparty passport of {counterparty}")
+            }
+        };
+        write!(f, "{}: {action} ({})", self.chain, self.reason)
+    }
+}
+
+/// Ordered steps for reaching desired state: contracts on all chains are deployed first,
+/// counterparties are registered last, once passports on all chains are known.
+pub fn plan(manifest: &Manifest, states: &BTreeMap<String, ChainState>) -> Vec<Step> {
+    let default_state = ChainState::default();
+    let state_of = |chain: &str| states.get(chain).unwrap_or(&default_state);
+    let mut steps = vec![];
+    for (chain, desired) in &manifest.chains {
+        plan_contracts(chain, desired, state_of(chain), &mut steps);
+    }
+    for (chain, desired) in &manifest.chains {
+        let Some(counterparty) = &desired.counterparty else {
+            continue;
+        };
+        let step = |reason: String| Step {
+            chain: chain.clone(),
+            action: Action::RegisterCounterparty {
+                counterparty: counterparty.clone(),
+            },
+            reason,
+        };
+        let current = state_of(chain)
+            .passport_config
+            .as_ref()
+            .and_then(|config| config.counterparty_contract.clone());
+        match (current, &state_of(counterparty).passport) {
+            (_, None) => steps.push(step(format!("passport on {counterparty} is new"))),
+            (None, Some(_)) => steps.push(step("no counterparty".to_string())),
+            (Some(current), Some(passport)) if current != passport.addr => {
+                steps.push(step(format!("counterparty {current} -> {}", passport.addr)))
+            }
+            _ => {}
+        }
+    }
+    steps
+}
+
+fn plan_contracts(chain: &str, desired: &ChainManifest, state: &ChainState, steps: &mut Vec<Step>) {
+    let mut push = |action: Action, reason: String| {
+        steps.push(Step {
+            chain: chain.to_string(),
+            action,
+            reason,
+        })
+    };
+    let code_ids = &desired.code_ids;
+
+    // passport
+    match (&state.passport, &state.passport_config) {
+        (Some(passport), Some(config)) => {
+            let mut diffs = vec![];
+            for (field, current, desired) in [
+                (
+                    "default token uri",
+                    &config.default_token_uri,
+                    &desired.passport.default_token_uri,
+                ),
+                (
+                    "escrowed token uri",
+                    &config.escrowed_token_uri,
+                    &desired.passport.escrowed_token_uri,
+                ),
+                (
+                    "transferred token uri",
+                    &config.transferred_token_uri,
+                    &desired.passport.transferred_token_uri,
+                ),
+            ] {
+                if current != desired {
+                    diffs.push(diff(field, current, desired));
+                }
+            }
+            // migrate msg also sets token uris
+            if passport.code_id != code_ids.arkite_passport {
+                diffs.insert(
+                    0,
+                    diff("code id", passport.code_id, code_ids.arkite_passport),
+                );
+                push(Action::MigratePassport, diffs.join(", "));
+            } else if !diffs.is_empty() {
+                push(Action::UpdatePassportConfig, diffs.join(", "));
+            }
+        }
+        _ => push(Action::InstantiatePassport, "not deployed".to_string()),
+    }
+
+    // passport and poap collection, instantiated by passport
+    for (name, cw721) in [("passport", &state.cw721), ("poap", &state.poap)] {
+        match cw721 {
+            Some(cw721) if cw721.code_id != code_ids.cw721 => push(
+                Action::MigrateCw721 {
+                    cw721: cw721.addr.clone(),
+                },
+                diff(&format!("{name} code id"), cw721.code_id, code_ids.cw721),
+            ),
+            _ => {}
+        }
+    }
+
+    // proxies, a new proxy with desired config is set by migrating ics721
+    let mut current_channels = state.incoming_channels.clone();
+    current_channels.sort();
+    let mut desired_channels = desired.incoming_channels();
+    desired_channels.sort();
+    let current_rate_limit = state
+        .outgoing_rate_limit
+        .map_or("none".to_string(), |rate_limit| rate_limit.to_string());
+    let desired_rate_limit = desired.proxies.outgoing_rate_limit.to_string();
+    let mut ics721_diffs = vec![];
+    for (action, proxy, code_id, name, config) in [
+        (
+            Action::InstantiateIncomingProxy,
+            &state.incoming_proxy,
+            code_ids.incoming_proxy,
+            "incoming proxy",
+            (
+                "incoming channels",
+                current_channels.join(","),
+                desired_channels.join(","),
+            ),
+        ),
+        (
+            Action::InstantiateOutgoingProxy,
+            &state.outgoing_proxy,
+            code_ids.outgoing_proxy,
+            "outgoing proxy",
+            (
+                "outgoing rate limit",
+                current_rate_limit,
+                desired_rate_limit,
+            ),
+        ),
+    ] {
+        let (field, current_config, desired_config) = config;
+        let reason = match proxy {
+            None => format!("no {name}"),
+            Some(proxy) if proxy.code_id != code_id => diff("code id", proxy.code_id, code_id),
+            Some(_) if current_config != desired_config => {
+                diff(field, current_config, desired_config)
+            }
+            Some(_) => continue,
+        };
+        ics721_diffs.push(format!("new {name}"));
+        push(action, reason);
+    }
+
+    // ics721, instantiated by passport. Migration also sets code id of voucher collections.
+    match (&state.ics721, state.ics721_cw721_code_id) {
+        (Some(ics721), cw721_code_id) => {
+            if cw721_code_id != Some(code_ids.cw721) {
+                let current =
+                    cw721_code_id.map_or("none".to_string(), |code_id| code_id.to_string());
+                ics721_diffs.insert(0, diff("cw721 code id", current, code_ids.cw721));
+            }
+            if ics721.code_id != code_ids.ics721 {
+                ics721_diffs.insert(0, diff("code id", ics721.code_id, code_ids.ics721));
+            }
+        }
+        (None, _) => ics721_diffs.insert(0, "ics721 is new".to_string()),
+    }
+    if !ics721_diffs.is_empty() {
+        push(Action::MigrateIcs721, ics721_diffs.join(", "));
+    }
+}
+
+fn diff(field: &str, current: impl fmt::Display, desired: impl fmt::Display) -> String {
+    format!("{field} {current} -> {desired}")
+}
+
+/// Queries contracts of passport in chain profile, including config of ics721 and its proxies.
+/// Passport is considered not deployed, if its address is empty.
+pub fn query_state(profile: &ChainProfile, cli: &ChainCli) -> Result<ChainState> {
+    let addr = profile.get("ADDR_ARKITE_PASSPORT").unwrap_or_default();
+    if addr.is_empty() {
+        return Ok(ChainState::default());
+    }
+    let config = query_passport_config(|msg| cli.query_smart(addr, msg))?;
+    let contract_state = |addr: Option<&str>| -> Result<Option<ContractState>> {
+        match addr {
+            Some(addr) => Ok(Some(ContractState {
+                addr: addr.to_string(),
+                code_id: cli.query_code_id(addr)?,
+            })),
+            None => Ok(None),
+        }
+    };
+    let (mut incoming_proxy, mut outgoing_proxy, mut ics721_cw721_code_id) = (None, None, None);
+    if let Some(ics721) = &config.ics721 {
+        incoming_proxy = serde_json::from_value::<Option<String>>(
+            cli.query_smart(ics721, &Ics721QueryMsg::IncomingProxy {})?,
+        )?;
+        outgoing_proxy = serde_json::from_value::<Option<String>>(
+            cli.query_smart(ics721, &Ics721QueryMsg::OutgoingProxy {})?,
+        )?;
+        ics721_cw721_code_id = Some(serde_json::from_value(
+            cli.query_smart(ics721, &Ics721QueryMsg::Cw721CodeId {})?,
+        )?);
+    }
+    let mut incoming_channels = vec![];
+    if let Some(incoming_proxy) = &incoming_proxy {
+        // channels are paginated
+        loop {
+            let channels: Vec<String> = serde_json::from_value(cli.query_smart(
+                incoming_proxy,
+                &msgs::incoming_channels_query(incoming_channels.last().cloned()),
+            )?)?;
+            if channels.is_empty() {
+                break;
+            }
+            incoming_channels.extend(channels);
+        }
+    }
+    let outgoing_rate_limit = match &outgoing_proxy {
+        Some(outgoing_proxy) => {
+            let rate: Rate = serde_json::from_value(
+                cli.query_smart(outgoing_proxy, &msgs::rate_limit_query())?,
+            )?;
+            match rate {
+                Rate::PerBlock(rate_limit) => Some(rate_limit),
+                Rate::Blocks(_) => None,
+            }
+        }
+        None => None,
+    };
+    Ok(ChainState {
+        passport: contract_state(Some(addr))?,
+        cw721: contract_state(config.cw721.as_deref())?,
+        poap: contract_state(config.poap.as_deref())?,
+        ics721: contract_state(config.ics721.as_deref())?,
+        ics721_cw721_code_id,
+        incoming_proxy: contract_state(incoming_proxy.as_deref())?,
+        outgoing_proxy: contract_state(outgoing_proxy.as_deref())?,
+        incoming_channels,
+        outgoing_rate_limit,
+        passport_config: Some(config),
+    })
+}
+
+/// Config of passport by `ContractInfo`, falls back to legacy queries of passports without it (e.g. 0.1.0).
+pub fn query_passport_config(query: impl Fn(&QueryMsg) -> Result<Value>) -> Result<PassportConfig> {
+    if let Ok(info) = query(&QueryMsg::ContractInfo {}) {
+        let info: ContractInfoResponse = serde_json::from_value(info)?;
+        let linked_contracts = info.linked_contracts;
+        return Ok(PassportConfig {
+            default_token_uri: info.config.default_token_uri,
+            escrowed_token_uri: info.config.escrowed_token_uri,
+            transferred_token_uri: info.config.transferred_token_uri,
+            cw721: linked_contracts.cw721.map(String::from),
+            poap: linked_contracts.poap.map(String::from),
+            ics721: linked_contracts.ics721.map(String::from),
+            counterparty_contract: info.config.counterparty_contract,
+        });
+    }
+    let token_uri = |msg: QueryMsg| -> Result<String> { Ok(serde_json::from_value(query(&msg)?)?) };
+    // legacy queries fail for unset addresses, newer ones return null
+    let optional = |msg: QueryMsg| -> Option<String> {
+        query(&msg)
+            .ok()
+            .and_then(|value| serde_json::from_value(value).ok())
+            .flatten()
+    };
+    Ok(PassportConfig {
+        default_token_uri: token_uri(QueryMsg::DefaultTokenUri {})?,
+        escrowed_token_uri: token_uri(QueryMsg::EscrowedTokenUri {})?,
+        transferred_token_uri: token_uri(QueryMsg::TransferredTokenUri {})?,
+        cw721: optional(QueryMsg::CW721 {}),
+        poap: optional(QueryMsg::Poap {}),
+        ics721: optional(QueryMsg::ICS721 {}),
+        counterparty_contract: optional(QueryMsg::CounterPartyContract {}),
+    })
+}
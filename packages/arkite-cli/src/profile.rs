@@ -0,0 +1,148 @@
+//! Chain profiles, as defined by `<chain>.env` files in scripts folder.
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+pub struct ChainProfile {
+    pub name: String,
+    path: PathBuf,
+    vars: BTreeMap<String, String>,
+}
+
+impl ChainProfile {
+    pub fn load(scripts_dir: &Path, name: &str) -> Result<Self> {
+        let path = scripts_dir.join(format!("{name}.env"));
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("failed reading chain profile {}", path.display()))?;
+        Ok(Self {
+            name: name.to_string(),
+            path,
+            vars: parse_env(&content),
+        })
+    }
+
+    pub fn get(&self, key: &str) -> Result<&str> {
+        self.vars
+            .get(key)
+            .map(String::as_str)
+            .ok_or_else(|| anyhow!("{key} is not defined in {}", self.path.display()))
+    }
+
+    pub fn get_u64(&self, key: &str) -> Result<u64> {
+        self.get(key)?
+            .parse()
+            .with_context(|| format!("{key} in {} is not a number", self.path.display()))
+    }
+
+    /// Updates variable, both in memory and in env file.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let content = fs::read_to_string(&self.path)?;
+        let content = set_env_var(&content, key, value)
+            .ok_or_else(|| anyhow!("{key} is not defined in {}", self.path.display()))?;
+        fs::write(&self.path, content)?;
+        self.vars.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    /// Updates variable in memory only, e.g. for printing commands without changing env file.
+    pub fn override_var(&mut self, key: &str, value: &str) {
+        self.vars.insert(key.to_string(), value.to_string());
+    }
+
+    /// Variable holding code id of given contract, marked by a comment, e.g. `export CODE_ID_CW721="1" # cw721_base`.
+    pub fn code_id_key(&self, contract: &str) -> Result<String> {
+        let content = fs::read_to_string(&self.path)?;
+        content
+            .lines()
+            .filter_map(parse_line)
+            .find(|line| line.key.starts_with("CODE_ID_") && line.comment == Some(contract))
+            .map(|line| line.key.to_string())
+            .ok_or_else(|| anyhow!("no code id for {contract} in {}", self.path.display()))
+    }
+}
+
+struct EnvLine<'a> {
+    key: &'a str,
+    value: &'a str,
+    comment: Option<&'a str>,
+}
+
+/// Parses `export KEY="value" # comment` and `export KEY=value`, any other line is ignored.
+fn parse_line(line: &str) -> Option<EnvLine> {
+    let rest = line.trim().strip_prefix("export ")?;
+    let (key, rest) = rest.split_once('=')?;
+    let (value, rest) = match rest.strip_prefix('"') {
+        Some(quoted) => {
+            let end = quoted.find('"')?;
+            (&quoted[..end], &quoted[end + 1..])
+        }
+        None => {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            rest.split_at(end)
+        }
+    };
+    let comment = rest.trim().strip_prefix('#').map(str::trim);
+    Some(EnvLine {
+        key: key.trim(),
+        value,
+        comment,
+    })
+}
+
+pub(crate) fn parse_env(content: &str) -> BTreeMap<String, String> {
+    let mut vars = BTreeMap::new();
+    for line in content.lines().filter_map(parse_line) {
+        let value = expand(line.value, &vars);
+        vars.insert(line.key.to_string(), value);
+    }
+    vars
+}
+
+/// Replaces `$NAME` references with previously defined variables, e.g. `"$CLI_GAS_PRICE$CLI_DENOM"`.
+fn expand(value: &str, vars: &BTreeMap<String, String>) -> String {
+    let mut expanded = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            expanded.push(c);
+            continue;
+        }
+        let mut name = String::new();
+        while let Some(next) = chars.next_if(|next| next.is_ascii_alphanumeric() || *next == '_') {
+            name.push(next);
+        }
+        if let Some(var) = vars.get(&name) {
+            expanded.push_str(var);
+        }
+    }
+    expanded
+}
+
+/// Sets value of an existing variable and keeps its comment. Returns `None` if variable is not defined.
+pub(crate) fn set_env_var(content: &str, key: &str, value: &str) -> Option<String> {
+    let mut found = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| match parse_line(line) {
+            Some(env_line) if env_line.key == key => {
+                found = true;
+                match env_line.comment {
+                    Some(comment) => format!("export {key}=\"{value}\" # {comment}"),
+                    None => format!("export {key}=\"{value}\""),
+                }
+            }
+            _ => line.to_string(),
+        })
+        .collect();
+    if !found {
+        return None;
+    }
+    if content.ends_with('\n') {
+        lines.push(String::new());
+    }
+    Some(lines.join("\n"))
+}
@@ -0,0 +1,764 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use anyhow::anyhow;
+use cosmwasm_std::{
+    from_json, testing::mock_env, to_json_binary, Addr, Binary, Decimal, Timestamp,
+};
+use cw_ics721_arkite_passport::{
+    execute::create_memo,
+    msg::{CallbackData, QueryMsg},
+    state::{CollectionMetadata, HomeMetadata, MetadataPolicy, RoyaltyInfo},
+};
+use ics721_types::ibc_types::IbcOutgoingMsg;
+use serde_json::json;
+
+use crate::{
+    deploy,
+    manifest::Manifest,
+    msgs::{self, Cw721Execute},
+    payload,
+    planner::{self, Action, ChainState, ContractState, PassportConfig, Step},
+    profile::{parse_env, set_env_var, ChainProfile},
+    tx::{find_attribute, shell_command, Mode},
+};
+
+const ENV: &str = r#"# CLI
+export CLI="osmosisd"
+export CLI_MAX_GAS=1000000
+export CLI_GAS_PRICE="0.025"
+export CLI_DENOM="uosmo"
+export CLI_GAS_PRICES="$CLI_GAS_PRICE$CLI_DENOM"
+
+export CODE_ID_CW721="8998" # cw721_base
+export ADDR_ARKITE_PASSPORT="osmo1passport"
+
+export DEFAULT_TOKEN_URI="ipfs://passport/default"
+export ESCROWED_TOKEN_URI="ipfs://passport/escrowed"
+export TRANSFERRED_TOKEN_URI="ipfs://passport/transferred"
+"#;
+
+/// Writes `test.env` chain profile into a temp folder, unique per test.
+fn profile_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("arkite-cli-{test}-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("test.env"), ENV).unwrap();
+    dir
+}
+
+fn load_profile(test: &str) -> ChainProfile {
+    ChainProfile::load(&profile_dir(test), "test").unwrap()
+}
+
+#[test]
+fn test_parse_env() {
+    let vars = parse_env(ENV);
+    assert_eq!(vars.len(), 10);
+    assert_eq!(vars["CLI"], "osmosisd");
+    assert_eq!(vars["CLI_MAX_GAS"], "1000000");
+    // variables are expanded
+    assert_eq!(vars["CLI_GAS_PRICES"], "0.025uosmo");
+    // comments are ignored
+    assert_eq!(vars["CODE_ID_CW721"], "8998");
+}
+
+#[test]
+fn test_set_env_var() {
+    // case 1: comment is kept
+    let content = set_env_var(ENV, "CODE_ID_CW721", "9000").unwrap();
+    assert!(content.contains("export CODE_ID_CW721=\"9000\" # cw721_base\n"));
+    assert_eq!(parse_env(&content)["CODE_ID_CW721"], "9000");
+    // other lines are unchanged
+    assert_eq!(content.replace("9000", "8998"), ENV);
+
+    // case 2: unquoted value
+    let content = set_env_var(ENV, "CLI_MAX_GAS", "2000000").unwrap();
+    assert!(content.contains("export CLI_MAX_GAS=\"2000000\"\n"));
+
+    // case 3: undefined variable
+    assert_eq!(set_env_var(ENV, "ADDR_CW721", "osmo1cw721"), None);
+}
+
+#[test]
+fn test_code_id_key() {
+    let dir = profile_dir("code_id_key");
+    let mut profile = ChainProfile::load(&dir, "test").unwrap();
+
+    assert_eq!(profile.code_id_key("cw721_base").unwrap(), "CODE_ID_CW721");
+    assert!(profile.code_id_key("ics721_base").is_err());
+
+    // update is written to env file
+    profile.set("CODE_ID_CW721", "9000").unwrap();
+    assert_eq!(profile.get_u64("CODE_ID_CW721").unwrap(), 9000);
+    let reloaded = ChainProfile::load(&dir, "test").unwrap();
+    assert_eq!(reloaded.get("CODE_ID_CW721").unwrap(), "9000");
+}
+
+#[test]
+fn test_shell_command() {
+    let args = [
+        "tx",
+        "wasm",
+        "execute",
+        "osmo1passport",
+        r#"{"mint":{}}"#,
+        "--label",
+        "Arkite's passport",
+    ]
+    .map(String::from);
+    assert_eq!(
+        shell_command("osmosisd", &args),
+        r#"osmosisd tx wasm execute osmo1passport '{"mint":{}}' --label 'Arkite'\''s passport'"#
+    );
+}
+
+#[test]
+fn test_transfer_msg() {
+    let profile = load_profile("transfer_msg");
+
+    let msg = msgs::transfer_msg(
+        &profile,
+        "1",
+        "stars1receiver",
+        "channel-0",
+        Timestamp::from_seconds(1000),
+    )
+    .unwrap();
+    let Cw721Execute::SendNft {
+        contract,
+        token_id,
+        msg,
+    } = msg
+    else {
+        panic!("expected send nft msg");
+    };
+    assert_eq!(contract, "osmo1passport");
+    assert_eq!(token_id, "1");
+    let ibc_outgoing_msg: IbcOutgoingMsg = from_json(msg).unwrap();
+    assert_eq!(ibc_outgoing_msg.receiver, "stars1receiver");
+    assert_eq!(ibc_outgoing_msg.channel_id, "channel-0");
+    assert_eq!(
+        ibc_outgoing_msg.timeout.timestamp(),
+        Some(Timestamp::from_seconds(1000))
+    );
+}
+
+#[test]
+fn test_find_attribute() {
+    // case 1: events of tx response
+    let tx = json!({
+        "events": [
+            {"type": "message", "attributes": [{"key": "code_id", "value": "1"}]},
+            {"type": "store_code", "attributes": [{"key": "code_id", "value": "42"}]},
+        ]
+    });
+    assert_eq!(
+        find_attribute(&tx, "store_code", "code_id"),
+        Some("42".to_string())
+    );
+    assert_eq!(find_attribute(&tx, "store_code", "checksum"), None);
+
+    // case 2: legacy logs, with quoted values
+    let tx = json!({
+        "logs": [{"events": [
+            {
+                "type": "cosmwasm.wasm.v1.EventCodeStored",
+                "attributes": [{"key": "code_id", "value": "\"43\""}]
+            },
+        ]}]
+    });
+    assert_eq!(
+        find_attribute(&tx, "cosmwasm.wasm.v1.EventCodeStored", "code_id"),
+        Some("43".to_string())
+    );
+}
+
+#[test]
+fn test_build_and_validate_send_nft() {
+    let profile = load_profile("send_nft");
+    let msg = msgs::transfer_msg(
+        &profile,
+        "1",
+        "stars1receiver",
+        "channel-0",
+        Timestamp::from_seconds(1000),
+    )
+    .unwrap();
+    let value = serde_json::to_value(msg).unwrap();
+    assert_eq!(
+        payload::validate(&value).unwrap(),
+        vec!["msg: cw721 ExecuteMsg", "msg.send_nft.msg: IbcOutgoingMsg",]
+    );
+
+    // nested ibc outgoing msg is decoded
+    let decoded = payload::decode(value);
+    assert_eq!(decoded["send_nft"]["msg"]["receiver"], "stars1receiver");
+    assert_eq!(
+        decoded["send_nft"]["msg"]["timeout"]["timestamp"],
+        "1000000000000"
+    );
+}
+
+#[test]
+fn test_build_and_validate_memo() {
+    let profile = load_profile("memo");
+    let target = load_profile("memo_target");
+
+    // case 1: ack callback only
+    let memo = payload::memo(
+        &profile,
+        None,
+        "1",
+        "osmo1sender",
+        MetadataPolicy::Passport,
+        None,
+        None,
+    )
+    .unwrap();
+    let callbacks = memo.callbacks.clone().unwrap();
+    assert_eq!(
+        callbacks.ack_callback_addr,
+        Some("osmo1passport".to_string())
+    );
+    assert_eq!(callbacks.receive_callback_addr, None);
+    assert_eq!(callbacks.receive_callback_data, None);
+    let callback_data: CallbackData = from_json(callbacks.ack_callback_data.unwrap()).unwrap();
+    assert_eq!(callback_data.token_id, "1");
+    assert_eq!(callback_data.sender, "osmo1sender");
+    assert_eq!(callback_data.escrowed_token_uri, "ipfs://passport/escrowed");
+
+    // case 2: with receive callback to counterparty
+    let memo = payload::memo(
+        &profile,
+        Some(&target),
+        "1",
+        "osmo1sender",
+        MetadataPolicy::Passport,
+        None,
+        None,
+    )
+    .unwrap();
+    let encoded = payload::encode_memo(&memo).unwrap();
+    assert_eq!(
+        payload::validate(&encoded.clone().into()).unwrap(),
+        vec![
+            "msg: Ics721Memo",
+            "msg.callbacks.ack_callback_data: CallbackData",
+            "msg.callbacks.receive_callback_data: CallbackData",
+        ]
+    );
+    let decoded = payload::decode(encoded.into());
+    assert_eq!(
+        decoded["callbacks"]["receive_callback_data"]["transferred_token_uri"],
+        "ipfs://passport/transferred"
+    );
+}
+
+#[test]
+fn test_memo_matches_passport_memo() {
+    let profile = load_profile("memo_round_trip");
+    let target = load_profile("memo_round_trip_target");
+    let royalty_info = RoyaltyInfo {
+        payment_address: "osmo1creator".to_string(),
+        share: Decimal::percent(5),
+    };
+    let collection_metadata = CollectionMetadata {
+        description: Some("Arkite InterChain Passport collection".to_string()),
+        image: Some("ipfs://passport/collection".to_string()),
+        external_link: None,
+    };
+    let mut env = mock_env();
+    env.contract.address = Addr::unchecked("osmo1passport");
+    for (metadata_policy, royalty_info, collection_metadata) in [
+        (MetadataPolicy::Passport, None, None),
+        (
+            MetadataPolicy::Passport,
+            Some(royalty_info.clone()),
+            Some(collection_metadata.clone()),
+        ),
+        (MetadataPolicy::PassThrough, None, Some(collection_metadata)),
+        (MetadataPolicy::Stamp, Some(royalty_info), None),
+    ] {
+        let memo = payload::memo(
+            &profile,
+            Some(&target),
+            "1",
+            "osmo1sender",
+            metadata_policy.clone(),
+            royalty_info.clone(),
+            collection_metadata.clone(),
+        )
+        .unwrap();
+        let passport_memo = create_memo(
+            env.clone(),
+            "osmo1sender".to_string(),
+            "1".to_string(),
+            metadata_policy,
+            HomeMetadata {
+                default_token_uri: "ipfs://passport/default".to_string(),
+                escrowed_token_uri: "ipfs://passport/escrowed".to_string(),
+                transferred_token_uri: "ipfs://passport/transferred".to_string(),
+                royalty_info,
+                collection_metadata,
+            },
+            Some("osmo1passport".to_string()),
+        )
+        .unwrap();
+        assert_eq!(
+            payload::encode_memo(&memo).unwrap(),
+            payload::encode_memo(&passport_memo).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_parse_metadata_policy() {
+    assert_eq!(
+        payload::parse_metadata_policy("pass_through").unwrap(),
+        MetadataPolicy::PassThrough
+    );
+    assert_eq!(
+        payload::parse_metadata_policy("stamp").unwrap(),
+        MetadataPolicy::Stamp
+    );
+    let err = payload::parse_metadata_policy("swap").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unknown metadata policy swap, expected passport, pass_through or stamp"
+    );
+}
+
+#[test]
+fn test_validate_nested_payloads() {
+    let profile = load_profile("validate");
+    let memo = payload::memo(
+        &profile,
+        None,
+        "1",
+        "osmo1sender",
+        MetadataPolicy::Passport,
+        None,
+        None,
+    )
+    .unwrap();
+    let ibc_outgoing_msg = json!({
+        "receiver": "stars1receiver",
+        "channel_id": "channel-0",
+        "timeout": {"timestamp": "1000000000000"},
+        "memo": payload::encode_memo(&memo).unwrap(),
+    });
+    let send_nft = |msg: Binary| {
+        json!({"send_nft": {
+            "contract": "osmo1passport",
+            "token_id": "1",
+            "msg": msg,
+        }})
+    };
+
+    // case 1: memo inside ibc outgoing msg is validated
+    let value = send_nft(to_json_binary(&ibc_outgoing_msg).unwrap());
+    assert_eq!(
+        payload::validate(&value).unwrap(),
+        vec![
+            "msg: cw721 ExecuteMsg",
+            "msg.send_nft.msg: IbcOutgoingMsg",
+            "msg.send_nft.msg.memo: Ics721Memo",
+            "msg.send_nft.msg.memo.callbacks.ack_callback_data: CallbackData",
+        ]
+    );
+
+    // case 2: messages of a tx are validated
+    let tx = json!({"body": {"messages": [{
+        "@type": "/cosmwasm.wasm.v1.MsgExecuteContract",
+        "msg": value,
+    }]}});
+    assert_eq!(payload::validate(&tx).unwrap().len(), 4);
+
+    // case 3: ibc outgoing msg not base64 encoded
+    let value = send_nft(to_json_binary(&ibc_outgoing_msg.to_string()).unwrap());
+    let err = payload::validate(&value).unwrap_err();
+    assert_eq!(err.to_string(), "msg.send_nft.msg: invalid IbcOutgoingMsg");
+
+    // case 4: memo is plain JSON instead of base64
+    let mut invalid_memo = ibc_outgoing_msg.clone();
+    invalid_memo["memo"] = serde_json::to_string(&memo).unwrap().into();
+    let value = send_nft(to_json_binary(&invalid_memo).unwrap());
+    let err = payload::validate(&value).unwrap_err();
+    assert_eq!(err.to_string(), "msg.send_nft.msg.memo: memo is not base64");
+
+    // case 5: unknown message
+    let err = payload::validate(&json!({"transfer_nft": {}})).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"msg: unknown message {"transfer_nft":{}}"#
+    );
+}
+
+#[test]
+fn test_build_and_validate_instantiate() {
+    let mut profile_env = ENV.to_string();
+    profile_env.push_str("export WALLET_ARKITE_PASSPORT=\"osmo1wallet\"\n");
+    profile_env.push_str("export CODE_ID_ICS721=\"9107\" # ics721_base\n");
+    let dir = profile_dir("instantiate");
+    std::fs::write(dir.join("test.env"), profile_env).unwrap();
+    let profile = ChainProfile::load(&dir, "test").unwrap();
+
+    let msg = msgs::passport_instantiate_msg(&profile).unwrap();
+    let value = serde_json::to_value(msg).unwrap();
+    assert_eq!(
+        payload::validate(&value).unwrap(),
+        vec![
+            "msg: passport InstantiateMsg",
+            "msg.cw721_base.msg: cw721 InstantiateMsg",
+            "msg.cw721_poap.msg: cw721 InstantiateMsg",
+            "msg.ics721_base.msg: ics721 InstantiateMsg",
+        ]
+    );
+    let decoded = payload::decode(value);
+    assert_eq!(decoded["cw721_poap"]["msg"]["symbol"], "poap");
+    assert_eq!(decoded["ics721_base"]["msg"]["cw721_base_code_id"], 8998);
+}
+
+const MANIFEST: &str = include_str!("../../../scripts/deployment.toml");
+
+#[test]
+fn test_manifest() {
+    // case 1: manifest in scripts folder
+    let manifest = Manifest::parse(MANIFEST).unwrap();
+    assert_eq!(
+        manifest.chains.keys().collect::<Vec<_>>(),
+        vec!["osmosis", "stargaze"]
+    );
+    let osmosis = &manifest.chains["osmosis"];
+    assert_eq!(osmosis.counterparty, Some("stargaze".to_string()));
+    assert_eq!(osmosis.incoming_channels(), vec!["channel-8134"]);
+    assert!(osmosis
+        .profile_vars()
+        .contains(&("CODE_ID_ARKITE_PASSPORT", "9125".to_string())));
+
+    // case 2: incoming channels default to channel of chain
+    let manifest =
+        Manifest::parse(&MANIFEST.replace("incoming_channels = [\"channel-977\"]", "")).unwrap();
+    assert_eq!(
+        manifest.chains["stargaze"].incoming_channels(),
+        vec!["channel-977"]
+    );
+
+    // case 3: unknown counterparty
+    let err =
+        Manifest::parse(&MANIFEST.replace("counterparty = \"osmosis\"", "counterparty = \"juno\""))
+            .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "counterparty juno of stargaze is not defined"
+    );
+
+    // case 4: unknown field
+    assert!(Manifest::parse(&MANIFEST.replace("outgoing_rate_limit", "rate_limit")).is_err());
+}
+
+/// Deployed state, matching manifest.
+fn deployed_state(manifest: &Manifest, chain: &str) -> ChainState {
+    let desired = &manifest.chains[chain];
+    let contract = |name: &str, code_id: u64| ContractState {
+        addr: format!("{chain}1{name}"),
+        code_id,
+    };
+    let counterparty = desired
+        .counterparty
+        .as_ref()
+        .map(|counterparty| format!("{counterparty}1passport"));
+    ChainState {
+        passport: Some(contract("passport", desired.code_ids.arkite_passport)),
+        passport_config: Some(PassportConfig {
+            default_token_uri: desired.passport.default_token_uri.clone(),
+            escrowed_token_uri: desired.passport.escrowed_token_uri.clone(),
+            transferred_token_uri: desired.passport.transferred_token_uri.clone(),
+            cw721: Some(format!("{chain}1cw721")),
+            poap: Some(format!("{chain}1poap")),
+            ics721: Some(format!("{chain}1ics721")),
+            counterparty_contract: counterparty,
+        }),
+        cw721: Some(contract("cw721", desired.code_ids.cw721)),
+        poap: Some(contract("poap", desired.code_ids.cw721)),
+        ics721: Some(contract("ics721", desired.code_ids.ics721)),
+        ics721_cw721_code_id: Some(desired.code_ids.cw721),
+        incoming_proxy: Some(contract("incoming", desired.code_ids.incoming_proxy)),
+        outgoing_proxy: Some(contract("outgoing", desired.code_ids.outgoing_proxy)),
+        incoming_channels: desired.incoming_channels(),
+        outgoing_rate_limit: Some(desired.proxies.outgoing_rate_limit),
+    }
+}
+
+fn actions(steps: &[Step]) -> Vec<(&str, Action)> {
+    steps
+        .iter()
+        .map(|step| (step.chain.as_str(), step.action.clone()))
+        .collect()
+}
+
+#[test]
+fn test_plan() {
+    let manifest = Manifest::parse(MANIFEST).unwrap();
+    let deployed: BTreeMap<String, ChainState> = ["osmosis", "stargaze"]
+        .into_iter()
+        .map(|chain| (chain.to_string(), deployed_state(&manifest, chain)))
+        .collect();
+    let register_counterparty = |counterparty: &str| Action::RegisterCounterparty {
+        counterparty: counterparty.to_string(),
+    };
+
+    // case 1: nothing deployed, contracts on all chains are set up first, then counterparties
+    let steps = planner::plan(&manifest, &BTreeMap::new());
+    assert_eq!(
+        actions(&steps),
+        vec![
+            ("osmosis", Action::InstantiatePassport),
+            ("osmosis", Action::InstantiateIncomingProxy),
+            ("osmosis", Action::InstantiateOutgoingProxy),
+            ("osmosis", Action::MigrateIcs721),
+            ("stargaze", Action::InstantiatePassport),
+            ("stargaze", Action::InstantiateIncomingProxy),
+            ("stargaze", Action::InstantiateOutgoingProxy),
+            ("stargaze", Action::MigrateIcs721),
+            ("osmosis", register_counterparty("stargaze")),
+            ("stargaze", register_counterparty("osmosis")),
+        ]
+    );
+
+    // case 2: up to date
+    assert_eq!(planner::plan(&manifest, &deployed), vec![]);
+
+    // case 3: new passport code id and token uri
+    let mut desired = manifest.clone();
+    let osmosis = desired.chains.get_mut("osmosis").unwrap();
+    osmosis.code_ids.arkite_passport = 9200;
+    osmosis.passport.escrowed_token_uri = "ipfs://passport/escrowed".to_string();
+    let steps = planner::plan(&desired, &deployed);
+    assert_eq!(actions(&steps), vec![("osmosis", Action::MigratePassport)]);
+    assert_eq!(
+        steps[0].to_string(),
+        format!(
+            "osmosis: migrate passport (code id 9125 -> 9200, escrowed token uri {} -> ipfs://passport/escrowed)",
+            manifest.chains["osmosis"].passport.escrowed_token_uri
+        )
+    );
+
+    // case 4: new token uri only, config is updated without migration
+    let mut desired = manifest.clone();
+    desired
+        .chains
+        .get_mut("osmosis")
+        .unwrap()
+        .passport
+        .default_token_uri = "ipfs://passport/default".to_string();
+    let steps = planner::plan(&desired, &deployed);
+    assert_eq!(
+        actions(&steps),
+        vec![("osmosis", Action::UpdatePassportConfig)]
+    );
+    assert_eq!(
+        steps[0].to_string(),
+        format!(
+            "osmosis: update passport config (default token uri {} -> ipfs://passport/default)",
+            manifest.chains["osmosis"].passport.default_token_uri
+        )
+    );
+
+    // case 5: proxy config drift, proxies with desired config are instantiated
+    let mut desired = manifest.clone();
+    let stargaze = desired.chains.get_mut("stargaze").unwrap();
+    stargaze.proxies.incoming_channels = vec!["channel-977".to_string(), "channel-1".to_string()];
+    stargaze.proxies.outgoing_rate_limit = 5;
+    let steps = planner::plan(&desired, &deployed);
+    assert_eq!(
+        actions(&steps),
+        vec![
+            ("stargaze", Action::InstantiateIncomingProxy),
+            ("stargaze", Action::InstantiateOutgoingProxy),
+            ("stargaze", Action::MigrateIcs721),
+        ]
+    );
+    assert_eq!(
+        steps[0].reason,
+        "incoming channels channel-977 -> channel-1,channel-977"
+    );
+    assert_eq!(steps[1].reason, "outgoing rate limit 1 -> 5");
+    // order of channels doesn't matter, rate limit not per block is drift
+    let mut states = deployed.clone();
+    let stargaze = states.get_mut("stargaze").unwrap();
+    stargaze.incoming_channels = vec!["channel-1".to_string(), "channel-977".to_string()];
+    stargaze.outgoing_rate_limit = None;
+    let steps = planner::plan(&desired, &states);
+    assert_eq!(
+        actions(&steps),
+        vec![
+            ("stargaze", Action::InstantiateOutgoingProxy),
+            ("stargaze", Action::MigrateIcs721),
+        ]
+    );
+    assert_eq!(steps[0].reason, "outgoing rate limit none -> 5");
+
+    // case 6: new outgoing proxy code id, ics721 is migrated with new proxy
+    let mut desired = manifest.clone();
+    desired
+        .chains
+        .get_mut("stargaze")
+        .unwrap()
+        .code_ids
+        .outgoing_proxy = 4300;
+    let steps = planner::plan(&desired, &deployed);
+    assert_eq!(
+        actions(&steps),
+        vec![
+            ("stargaze", Action::InstantiateOutgoingProxy),
+            ("stargaze", Action::MigrateIcs721),
+        ]
+    );
+    assert_eq!(
+        steps[1].to_string(),
+        "stargaze: migrate ics721 (new outgoing proxy)"
+    );
+
+    // case 7: passport redeployed on stargaze, counterparty on osmosis is outdated
+    let mut states = deployed.clone();
+    states.get_mut("stargaze").unwrap().passport = Some(ContractState {
+        addr: "stargaze1passport2".to_string(),
+        code_id: 4264,
+    });
+    let steps = planner::plan(&manifest, &states);
+    assert_eq!(
+        actions(&steps),
+        vec![("osmosis", register_counterparty("stargaze"))]
+    );
+    assert_eq!(
+        steps[0].reason,
+        "counterparty stargaze1passport -> stargaze1passport2"
+    );
+
+    // case 8: new cw721 code id, passport and poap collection are migrated, and ics721 for voucher collections
+    let mut desired = manifest.clone();
+    desired.chains.get_mut("osmosis").unwrap().code_ids.cw721 = 9000;
+    let steps = planner::plan(&desired, &deployed);
+    assert_eq!(
+        actions(&steps),
+        vec![
+            (
+                "osmosis",
+                Action::MigrateCw721 {
+                    cw721: "osmosis1cw721".to_string()
+                }
+            ),
+            (
+                "osmosis",
+                Action::MigrateCw721 {
+                    cw721: "osmosis1poap".to_string()
+                }
+            ),
+            ("osmosis", Action::MigrateIcs721),
+        ]
+    );
+    assert_eq!(steps[0].reason, "passport code id 8998 -> 9000");
+    assert_eq!(steps[2].reason, "cw721 code id 8998 -> 9000");
+}
+
+#[test]
+fn test_query_passport_config() {
+    // case 1: passport with contract info query
+    let info = json!({
+        "contract_version": {"contract": "crates.io:arkite-passport", "version": "0.4.0"},
+        "ownership": {"owner": "osmo1wallet", "pending_owner": null, "pending_expiry": null},
+        "config": {
+            "default_token_uri": "ipfs://passport/default",
+            "escrowed_token_uri": "ipfs://passport/escrowed",
+            "transferred_token_uri": "ipfs://passport/transferred",
+            "addr_cw721": "osmo1cw721",
+            "addr_poap": "osmo1poap",
+            "addr_ics721": "osmo1ics721",
+            "counterparty_contract": "stars1passport",
+            "poap_policy": "always",
+            "timeout_policy": {"min_seconds": null, "max_seconds": null},
+        },
+        "linked_contracts": {
+            "cw721": "osmo1cw721",
+            "poap": "osmo1poap",
+            "ics721": "osmo1ics721",
+            "ics721_outgoing_proxy": null,
+            "ics721_incoming_proxy": null,
+            "counterparty_contract": "stars1passport",
+        },
+        "counters": {
+            "passports_minted": 0,
+            "poaps_minted": 0,
+            "transfers_acked": 0,
+            "transfers_failed": 0,
+        },
+    });
+    let expected = PassportConfig {
+        default_token_uri: "ipfs://passport/default".to_string(),
+        escrowed_token_uri: "ipfs://passport/escrowed".to_string(),
+        transferred_token_uri: "ipfs://passport/transferred".to_string(),
+        cw721: Some("osmo1cw721".to_string()),
+        poap: Some("osmo1poap".to_string()),
+        ics721: Some("osmo1ics721".to_string()),
+        counterparty_contract: Some("stars1passport".to_string()),
+    };
+    let config = planner::query_passport_config(|msg| match msg {
+        QueryMsg::ContractInfo {} => Ok(info.clone()),
+        _ => panic!("unexpected query {msg:?}"),
+    })
+    .unwrap();
+    assert_eq!(config, expected);
+
+    // case 2: 0.1.0 passport without contract info query, and without counterparty
+    let config = planner::query_passport_config(|msg| match msg {
+        QueryMsg::DefaultTokenUri {} => Ok(json!("ipfs://passport/default")),
+        QueryMsg::EscrowedTokenUri {} => Ok(json!("ipfs://passport/escrowed")),
+        QueryMsg::TransferredTokenUri {} => Ok(json!("ipfs://passport/transferred")),
+        QueryMsg::CW721 {} => Ok(json!("osmo1cw721")),
+        QueryMsg::Poap {} => Ok(json!("osmo1poap")),
+        QueryMsg::ICS721 {} => Ok(json!("osmo1ics721")),
+        _ => Err(anyhow!("unknown variant")),
+    })
+    .unwrap();
+    assert_eq!(
+        config,
+        PassportConfig {
+            counterparty_contract: None,
+            ..expected
+        }
+    );
+}
+
+#[test]
+fn test_load_profiles() {
+    let dir = profile_dir("load_profiles");
+    // env file defining all variables of manifest
+    let env = format!(
+        "{ENV}export CHANNEL_ID=\"\"\nexport CODE_ID_ARKITE_PASSPORT=\"\"\nexport CODE_ID_ICS721=\"\"\n\
+         export CODE_ID_INCOMING_PROXY=\"\"\nexport CODE_ID_OUTGOING_PROXY=\"\"\n"
+    );
+    std::fs::write(dir.join("test.env"), &env).unwrap();
+    let mut manifest = Manifest::parse(MANIFEST).unwrap();
+    let mut desired = manifest.chains.remove("osmosis").unwrap();
+    desired.counterparty = None;
+    manifest.chains = BTreeMap::from([("test".to_string(), desired.clone())]);
+
+    // case 1: print and generate only have no side effects, manifest values are applied in memory only
+    for mode in [Mode::Print, Mode::GenerateOnly] {
+        let profiles = deploy::load_profiles(&dir, &manifest, mode).unwrap();
+        assert_eq!(
+            profiles["test"].get("DEFAULT_TOKEN_URI").unwrap(),
+            desired.passport.default_token_uri
+        );
+        assert_eq!(std::fs::read_to_string(dir.join("test.env")).unwrap(), env);
+    }
+
+    // case 2: broadcast writes manifest values to env file
+    deploy::load_profiles(&dir, &manifest, Mode::Broadcast).unwrap();
+    let reloaded = ChainProfile::load(&dir, "test").unwrap();
+    assert_eq!(
+        reloaded.get("DEFAULT_TOKEN_URI").unwrap(),
+        desired.passport.default_token_uri
+    );
+    assert_eq!(reloaded.get("CHANNEL_ID").unwrap(), desired.channel_id);
+}
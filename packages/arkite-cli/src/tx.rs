@@ -0,0 +1,281 @@
+//! Wasm txs and queries, executed by the chain binary of a chain profile (e.g. `osmosisd` or `starsd`).
+use std::{path::PathBuf, process::Command, thread, time::Duration};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::profile::ChainProfile;
+
+/// number of attempts waiting for a tx to be included in a block
+const WAIT_FOR_TX_ATTEMPTS: u32 = 10;
+const WAIT_FOR_TX_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    /// sign with wallet of chain profile and broadcast
+    Broadcast,
+    /// print unsigned tx, for offline signing
+    GenerateOnly,
+    /// print command only, without executing it
+    Print,
+}
+
+pub enum WasmTx {
+    Store {
+        wasm: PathBuf,
+    },
+    Instantiate {
+        code_id: u64,
+        msg: String,
+        label: String,
+    },
+    Execute {
+        contract: String,
+        msg: String,
+    },
+    Migrate {
+        contract: String,
+        code_id: u64,
+        msg: String,
+    },
+}
+
+impl WasmTx {
+    pub fn instantiate(code_id: u64, msg: &impl Serialize, label: &str) -> Result<Self> {
+        Ok(Self::Instantiate {
+            code_id,
+            msg: serde_json::to_string(msg)?,
+            label: label.to_string(),
+        })
+    }
+
+    pub fn execute(contract: &str, msg: &impl Serialize) -> Result<Self> {
+        Ok(Self::Execute {
+            contract: contract.to_string(),
+            msg: serde_json::to_string(msg)?,
+        })
+    }
+
+    pub fn migrate(contract: &str, code_id: u64, msg: &impl Serialize) -> Result<Self> {
+        Ok(Self::Migrate {
+            contract: contract.to_string(),
+            code_id,
+            msg: serde_json::to_string(msg)?,
+        })
+    }
+
+    fn args(&self, profile: &ChainProfile, mode: Mode) -> Result<Vec<String>> {
+        let wallet = profile.get("WALLET_ARKITE_PASSPORT")?.to_string();
+        let mut args: Vec<String> = vec!["tx".into(), "wasm".into()];
+        match self {
+            WasmTx::Store { wasm } => {
+                args.extend(["store".into(), wasm.display().to_string()]);
+            }
+            WasmTx::Instantiate {
+                code_id,
+                msg,
+                label,
+            } => {
+                args.extend([
+                    "instantiate".into(),
+                    code_id.to_string(),
+                    msg.clone(),
+                    "--label".into(),
+                    label.clone(),
+                    "--admin".into(),
+                    wallet.clone(),
+                ]);
+            }
+            WasmTx::Execute { contract, msg } => {
+                args.extend(["execute".into(), contract.clone(), msg.clone()]);
+            }
+            WasmTx::Migrate {
+                contract,
+                code_id,
+                msg,
+            } => {
+                args.extend([
+                    "migrate".into(),
+                    contract.clone(),
+                    code_id.to_string(),
+                    msg.clone(),
+                ]);
+            }
+        }
+        args.extend([
+            "--from".into(),
+            wallet,
+            "--chain-id".into(),
+            profile.get("CHAIN_ID")?.to_string(),
+            "--node".into(),
+            profile.get("CHAIN_NODE")?.to_string(),
+            "--gas-prices".into(),
+            profile.get("CLI_GAS_PRICES")?.to_string(),
+            "--output".into(),
+            "json".into(),
+        ]);
+        match mode {
+            // gas can't be simulated offline
+            Mode::GenerateOnly => args.extend([
+                "--gas".into(),
+                profile.get("CLI_MAX_GAS")?.to_string(),
+                "--generate-only".into(),
+            ]),
+            Mode::Broadcast | Mode::Print => args.extend([
+                "--gas".into(),
+                profile.get("CLI_GAS")?.to_string(),
+                "--gas-adjustment".into(),
+                profile.get("CLI_GAS_ADJUSTMENT")?.to_string(),
+                "-b".into(),
+                profile.get("CLI_BROADCAST_MODE")?.to_string(),
+                "--yes".into(),
+            ]),
+        }
+        Ok(args)
+    }
+}
+
+/// Chain binary of a chain profile.
+pub struct ChainCli<'a> {
+    pub profile: &'a ChainProfile,
+    pub mode: Mode,
+}
+
+impl<'a> ChainCli<'a> {
+    pub fn new(profile: &'a ChainProfile, mode: Mode) -> Self {
+        Self { profile, mode }
+    }
+
+    /// Runs tx and returns its hash once broadcasted. Returns `None` in case tx is printed only.
+    pub fn tx(&self, tx: &WasmTx) -> Result<Option<String>> {
+        let cli = self.profile.get("CLI")?;
+        let args = tx.args(self.profile, self.mode)?;
+        let command = shell_command(cli, &args);
+        match self.mode {
+            Mode::Print => {
+                println!("{command}");
+                Ok(None)
+            }
+            Mode::GenerateOnly => {
+                eprintln!("executing cmd: {command}");
+                println!("{}", run(cli, &args)?);
+                Ok(None)
+            }
+            Mode::Broadcast => {
+                eprintln!("executing cmd: {command}");
+                let output: Value = serde_json::from_str(&run(cli, &args)?)?;
+                if output["code"].as_u64().unwrap_or_default() != 0 {
+                    bail!("tx failed: {}", output["raw_log"]);
+                }
+                let tx_hash = output["txhash"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("no tx hash in output: {output}"))?;
+                eprintln!("TX_HASH: {tx_hash}");
+                Ok(Some(tx_hash.to_string()))
+            }
+        }
+    }
+
+    /// Waits until tx is included in a block and returns it.
+    pub fn wait_for_tx(&self, tx_hash: &str) -> Result<Value> {
+        let cli = self.profile.get("CLI")?;
+        let args = self.query_args(["tx", tx_hash])?;
+        for _ in 0..WAIT_FOR_TX_ATTEMPTS {
+            thread::sleep(WAIT_FOR_TX_INTERVAL);
+            if let Ok(output) = run(cli, &args) {
+                let tx: Value = serde_json::from_str(&output)?;
+                if tx["code"].as_u64().unwrap_or_default() != 0 {
+                    bail!("tx {tx_hash} failed: {}", tx["raw_log"]);
+                }
+                return Ok(tx);
+            }
+        }
+        bail!("tx {tx_hash} not found")
+    }
+
+    /// Smart query, returning `data` of query response.
+    pub fn query_smart(&self, contract: &str, msg: &impl Serialize) -> Result<Value> {
+        let cli = self.profile.get("CLI")?;
+        let msg = serde_json::to_string(msg)?;
+        let args = self.query_args(["wasm", "contract-state", "smart", contract, &msg])?;
+        let output: Value = serde_json::from_str(&run(cli, &args)?)?;
+        Ok(output["data"].clone())
+    }
+
+    pub fn query_code_id(&self, contract: &str) -> Result<u64> {
+        let cli = self.profile.get("CLI")?;
+        let args = self.query_args(["wasm", "contract", contract])?;
+        let output: Value = serde_json::from_str(&run(cli, &args)?)?;
+        let code_id = &output["contract_info"]["code_id"];
+        // code id is a string in json output
+        code_id
+            .as_str()
+            .and_then(|code_id| code_id.parse().ok())
+            .or_else(|| code_id.as_u64())
+            .ok_or_else(|| anyhow!("no code id for {contract}: {output}"))
+    }
+
+    fn query_args<const N: usize>(&self, query: [&str; N]) -> Result<Vec<String>> {
+        let mut args = vec!["query".to_string()];
+        args.extend(query.iter().map(|arg| arg.to_string()));
+        args.extend([
+            "--chain-id".into(),
+            self.profile.get("CHAIN_ID")?.to_string(),
+            "--node".into(),
+            self.profile.get("CHAIN_NODE")?.to_string(),
+            "--output".into(),
+            "json".into(),
+        ]);
+        Ok(args)
+    }
+}
+
+fn run(cli: &str, args: &[String]) -> Result<String> {
+    let output = Command::new(cli)
+        .args(args)
+        .output()
+        .with_context(|| format!("failed running {cli}"))?;
+    if !output.status.success() {
+        bail!(
+            "{cli} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Command as it can be pasted into a shell.
+pub(crate) fn shell_command(cli: &str, args: &[String]) -> String {
+    let mut command = cli.to_string();
+    for arg in args {
+        command.push(' ');
+        if !arg.is_empty()
+            && arg
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_./:@=,".contains(c))
+        {
+            command.push_str(arg);
+        } else {
+            command.push_str(&format!("'{}'", arg.replace('\'', r"'\''")));
+        }
+    }
+    command
+}
+
+/// First attribute with given key in events of given type, in both `events` and `logs` of a tx.
+pub fn find_attribute(tx: &Value, event_type: &str, key: &str) -> Option<String> {
+    let events = tx["events"].as_array().into_iter().flatten().chain(
+        tx["logs"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .flat_map(|log| log["events"].as_array().into_iter().flatten()),
+    );
+    events
+        .filter(|event| event["type"] == event_type)
+        .flat_map(|event| event["attributes"].as_array().into_iter().flatten())
+        .find(|attribute| attribute["key"] == key)
+        .and_then(|attribute| attribute["value"].as_str())
+        .map(|value| value.trim_matches('"').to_string())
+}
@@ -0,0 +1,166 @@
+//! Glue for contracts using ics721 callbacks. On incoming callbacks, the sender is checked to be ics721 and callback data
+//! is decoded, before dispatching to an [`Ics721CallbackHandler`]. Acks are only dispatched for packets sent by the
+//! contract itself. For outgoing transfers, memos with callbacks are
+//! created and the contract NFTs are sent to (outgoing proxy or ics721) is looked up.
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, QuerierWrapper,
+    Response, StdError, StdResult,
+};
+use ics721_types::{
+    ibc_types::IbcOutgoingMsg,
+    types::{
+        Ics721AckCallbackMsg, Ics721Callbacks, Ics721Memo, Ics721ReceiveCallbackMsg, Ics721Status,
+    },
+};
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+
+#[cfg(feature = "testing")]
+pub mod multi_test;
+#[cfg(test)]
+mod testing;
+
+/// Error of a failed ack callback, reported by ics721 in case a packet timed out.
+pub const TIMEOUT_ERROR: &str = "timeout";
+
+#[derive(Error, Debug, PartialEq)]
+pub enum UnauthorizedCallback {
+    #[error("Unauthorized callback. Only ICS721 can call back.")]
+    Sender,
+
+    /// Anyone may send NFTs via ics721 with a memo calling back the contract, but callback data of such an ack is forged.
+    #[error("Unauthorized ack callback. Packet has been sent by {sender}, not by this contract.")]
+    PacketSender { sender: String },
+}
+
+/// Callbacks of a contract sending and receiving NFTs via ics721.
+pub trait Ics721CallbackHandler {
+    /// Data sent along an outgoing transfer, passed to ack callback on source chain and to receive callback on target chain.
+    type CallbackData: Serialize + DeserializeOwned;
+    type Error: From<StdError> + From<UnauthorizedCallback>;
+
+    /// ics721 contract, the only sender allowed to call back.
+    fn ics721(&self, deps: Deps) -> StdResult<Addr>;
+
+    /// NFT is received on target chain. An error reverts the transfer, and the NFT is returned on source chain.
+    fn on_receive(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        msg: Ics721ReceiveCallbackMsg,
+        data: Self::CallbackData,
+    ) -> Result<Response, Self::Error>;
+
+    /// NFT is received on target chain, and escrowed (or burned on back transfer) by ics721 on source chain.
+    fn on_ack_success(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        msg: Ics721AckCallbackMsg,
+        data: Self::CallbackData,
+    ) -> Result<Response, Self::Error>;
+
+    /// Transfer failed on target chain, and the NFT is returned to the packet sender on source chain.
+    fn on_ack_failure(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        msg: Ics721AckCallbackMsg,
+        data: Self::CallbackData,
+        error: String,
+    ) -> Result<Response, Self::Error>;
+
+    /// Packet timed out. NFT is returned the same way as on failure, so by default this is handled as a failed ack.
+    fn on_timeout(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        msg: Ics721AckCallbackMsg,
+        data: Self::CallbackData,
+    ) -> Result<Response, Self::Error> {
+        self.on_ack_failure(deps, env, msg, data, TIMEOUT_ERROR.to_string())
+    }
+}
+
+/// Checks sender and decodes callback data, before dispatching to `on_receive`.
+pub fn handle_receive_callback<H: Ics721CallbackHandler>(
+    handler: &H,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Ics721ReceiveCallbackMsg,
+) -> Result<Response, H::Error> {
+    assert_ics721(handler, deps.as_ref(), &info)?;
+    let data = from_json(&msg.msg)?;
+    handler.on_receive(deps, env, msg, data)
+}
+
+/// Checks sender and packet sender, and decodes callback data, before dispatching to `on_ack_success`, `on_ack_failure`
+/// or `on_timeout`.
+pub fn handle_ack_callback<H: Ics721CallbackHandler>(
+    handler: &H,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Ics721AckCallbackMsg,
+) -> Result<Response, H::Error> {
+    assert_ics721(handler, deps.as_ref(), &info)?;
+    // memo is set by packet sender, so callback data is only trusted for packets sent by this contract
+    if msg.original_packet.sender != env.contract.address.as_str() {
+        return Err(UnauthorizedCallback::PacketSender {
+            sender: msg.original_packet.sender,
+        }
+        .into());
+    }
+    let data = from_json(&msg.msg)?;
+    match msg.status.clone() {
+        Ics721Status::Success => handler.on_ack_success(deps, env, msg, data),
+        Ics721Status::Failed(error) if error == TIMEOUT_ERROR => {
+            handler.on_timeout(deps, env, msg, data)
+        }
+        Ics721Status::Failed(error) => handler.on_ack_failure(deps, env, msg, data, error),
+    }
+}
+
+fn assert_ics721<H: Ics721CallbackHandler>(
+    handler: &H,
+    deps: Deps,
+    info: &MessageInfo,
+) -> Result<(), H::Error> {
+    if info.sender != handler.ics721(deps)? {
+        return Err(UnauthorizedCallback::Sender.into());
+    }
+    Ok(())
+}
+
+/// Memo with ack callback to `ack_callback_addr` on source chain, and receive callback to `receive_callback_addr` on
+/// target chain - if given. Both callbacks get the same data.
+pub fn callback_memo<T: Serialize>(
+    data: &T,
+    ack_callback_addr: String,
+    receive_callback_addr: Option<String>,
+) -> StdResult<Ics721Memo> {
+    let data = to_json_binary(data)?;
+    let receive_callback_data = receive_callback_addr.as_ref().map(|_| data.clone());
+    Ok(Ics721Memo {
+        callbacks: Some(Ics721Callbacks {
+            ack_callback_data: Some(data),
+            ack_callback_addr: Some(ack_callback_addr),
+            receive_callback_data,
+            receive_callback_addr,
+        }),
+    })
+}
+
+/// Sets memo of an outgoing transfer, ics721 expects it as base64 encoded json.
+pub fn set_memo(ibc_msg: &mut IbcOutgoingMsg, memo: &Ics721Memo) -> StdResult<()> {
+    ibc_msg.memo = Some(Binary::to_base64(&to_json_binary(memo)?));
+    Ok(())
+}
+
+/// Contract NFTs are sent to for an outgoing transfer: outgoing proxy of ics721 if there is one, otherwise ics721.
+pub fn outgoing_proxy_or_ics721(querier: &QuerierWrapper, ics721: Addr) -> StdResult<Addr> {
+    let outgoing_proxy: Option<Addr> =
+        querier.query_wasm_smart(ics721.clone(), &ics721::msg::QueryMsg::OutgoingProxy {})?;
+    Ok(outgoing_proxy.unwrap_or(ics721))
+}
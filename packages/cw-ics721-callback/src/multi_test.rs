@@ -0,0 +1,93 @@
+//! Shared cw-multi-test setup for contracts using this crate: bech32 app with wasmd-like contract addresses.
+use anyhow::Result;
+use cosmwasm_std::{
+    instantiate2_address, Addr, Api, CanonicalAddr, Empty, GovMsg, MemoryStorage, Storage,
+};
+use cw_multi_test::{
+    addons::MockApiBech32, AddressGenerator, App, AppBuilder, BankKeeper, DistributionKeeper,
+    FailingModule, IbcAcceptingModule, Router, StakeKeeper, StargateFailing, WasmKeeper,
+};
+use sha2::{digest::Update, Digest, Sha256};
+
+pub type MockRouter = Router<
+    BankKeeper,
+    FailingModule<Empty, Empty, Empty>,
+    WasmKeeper<Empty, Empty>,
+    StakeKeeper,
+    DistributionKeeper,
+    IbcAcceptingModule,
+    FailingModule<GovMsg, Empty, Empty>,
+    StargateFailing,
+>;
+
+pub type MockApp = App<
+    BankKeeper,
+    MockApiBech32,
+    MemoryStorage,
+    FailingModule<Empty, Empty, Empty>,
+    WasmKeeper<Empty, Empty>,
+    StakeKeeper,
+    DistributionKeeper,
+    IbcAcceptingModule,
+>;
+
+#[derive(Default)]
+pub struct MockAddressGenerator;
+
+impl AddressGenerator for MockAddressGenerator {
+    fn contract_address(
+        &self,
+        api: &dyn Api,
+        _storage: &mut dyn Storage,
+        code_id: u64,
+        instance_id: u64,
+    ) -> Result<Addr> {
+        let canonical_addr = Self::instantiate_address(code_id, instance_id);
+        Ok(Addr::unchecked(api.addr_humanize(&canonical_addr)?))
+    }
+
+    fn predictable_contract_address(
+        &self,
+        api: &dyn Api,
+        _storage: &mut dyn Storage,
+        _code_id: u64,
+        _instance_id: u64,
+        checksum: &[u8],
+        creator: &CanonicalAddr,
+        salt: &[u8],
+    ) -> Result<Addr> {
+        let canonical_addr = instantiate2_address(checksum, creator, salt)?;
+        Ok(Addr::unchecked(api.addr_humanize(&canonical_addr)?))
+    }
+}
+
+impl MockAddressGenerator {
+    // non-predictable contract address generator, see `BuildContractAddressClassic`
+    // implementation in wasmd: https://github.com/CosmWasm/wasmd/blob/main/x/wasm/keeper/addresses.go#L35-L42
+    fn instantiate_address(code_id: u64, instance_id: u64) -> CanonicalAddr {
+        let mut key = Vec::<u8>::new();
+        key.extend_from_slice(b"wasm\0");
+        key.extend_from_slice(&code_id.to_be_bytes());
+        key.extend_from_slice(&instance_id.to_be_bytes());
+        let module = Sha256::digest("module".as_bytes());
+        Sha256::new()
+            .chain(module)
+            .chain(key)
+            .finalize()
+            .to_vec()
+            .into()
+    }
+}
+
+pub fn no_init(_router: &mut MockRouter, _api: &dyn Api, _storage: &mut dyn Storage) {}
+
+/// App with bech32 addresses for given prefix, supporting instantiate2.
+pub fn mock_app(bech32_prefix: &'static str) -> MockApp {
+    AppBuilder::new()
+        .with_wasm::<WasmKeeper<Empty, Empty>>(
+            WasmKeeper::new().with_address_generator(MockAddressGenerator),
+        )
+        .with_ibc(IbcAcceptingModule::default())
+        .with_api(MockApiBech32::new(bech32_prefix))
+        .build(no_init)
+}
@@ -0,0 +1,311 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    from_json,
+    testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR},
+    to_json_binary, Addr, Binary, ContractResult, Deps, DepsMut, Env, IbcTimeout, Response,
+    StdError, StdResult, SystemResult, WasmQuery,
+};
+use ics721::{ClassId, NonFungibleTokenPacketData, TokenId};
+use ics721_types::{
+    ibc_types::IbcOutgoingMsg,
+    types::{Ics721AckCallbackMsg, Ics721Memo, Ics721ReceiveCallbackMsg, Ics721Status},
+};
+use thiserror::Error;
+
+use crate::{
+    callback_memo, handle_ack_callback, handle_receive_callback, outgoing_proxy_or_ics721,
+    set_memo, Ics721CallbackHandler, UnauthorizedCallback, TIMEOUT_ERROR,
+};
+
+const ICS721: &str = "ics721";
+
+#[cw_serde]
+struct TestData {
+    token_id: String,
+}
+
+#[derive(Error, Debug, PartialEq)]
+enum TestError {
+    #[error(transparent)]
+    Std(#[from] StdError),
+
+    #[error(transparent)]
+    Unauthorized(#[from] UnauthorizedCallback),
+}
+
+/// Responds with called handler, and error in case of failure.
+struct TestHandler;
+
+impl Ics721CallbackHandler for TestHandler {
+    type CallbackData = TestData;
+    type Error = TestError;
+
+    fn ics721(&self, _deps: Deps) -> StdResult<Addr> {
+        Ok(Addr::unchecked(ICS721))
+    }
+
+    fn on_receive(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _msg: Ics721ReceiveCallbackMsg,
+        data: TestData,
+    ) -> Result<Response, TestError> {
+        Ok(Response::default()
+            .add_attribute("handler", "on_receive")
+            .add_attribute("token_id", data.token_id))
+    }
+
+    fn on_ack_success(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _msg: Ics721AckCallbackMsg,
+        data: TestData,
+    ) -> Result<Response, TestError> {
+        Ok(Response::default()
+            .add_attribute("handler", "on_ack_success")
+            .add_attribute("token_id", data.token_id))
+    }
+
+    fn on_ack_failure(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _msg: Ics721AckCallbackMsg,
+        data: TestData,
+        error: String,
+    ) -> Result<Response, TestError> {
+        Ok(Response::default()
+            .add_attribute("handler", "on_ack_failure")
+            .add_attribute("token_id", data.token_id)
+            .add_attribute("error", error))
+    }
+}
+
+fn packet() -> NonFungibleTokenPacketData {
+    NonFungibleTokenPacketData {
+        class_id: ClassId::new("cw721"),
+        class_uri: None,
+        class_data: None,
+        token_ids: vec![TokenId::new("1")],
+        token_uris: None,
+        token_data: None,
+        sender: "sender".to_string(),
+        receiver: "receiver".to_string(),
+        memo: None,
+    }
+}
+
+fn data() -> Binary {
+    to_json_binary(&TestData {
+        token_id: "1".to_string(),
+    })
+    .unwrap()
+}
+
+/// Ack of a packet sent by `packet_sender`, contract itself in case of mock env.
+fn ack_callback_msg(
+    status: Ics721Status,
+    msg: Binary,
+    packet_sender: &str,
+) -> Ics721AckCallbackMsg {
+    Ics721AckCallbackMsg {
+        status,
+        nft_contract: "cw721".to_string(),
+        original_packet: NonFungibleTokenPacketData {
+            sender: packet_sender.to_string(),
+            ..packet()
+        },
+        msg,
+    }
+}
+
+fn receive_callback_msg(msg: Binary) -> Ics721ReceiveCallbackMsg {
+    Ics721ReceiveCallbackMsg {
+        nft_contract: "voucher".to_string(),
+        original_packet: packet(),
+        msg,
+    }
+}
+
+fn attributes(res: Response) -> Vec<(String, String)> {
+    res.attributes
+        .into_iter()
+        .map(|attribute| (attribute.key, attribute.value))
+        .collect()
+}
+
+#[test]
+fn test_handle_receive_callback() {
+    let mut deps = mock_dependencies();
+
+    // case 1: only ics721 can call back
+    let err = handle_receive_callback(
+        &TestHandler,
+        deps.as_mut(),
+        mock_env(),
+        mock_info("other", &[]),
+        receive_callback_msg(data()),
+    )
+    .unwrap_err();
+    assert_eq!(err, TestError::Unauthorized(UnauthorizedCallback::Sender));
+
+    // case 2: invalid callback data
+    let err = handle_receive_callback(
+        &TestHandler,
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ICS721, &[]),
+        receive_callback_msg(Binary::from(b"invalid")),
+    )
+    .unwrap_err();
+    assert!(matches!(err, TestError::Std(StdError::ParseErr { .. })));
+
+    // case 3: decoded data is passed to handler
+    let res = handle_receive_callback(
+        &TestHandler,
+        deps.as_mut(),
+        mock_env(),
+        mock_info(ICS721, &[]),
+        receive_callback_msg(data()),
+    )
+    .unwrap();
+    assert_eq!(
+        attributes(res),
+        vec![
+            ("handler".to_string(), "on_receive".to_string()),
+            ("token_id".to_string(), "1".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_handle_ack_callback() {
+    let mut deps = mock_dependencies();
+
+    // case 1: only ics721 can call back
+    let err = handle_ack_callback(
+        &TestHandler,
+        deps.as_mut(),
+        mock_env(),
+        mock_info("other", &[]),
+        ack_callback_msg(Ics721Status::Success, data(), MOCK_CONTRACT_ADDR),
+    )
+    .unwrap_err();
+    assert_eq!(err, TestError::Unauthorized(UnauthorizedCallback::Sender));
+
+    // case 2: forged memo, packet sent by someone else calling back this contract
+    for status in [
+        Ics721Status::Success,
+        Ics721Status::Failed("invalid receiver".to_string()),
+        Ics721Status::Failed(TIMEOUT_ERROR.to_string()),
+    ] {
+        let err = handle_ack_callback(
+            &TestHandler,
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ICS721, &[]),
+            ack_callback_msg(status, data(), "attacker"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            TestError::Unauthorized(UnauthorizedCallback::PacketSender {
+                sender: "attacker".to_string()
+            })
+        );
+    }
+
+    // case 3: status is dispatched to handler, timeout defaults to failure
+    for (status, expected) in [
+        (Ics721Status::Success, vec![("handler", "on_ack_success")]),
+        (
+            Ics721Status::Failed("invalid receiver".to_string()),
+            vec![("handler", "on_ack_failure"), ("error", "invalid receiver")],
+        ),
+        (
+            Ics721Status::Failed(TIMEOUT_ERROR.to_string()),
+            vec![("handler", "on_ack_failure"), ("error", TIMEOUT_ERROR)],
+        ),
+    ] {
+        let res = handle_ack_callback(
+            &TestHandler,
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ICS721, &[]),
+            ack_callback_msg(status, data(), MOCK_CONTRACT_ADDR),
+        )
+        .unwrap();
+        let attributes = attributes(res);
+        for (key, value) in expected {
+            assert!(attributes.contains(&(key.to_string(), value.to_string())));
+        }
+    }
+}
+
+#[test]
+fn test_callback_memo() {
+    let data = TestData {
+        token_id: "1".to_string(),
+    };
+
+    // case 1: no receive callback without receive callback address
+    let memo = callback_memo(&data, "sender_contract".to_string(), None).unwrap();
+    let callbacks = memo.callbacks.unwrap();
+    assert_eq!(
+        callbacks.ack_callback_data,
+        Some(to_json_binary(&data).unwrap())
+    );
+    assert_eq!(
+        callbacks.ack_callback_addr,
+        Some("sender_contract".to_string())
+    );
+    assert_eq!(callbacks.receive_callback_data, None);
+    assert_eq!(callbacks.receive_callback_addr, None);
+
+    // case 2: receive callback gets same data
+    let memo = callback_memo(
+        &data,
+        "sender_contract".to_string(),
+        Some("counterparty_contract".to_string()),
+    )
+    .unwrap();
+    let callbacks = memo.clone().callbacks.unwrap();
+    assert_eq!(
+        callbacks.receive_callback_data,
+        Some(to_json_binary(&data).unwrap())
+    );
+    assert_eq!(
+        callbacks.receive_callback_addr,
+        Some("counterparty_contract".to_string())
+    );
+
+    // case 3: memo is set as base64 encoded json
+    let mut ibc_msg = IbcOutgoingMsg {
+        receiver: "receiver".to_string(),
+        channel_id: "channel-0".to_string(),
+        timeout: IbcTimeout::with_timestamp(mock_env().block.time),
+        memo: None,
+    };
+    set_memo(&mut ibc_msg, &memo).unwrap();
+    let decoded: Ics721Memo =
+        from_json(Binary::from_base64(&ibc_msg.memo.unwrap()).unwrap()).unwrap();
+    assert_eq!(decoded, memo);
+}
+
+#[test]
+fn test_outgoing_proxy_or_ics721() {
+    for (outgoing_proxy, expected) in [(None, ICS721), (Some("outgoing_proxy"), "outgoing_proxy")] {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == ICS721 => SystemResult::Ok(
+                ContractResult::Ok(to_json_binary(&outgoing_proxy.map(Addr::unchecked)).unwrap()),
+            ),
+            _ => panic!("unexpected query {query:?}"),
+        });
+        let addr =
+            outgoing_proxy_or_ics721(&deps.as_ref().querier, Addr::unchecked(ICS721)).unwrap();
+        assert_eq!(addr, Addr::unchecked(expected));
+    }
+}